@@ -3,7 +3,26 @@ pub const THREAD_NAME: &str = "kallax";
 pub const TRACKER_API_DEFAULT_PORT: u16 = 53972;
 pub const TRACKER_GRPC_DEFAULT_PORT: u16 = 53973;
 pub const TRACKER_DEFAULT_PEER_TIME_TO_LIVE_SECONDS: u64 = 60;
+pub const TRACKER_DEFAULT_MIN_SURVIVING_PEERS_PER_CHAIN: usize = 0;
+pub const TRACKER_DEFAULT_TOMBSTONE_TIME_TO_LIVE_SECONDS: u64 = 300;
+pub const TRACKER_DEFAULT_RATE_LIMIT_PER_SECOND: u32 = 0;
+pub const TRACKER_DEFAULT_RATE_LIMIT_BURST: u32 = 200;
+pub const TRACKER_DEFAULT_MAX_CHAINS_PER_LAYER: usize = 256;
+pub const TRACKER_DEFAULT_MAX_PEERS_PER_CHAIN: usize = 1024;
+pub const TRACKER_DEFAULT_MAX_ADDRESSES_PER_PEER: usize = 16;
+pub const TRACKER_DEFAULT_MAX_COMPONENTS: usize = 1024;
+pub const TRACKER_DEFAULT_PEER_VERIFICATION_TIMEOUT_SECONDS: u64 = 10;
+pub const TRACKER_DEFAULT_PEER_VERIFICATION_CONCURRENCY: usize = 16;
+pub const TRACKER_DEFAULT_CHAIN_STALL_THRESHOLD_SECONDS: u64 = 60;
+pub const TRACKER_DEFAULT_AUDIT_LOG_MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+pub const TRACKER_DEFAULT_AUDIT_LOG_MAX_FILE_AGE_SECONDS: u64 = 24 * 60 * 60;
+pub const TRACKER_DEFAULT_AUDIT_LOG_MAX_FILES: usize = 30;
 
 pub const SIDECAR_DIAGNOSTIC_DEFAULT_PORT: u16 = 53974;
 
+pub const NETWORK_BROKER_DEFAULT_TRACKER_REQUEST_TIMEOUT_SECONDS: u64 = 10;
+pub const NETWORK_BROKER_DEFAULT_TRACKER_MAX_RETRIES: usize = 3;
+pub const NETWORK_BROKER_DEFAULT_TRACKER_RETRY_BACKOFF_MILLISECONDS: u64 = 500;
+
 pub const KALLAX_SESSION_KEY_MNEMONIC_PHRASE_ENV: &str = "KALLAX_SESSION_KEY_MNEMONIC_PHRASE";
+pub const KALLAX_TRACKER_ADMIN_TOKEN_ENV: &str = "KALLAX_TRACKER_ADMIN_TOKEN";
@@ -154,7 +154,7 @@ mod session_key;
 mod sidecar;
 mod tracker;
 
-use std::{fmt, future::Future, io::Write, path::PathBuf};
+use std::{fmt, future::Future, io::Write};
 
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::Shell;
@@ -202,25 +202,19 @@ pub enum Commands {
     #[command(about = "Run Kubernetes sidecar for Substrate-based node")]
     Sidecar {
         #[clap(flatten)]
-        options: sidecar::Options,
+        options: Box<sidecar::Options>,
+    },
+
+    #[command(about = "Run sidecar for bridge relayers and offline workers")]
+    ComponentSidecar {
+        #[clap(flatten)]
+        options: sidecar::ComponentOptions,
     },
 
     #[command(about = "Run network broker for Substrate-based node which is out of Kubernetes")]
     NetworkBroker {
-        #[clap(
-            long = "tracker-api-endpoint",
-            help = "Tracker api endpoint",
-            default_value = network_broker::TRACKER_API_ENDPOINT
-        )]
-        tracker_api_endpoint: http::Uri,
-
-        #[clap(
-            short = 'f',
-            long = "file",
-            help = "Config file path",
-            default_value = network_broker::CONFIG_PATH
-        )]
-        file: PathBuf,
+        #[clap(flatten)]
+        options: network_broker::Options,
     },
 
     #[command(about = "Run tracker for Substrate-based node")]
@@ -259,12 +253,13 @@ impl Cli {
                 execute("Initializer", async { initializer::run(options).await })
             }
             Commands::Sidecar { options } => {
-                execute("Sidecar", async { sidecar::run(options).await })
+                execute("Sidecar", async { sidecar::run(*options).await })
             }
-            Commands::NetworkBroker { tracker_api_endpoint, file } => {
-                execute("Network Broker", async {
-                    network_broker::run(tracker_api_endpoint, file).await
-                })
+            Commands::ComponentSidecar { options } => {
+                execute("Component Sidecar", async { sidecar::run_component(options).await })
+            }
+            Commands::NetworkBroker { options } => {
+                execute("Network Broker", async { network_broker::run(options).await })
             }
             Commands::Tracker { options } => {
                 execute("Tracker", async { tracker::run(options).await })
@@ -310,6 +305,9 @@ fn init_tracing() {
 #[cfg(test)]
 mod tests {
     use clap::Parser;
+    use kallax_primitives::{
+        BlockchainComponent, BlockchainLayer, ExternalEndpoint, ServedChain, Transport,
+    };
     use std::path::PathBuf;
 
     use crate::{Cli, Commands};
@@ -459,16 +457,22 @@ mod tests {
 
     #[test]
     fn test_command_network_broker() {
-        if let Commands::NetworkBroker { tracker_api_endpoint, file } = Cli::parse_from([
+        if let Commands::NetworkBroker { options } = Cli::parse_from([
             "program_name",
             "network-broker",
             "--tracker-api-endpoint=https://tracker.example.com",
+            "--tracker-request-timeout=5",
+            "--tracker-max-retries=0",
+            "--tracker-retry-backoff=100",
             "--file=/tmp/config.json",
         ])
         .commands
         {
-            assert_eq!(tracker_api_endpoint.to_string(), "https://tracker.example.com/");
-            assert_eq!(file, PathBuf::from("/tmp/config.json"));
+            assert_eq!(options.tracker_api_endpoint.to_string(), "https://tracker.example.com/");
+            assert_eq!(options.tracker_request_timeout, 5);
+            assert_eq!(options.tracker_max_retries, 0);
+            assert_eq!(options.tracker_retry_backoff, 100);
+            assert_eq!(options.file, PathBuf::from("/tmp/config.json"));
         } else {
             panic!();
         }
@@ -476,12 +480,16 @@ mod tests {
 
     #[test]
     fn test_command_network_broker_defaults() {
-        if let Commands::NetworkBroker { tracker_api_endpoint, file } =
+        if let Commands::NetworkBroker { options } =
             Cli::parse_from(["program_name", "network-broker"]).commands
         {
             // Default values should be set
-            assert!(!tracker_api_endpoint.to_string().is_empty());
-            assert!(!file.as_os_str().is_empty());
+            assert!(!options.tracker_api_endpoint.to_string().is_empty());
+            assert!(!options.file.as_os_str().is_empty());
+            assert_eq!(
+                options.tracker_max_retries,
+                crate::consts::NETWORK_BROKER_DEFAULT_TRACKER_MAX_RETRIES
+            );
         } else {
             panic!();
         }
@@ -517,6 +525,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_command_component_sidecar() {
+        if let Commands::ComponentSidecar { options } = Cli::parse_from([
+            "program_name",
+            "component-sidecar",
+            "--tracker-grpc-endpoint=http://kallax-tracker.mainnet.svc.cluster.local:80",
+            "--kind=bridge",
+            "--name=thx-relayer",
+            "--endpoint=http://10.0.0.1:9615/metrics",
+            "--chain=rootchain:mainnet",
+            "--chain=leafchain:thx",
+        ])
+        .commands
+        {
+            assert_eq!(options.kind, BlockchainComponent::Bridge);
+            assert_eq!(options.name, "thx-relayer");
+            assert_eq!(options.endpoints, vec!["http://10.0.0.1:9615/metrics".to_string()]);
+            assert_eq!(
+                options.chains,
+                vec![
+                    ServedChain { layer: BlockchainLayer::Rootchain, chain_id: "mainnet".into() },
+                    ServedChain { layer: BlockchainLayer::Leafchain, chain_id: "thx".into() },
+                ]
+            );
+            assert_eq!(options.version, None);
+        } else {
+            panic!();
+        }
+    }
+
     #[test]
     fn test_command_sidecar_auto_detect_public_ip() {
         if let Commands::Sidecar { options } = Cli::parse_from([
@@ -577,4 +615,34 @@ mod tests {
             panic!();
         }
     }
+
+    #[test]
+    fn test_command_sidecar_multiple_external_endpoints() {
+        if let Commands::Sidecar { options } = Cli::parse_from([
+            "program_name",
+            "sidecar",
+            "--tracker-grpc-endpoint=http://kallax-tracker.mainnet.svc.cluster.local:80",
+            "--rootchain-id=mainnet",
+            "--rootchain-node-websocket-endpoint=ws://127.0.0.1:50002",
+            "--external-rootchain-p2p-host=1.2.3.4",
+            "--external-rootchain-p2p-port=30333",
+            "--external-rootchain-p2p-endpoint=[2001:db8::1]:30333",
+            "--external-rootchain-p2p-endpoint=node.example.com:30333",
+            "--external-rootchain-p2p-transport-port=ws=30334",
+        ])
+        .commands
+        {
+            assert_eq!(
+                options.external_rootchain_p2p_endpoints,
+                [
+                    ExternalEndpoint::new("2001:db8::1".to_string(), 30333),
+                    ExternalEndpoint::new("node.example.com".to_string(), 30333),
+                ]
+            );
+            assert_eq!(options.external_rootchain_p2p_transport_ports, [(Transport::Ws, 30334)]);
+            assert!(options.external_leafchain_p2p_endpoints.is_empty());
+        } else {
+            panic!();
+        }
+    }
 }
@@ -1,5 +1,7 @@
+use std::collections::BTreeMap;
+
 use kallax_network_broker::{ChainEndpoint, Node};
-use kallax_primitives::ExternalEndpoint;
+use kallax_primitives::{ExternalEndpoint, Transport};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -54,7 +56,11 @@ pub struct Leafchain {
 pub struct RootchainNode {
     pub ws_endpoint: String,
 
+    /// Superseded by `external_p2p_endpoints`, kept for existing
+    /// configurations.
     pub external_p2p_endpoint: Option<P2pEndpoint>,
+    #[serde(default)]
+    pub external_p2p_endpoints: Vec<P2pEndpoint>,
 }
 
 #[allow(clippy::struct_field_names)]
@@ -64,8 +70,16 @@ pub struct LeafchainNode {
     pub rootchain_ws_endpoint: String,
     pub leafchain_ws_endpoint: String,
 
+    /// Superseded by `external_rootchain_p2p_endpoints`, kept for existing
+    /// configurations.
     pub external_rootchain_p2p_endpoint: Option<P2pEndpoint>,
+    #[serde(default)]
+    pub external_rootchain_p2p_endpoints: Vec<P2pEndpoint>,
+    /// Superseded by `external_leafchain_p2p_endpoints`, kept for existing
+    /// configurations.
     pub external_leafchain_p2p_endpoint: Option<P2pEndpoint>,
+    #[serde(default)]
+    pub external_leafchain_p2p_endpoints: Vec<P2pEndpoint>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -73,6 +87,25 @@ pub struct LeafchainNode {
 pub struct P2pEndpoint {
     pub host: String,
     pub port: u16,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub transport_ports: BTreeMap<Transport, u16>,
+}
+
+impl From<P2pEndpoint> for ExternalEndpoint {
+    fn from(P2pEndpoint { host, port, transport_ports }: P2pEndpoint) -> Self {
+        transport_ports.into_iter().fold(Self::new(host, port), |endpoint, (transport, port)| {
+            endpoint.with_transport_port(transport, port)
+        })
+    }
+}
+
+/// Merges the single endpoint of existing configurations into the list of
+/// endpoints.
+fn external_endpoints(
+    endpoint: Option<P2pEndpoint>,
+    endpoints: Vec<P2pEndpoint>,
+) -> Vec<ExternalEndpoint> {
+    endpoint.into_iter().chain(endpoints).map(Into::into).collect()
 }
 
 impl Thxnet {
@@ -230,13 +263,9 @@ impl Rootchain {
 
         rootchain_nodes
             .into_iter()
-            .map(|RootchainNode { ws_endpoint, external_p2p_endpoint }| {
-                let external_rootchain_p2p_endpoint =
-                    if let Some(P2pEndpoint { host, port }) = external_p2p_endpoint {
-                        Some(ExternalEndpoint { host, port })
-                    } else {
-                        None
-                    };
+            .map(|RootchainNode { ws_endpoint, external_p2p_endpoint, external_p2p_endpoints }| {
+                let external_rootchain_p2p_endpoints =
+                    external_endpoints(external_p2p_endpoint, external_p2p_endpoints);
                 Node {
                     rootchain_endpoint: ChainEndpoint {
                         chain_id: rootchain_id.to_string(),
@@ -245,8 +274,8 @@ impl Rootchain {
                             .expect("websocket endpoint is invalid"),
                     },
                     leafchain_endpoint: None,
-                    external_rootchain_p2p_endpoint,
-                    external_leafchain_p2p_endpoint: None,
+                    external_rootchain_p2p_endpoints,
+                    external_leafchain_p2p_endpoints: Vec::new(),
                 }
             })
             .collect()
@@ -278,21 +307,19 @@ impl Leafchain {
                      rootchain_ws_endpoint,
                      leafchain_ws_endpoint,
                      external_rootchain_p2p_endpoint,
+                     external_rootchain_p2p_endpoints,
                      external_leafchain_p2p_endpoint,
+                     external_leafchain_p2p_endpoints,
                  }| {
-                    let external_rootchain_p2p_endpoint =
-                        if let Some(P2pEndpoint { host, port }) = external_rootchain_p2p_endpoint {
-                            Some(ExternalEndpoint { host, port })
-                        } else {
-                            None
-                        };
-
-                    let external_leafchain_p2p_endpoint =
-                        if let Some(P2pEndpoint { host, port }) = external_leafchain_p2p_endpoint {
-                            Some(ExternalEndpoint { host, port })
-                        } else {
-                            None
-                        };
+                    let external_rootchain_p2p_endpoints = external_endpoints(
+                        external_rootchain_p2p_endpoint,
+                        external_rootchain_p2p_endpoints,
+                    );
+
+                    let external_leafchain_p2p_endpoints = external_endpoints(
+                        external_leafchain_p2p_endpoint,
+                        external_leafchain_p2p_endpoints,
+                    );
 
                     Node {
                         rootchain_endpoint: ChainEndpoint {
@@ -307,8 +334,8 @@ impl Leafchain {
                                 .parse::<http::Uri>()
                                 .expect("websocket endpoint is invalid"),
                         }),
-                        external_rootchain_p2p_endpoint,
-                        external_leafchain_p2p_endpoint,
+                        external_rootchain_p2p_endpoints,
+                        external_leafchain_p2p_endpoints,
                     }
                 },
             )
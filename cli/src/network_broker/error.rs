@@ -8,7 +8,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[snafu(visibility(pub))]
 pub enum Error {
     #[snafu(display("{source}"))]
-    Application { source: kallax_network_broker::Error },
+    Application { source: Box<kallax_network_broker::Error> },
 
     #[snafu(display("{source}"))]
     JoinTaskHandle { source: tokio::task::JoinError },
@@ -26,7 +26,7 @@ pub enum Error {
 impl From<kallax_network_broker::Error> for Error {
     #[inline]
     fn from(source: kallax_network_broker::Error) -> Self {
-        Self::Application { source }
+        Self::Application { source: Box::new(source) }
     }
 }
 
@@ -2,7 +2,7 @@ mod config;
 mod error;
 mod options;
 
-use std::{path::PathBuf, time::Duration};
+use std::time::Duration;
 
 use kallax_network_broker::Node;
 use serde_yaml::{self};
@@ -11,6 +11,7 @@ use tokio::fs;
 pub use self::{
     config::Thxnet,
     error::{Error, Result},
+    options::Options,
 };
 
 const POLLING_INTERVAL: Duration = Duration::from_millis(5000);
@@ -20,7 +21,15 @@ pub const CONFIG_PATH: &str = "./network-broker.yaml";
 /// # Errors
 ///
 /// This function returns an error if the network-broker is not created.
-pub async fn run(tracker_api_endpoint: http::Uri, file: PathBuf) -> Result<()> {
+pub async fn run(
+    Options {
+        tracker_api_endpoint,
+        tracker_request_timeout,
+        tracker_max_retries,
+        tracker_retry_backoff,
+        file,
+    }: Options,
+) -> Result<()> {
     let config = {
         tracing::info!("Read configuration file from `{}`", file.display());
 
@@ -34,6 +43,9 @@ pub async fn run(tracker_api_endpoint: http::Uri, file: PathBuf) -> Result<()> {
 
         kallax_network_broker::Config {
             tracker_api_endpoint,
+            tracker_request_timeout: Duration::from_secs(tracker_request_timeout),
+            tracker_max_retries,
+            tracker_retry_backoff: Duration::from_millis(tracker_retry_backoff),
             polling_interval: POLLING_INTERVAL,
             nodes,
         }
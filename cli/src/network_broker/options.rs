@@ -2,13 +2,37 @@ use std::path::PathBuf;
 
 use clap::Args;
 
-use crate::network_broker::{CONFIG_PATH, TRACKER_API_ENDPOINT};
+use crate::{
+    consts,
+    network_broker::{CONFIG_PATH, TRACKER_API_ENDPOINT},
+};
 
 #[derive(Args, Debug)]
 pub struct Options {
     #[clap(long = "tracker-api-endpoint", help = "Tracker api endpoint", default_value = TRACKER_API_ENDPOINT)]
     pub tracker_api_endpoint: http::Uri,
 
+    #[clap(
+        long = "tracker-request-timeout",
+        default_value_t = consts::NETWORK_BROKER_DEFAULT_TRACKER_REQUEST_TIMEOUT_SECONDS,
+        help = "Timeout of a request to the tracker in seconds"
+    )]
+    pub tracker_request_timeout: u64,
+
+    #[clap(
+        long = "tracker-max-retries",
+        default_value_t = consts::NETWORK_BROKER_DEFAULT_TRACKER_MAX_RETRIES,
+        help = "Times a failed request to the tracker is retried"
+    )]
+    pub tracker_max_retries: usize,
+
+    #[clap(
+        long = "tracker-retry-backoff",
+        default_value_t = consts::NETWORK_BROKER_DEFAULT_TRACKER_RETRY_BACKOFF_MILLISECONDS,
+        help = "Delay before the first retry of a request to the tracker in milliseconds, doubled after every retry"
+    )]
+    pub tracker_retry_backoff: u64,
+
     #[clap(short = 'f', long = "file", help = "Config file path", default_value = CONFIG_PATH)]
     pub file: PathBuf,
 }
@@ -1,3 +1,4 @@
+use kallax_primitives::BlockchainComponent;
 use snafu::Snafu;
 
 use crate::error::CommandError;
@@ -21,6 +22,9 @@ pub enum Error {
 
     #[snafu(display("Leafchain node WebSocket endpoint must be provided"))]
     LeafchainNodeWebSocketEndpointNotProvided,
+
+    #[snafu(display("{kind} is tracked by the node sidecar, not as a component"))]
+    ChainIsNotComponent { kind: BlockchainComponent },
 }
 
 impl From<kallax_sidecar::Error> for Error {
@@ -35,9 +39,9 @@ impl CommandError for Error {
         match self {
             Self::Application { .. } => exitcode::SOFTWARE,
             Self::JoinTaskHandle { .. } | Self::CreateUnixSignalListener { .. } => exitcode::IOERR,
-            Self::LeafchainNameNotProvided | Self::LeafchainNodeWebSocketEndpointNotProvided => {
-                exitcode::USAGE
-            }
+            Self::LeafchainNameNotProvided
+            | Self::LeafchainNodeWebSocketEndpointNotProvided
+            | Self::ChainIsNotComponent { .. } => exitcode::USAGE,
         }
     }
 }
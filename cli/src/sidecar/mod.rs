@@ -3,15 +3,18 @@ mod options;
 
 use std::{net::IpAddr, net::SocketAddr, time::Duration};
 
-use kallax_primitives::ExternalEndpoint;
-use kallax_sidecar::ChainEndpoint;
+use kallax_primitives::{
+    AddressPolicy, AddressScope, ComponentRegistration, ExternalEndpoint, Transport,
+};
+use kallax_sidecar::{ChainEndpoint, ComponentConfig};
 
 pub use self::{
     error::{Error, Result},
-    options::Options,
+    options::{ComponentOptions, Options},
 };
 
 const POLLING_INTERVAL: Duration = Duration::from_millis(1000);
+const COMPONENT_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
 const HETZNER_METADATA_URL: &str = "http://169.254.169.254/hetzner/v1/metadata/public-ipv4";
 const FALLBACK_IP_DETECTION_URL: &str = "https://ifconfig.me/ip";
 const PUBLIC_IP_DETECTION_TIMEOUT: Duration = Duration::from_secs(5);
@@ -28,10 +31,18 @@ pub async fn run(options: Options) -> Result<()> {
             leafchain_id,
             leafchain_node_websocket_endpoint,
             allow_loopback_ip,
+            advertise_address_scopes,
             external_rootchain_p2p_host,
             external_rootchain_p2p_port,
+            external_rootchain_p2p_endpoints,
+            external_rootchain_p2p_transport_ports,
             external_leafchain_p2p_host,
             external_leafchain_p2p_port,
+            external_leafchain_p2p_endpoints,
+            external_leafchain_p2p_transport_ports,
+            node_name,
+            rootchain_node_role,
+            leafchain_node_role,
             auto_detect_public_ip,
             public_ip_detection_url,
             prefer_exposed_peers,
@@ -55,6 +66,15 @@ pub async fn run(options: Options) -> Result<()> {
             (None, None) => None,
         };
 
+        let mut advertise_address_scopes = if advertise_address_scopes.is_empty() {
+            AddressPolicy::routable()
+        } else {
+            AddressPolicy::new(advertise_address_scopes)
+        };
+        if allow_loopback_ip {
+            advertise_address_scopes = advertise_address_scopes.with(AddressScope::Loopback);
+        }
+
         let rootchain_endpoint = ChainEndpoint {
             chain_id: rootchain_id,
             websocket_endpoint: rootchain_node_websocket_endpoint,
@@ -69,25 +89,35 @@ pub async fn run(options: Options) -> Result<()> {
 
         // Warn loudly if auto-detect was requested but failed and no explicit host set
         if auto_detect_public_ip && detected_ip.is_none() {
-            if external_rootchain_p2p_host.is_none() || external_leafchain_p2p_host.is_none() {
+            if (external_rootchain_p2p_host.is_none()
+                && external_rootchain_p2p_endpoints.is_empty())
+                || (external_leafchain_p2p_host.is_none()
+                    && external_leafchain_p2p_endpoints.is_empty())
+            {
                 tracing::error!(
                     "Public IP auto-detection was enabled but failed, and no explicit \
-                     --external-*-p2p-host was provided. P2P addresses may not be routable."
+                     --external-*-p2p-host or --external-*-p2p-endpoint was provided. P2P \
+                     addresses may not be routable."
                 );
             }
         }
 
         // Explicit --external-*-p2p-host takes priority over auto-detected IP
-        let external_rootchain_p2p_endpoint =
+        let external_rootchain_p2p_endpoints = external_endpoints(
             external_rootchain_p2p_host.or_else(|| detected_ip.clone()).map(|host| {
-                ExternalEndpoint { host, port: external_rootchain_p2p_port.unwrap_or_default() }
-            });
+                ExternalEndpoint::new(host, external_rootchain_p2p_port.unwrap_or_default())
+            }),
+            external_rootchain_p2p_endpoints,
+            &external_rootchain_p2p_transport_ports,
+        );
         let detected_public_ip = detected_ip.clone();
-        let external_leafchain_p2p_endpoint =
-            external_leafchain_p2p_host.or(detected_ip).map(|host| ExternalEndpoint {
-                host,
-                port: external_leafchain_p2p_port.unwrap_or_default(),
-            });
+        let external_leafchain_p2p_endpoints = external_endpoints(
+            external_leafchain_p2p_host.or(detected_ip).map(|host| {
+                ExternalEndpoint::new(host, external_leafchain_p2p_port.unwrap_or_default())
+            }),
+            external_leafchain_p2p_endpoints,
+            &external_leafchain_p2p_transport_ports,
+        );
 
         kallax_sidecar::Config {
             tracker_grpc_endpoint,
@@ -95,13 +125,17 @@ pub async fn run(options: Options) -> Result<()> {
             rootchain_endpoint,
             leafchain_endpoint,
             allow_loopback_ip,
-            external_rootchain_p2p_endpoint,
-            external_leafchain_p2p_endpoint,
+            advertise_address_scopes,
+            external_rootchain_p2p_endpoints,
+            external_leafchain_p2p_endpoints,
             diagnostic_listen_address: SocketAddr::new(
                 diagnostic_listen_address,
                 diagnostic_listen_port,
             ),
             detected_public_ip,
+            node_name,
+            rootchain_node_role,
+            leafchain_node_role,
         }
     };
 
@@ -110,6 +144,45 @@ pub async fn run(options: Options) -> Result<()> {
     Ok(())
 }
 
+/// # Errors
+///
+/// This function returns an error if the component sidecar is not created.
+pub async fn run_component(options: ComponentOptions) -> Result<()> {
+    let ComponentOptions { tracker_grpc_endpoint, kind, name, endpoints, chains, version } =
+        options;
+
+    if kind.is_chain() {
+        return Err(Error::ChainIsNotComponent { kind });
+    }
+
+    kallax_sidecar::serve_component(ComponentConfig {
+        tracker_grpc_endpoint,
+        keep_alive_interval: COMPONENT_KEEP_ALIVE_INTERVAL,
+        registration: ComponentRegistration { kind, name, endpoints, chains, version },
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Returns the endpoint given by host and port followed by the additional
+/// endpoints, each with the external ports of `transport_ports`.
+fn external_endpoints(
+    endpoint: Option<ExternalEndpoint>,
+    endpoints: Vec<ExternalEndpoint>,
+    transport_ports: &[(Transport, u16)],
+) -> Vec<ExternalEndpoint> {
+    endpoint
+        .into_iter()
+        .chain(endpoints)
+        .map(|endpoint| {
+            transport_ports.iter().fold(endpoint, |endpoint, &(transport, port)| {
+                endpoint.with_transport_port(transport, port)
+            })
+        })
+        .collect()
+}
+
 async fn detect_public_ip(custom_url: Option<&str>) -> Option<String> {
     let client = reqwest::Client::builder().timeout(PUBLIC_IP_DETECTION_TIMEOUT).build().ok()?;
 
@@ -1,6 +1,9 @@
 use std::net::IpAddr;
 
 use clap::Args;
+use kallax_primitives::{
+    AddressScope, BlockchainComponent, ExternalEndpoint, NodeRole, ServedChain, Transport,
+};
 
 #[derive(Args, Debug)]
 pub struct Options {
@@ -22,6 +25,16 @@ pub struct Options {
     #[clap(long = "allow-loopback-ip", help = "Allow to make connection with loopback IP address")]
     pub allow_loopback_ip: bool,
 
+    #[clap(
+        long = "advertise-address-scopes",
+        value_delimiter = ',',
+        help = "Scopes of the local listen addresses advertised to the tracker (unspecified, \
+                loopback, link_local, docker_bridge, private, shared_nat, public or dns), every \
+                scope except unspecified and loopback if omitted, loopback is added by \
+                --allow-loopback-ip"
+    )]
+    pub advertise_address_scopes: Vec<AddressScope>,
+
     #[clap(
         long = "external-rootchain-p2p-host",
         help = "External host name for exposing the P2P network of Rootchain"
@@ -34,6 +47,22 @@ pub struct Options {
     )]
     pub external_rootchain_p2p_port: Option<u16>,
 
+    #[clap(
+        long = "external-rootchain-p2p-endpoint",
+        help = "Additional external endpoint `<host>:<port>` of the P2P network of Rootchain, e.g. \
+                the IPv6 route of a dual-stack node as `[2001:db8::1]:30333`, may be repeated"
+    )]
+    pub external_rootchain_p2p_endpoints: Vec<ExternalEndpoint>,
+
+    #[clap(
+        long = "external-rootchain-p2p-transport-port",
+        value_parser = parse_transport_port,
+        help = "External port of a transport of Rootchain if it differs from the port of the \
+                external endpoints, e.g. `ws=30334` (tcp, ws, wss or quic), applied to every \
+                external endpoint, may be repeated"
+    )]
+    pub external_rootchain_p2p_transport_ports: Vec<(Transport, u16)>,
+
     #[clap(
         long = "external-leafchain-p2p-host",
         help = "External host name for exposing the P2P network of Leafchain"
@@ -46,6 +75,39 @@ pub struct Options {
     )]
     pub external_leafchain_p2p_port: Option<u16>,
 
+    #[clap(
+        long = "external-leafchain-p2p-endpoint",
+        help = "Additional external endpoint `<host>:<port>` of the P2P network of Leafchain, e.g. \
+                the IPv6 route of a dual-stack node as `[2001:db8::1]:30333`, may be repeated"
+    )]
+    pub external_leafchain_p2p_endpoints: Vec<ExternalEndpoint>,
+
+    #[clap(
+        long = "external-leafchain-p2p-transport-port",
+        value_parser = parse_transport_port,
+        help = "External port of a transport of Leafchain if it differs from the port of the \
+                external endpoints, e.g. `ws=30334` (tcp, ws, wss or quic), applied to every \
+                external endpoint, may be repeated"
+    )]
+    pub external_leafchain_p2p_transport_ports: Vec<(Transport, u16)>,
+
+    #[clap(long = "node-name", help = "Node name reported to the tracker")]
+    pub node_name: Option<String>,
+
+    #[clap(
+        long = "rootchain-node-role",
+        help = "Role of the Rootchain node reported to the tracker (validator, collator, archive \
+                or full), derived from `system_nodeRoles` if not set"
+    )]
+    pub rootchain_node_role: Option<NodeRole>,
+
+    #[clap(
+        long = "leafchain-node-role",
+        help = "Role of the Leafchain node reported to the tracker (validator, collator, archive \
+                or full), derived from `system_nodeRoles` if not set"
+    )]
+    pub leafchain_node_role: Option<NodeRole>,
+
     #[clap(
         long = "auto-detect-public-ip",
         help = "Auto-detect public IP at startup using cloud metadata or HTTP service. \
@@ -79,3 +141,39 @@ pub struct Options {
     )]
     pub diagnostic_listen_port: u16,
 }
+
+#[derive(Args, Debug)]
+pub struct ComponentOptions {
+    #[clap(long = "tracker-grpc-endpoint", help = "Tracker gRPC endpoint")]
+    pub tracker_grpc_endpoint: http::Uri,
+
+    #[clap(long = "kind", help = "Kind of the component (bridge or offline_worker)")]
+    pub kind: BlockchainComponent,
+
+    #[clap(long = "name", help = "Name of the component, unique among the components of its kind")]
+    pub name: String,
+
+    #[clap(
+        long = "endpoint",
+        help = "Endpoint of the component, e.g. its metrics or RPC URL, may be repeated"
+    )]
+    pub endpoints: Vec<String>,
+
+    #[clap(
+        long = "chain",
+        help = "Chain served by the component as `<layer>:<chain_id>`, e.g. `leafchain:thx`, may \
+                be repeated"
+    )]
+    pub chains: Vec<ServedChain>,
+
+    #[clap(long = "component-version", help = "Version of the component reported to the tracker")]
+    pub version: Option<String>,
+}
+
+fn parse_transport_port(s: &str) -> Result<(Transport, u16), String> {
+    let (transport, port) =
+        s.split_once('=').ok_or_else(|| format!("expected `<transport>=<port>`, got `{s}`"))?;
+    let transport = transport.parse::<Transport>().map_err(|err| err.to_string())?;
+    let port = port.parse::<u16>().map_err(|err| err.to_string())?;
+    Ok((transport, port))
+}
@@ -3,7 +3,7 @@ mod options;
 
 use std::{net::SocketAddr, path::Path, time::Duration};
 
-use kallax_primitives::ChainSpec;
+use kallax_primitives::{AddressPolicy, AddressScope, ChainSpec};
 
 use self::error::Result;
 pub use self::{error::Error, options::Options};
@@ -51,17 +51,68 @@ pub async fn run(options: Options) -> Result<()> {
         rootchain_spec_files,
         leafchain_spec_files,
         allow_peer_in_loopback_network,
+        accept_address_scopes,
+        scope_addresses_by_caller,
+        record_observed_addresses,
         peer_time_to_live,
+        min_surviving_peers_per_chain,
+        tombstone_time_to_live,
+        rate_limit_per_second,
+        rate_limit_burst,
+        max_chains_per_layer,
+        max_peers_per_chain,
+        max_addresses_per_peer,
+        max_components,
+        peer_policy_file,
+        admin_token,
+        peer_verification,
+        peer_verification_timeout,
+        peer_verification_concurrency,
+        chain_stall_threshold,
+        alert_rules_file,
+        audit_log_directory,
+        audit_log_max_file_size,
+        audit_log_max_file_age,
+        audit_log_max_files,
     } = options;
     let config = {
         let api_listen_address = SocketAddr::from((api_listen_address, api_listen_port));
         let grpc_listen_address = SocketAddr::from((grpc_listen_address, grpc_listen_port));
         let peer_time_to_live = Duration::from_secs(peer_time_to_live);
+        let mut accepted_address_scopes = if accept_address_scopes.is_empty() {
+            AddressPolicy::routable()
+        } else {
+            AddressPolicy::new(accept_address_scopes)
+        };
+        if allow_peer_in_loopback_network {
+            accepted_address_scopes = accepted_address_scopes.with(AddressScope::Loopback);
+        }
         kallax_tracker_server::Config {
             api_listen_address,
             grpc_listen_address,
-            allow_peer_in_loopback_network,
+            accepted_address_scopes,
+            scope_addresses_by_caller,
+            record_observed_addresses,
             peer_time_to_live,
+            min_surviving_peers_per_chain,
+            tombstone_time_to_live: Duration::from_secs(tombstone_time_to_live),
+            rate_limit_per_second,
+            rate_limit_burst,
+            max_chains_per_layer,
+            max_peers_per_chain,
+            max_addresses_per_peer,
+            max_components,
+            peer_policy_file,
+            admin_token,
+            peer_verification_mode: peer_verification.into(),
+            peer_verification_timeout: Duration::from_secs(peer_verification_timeout),
+            peer_verification_concurrency,
+            chain_stall_threshold: Duration::from_secs(chain_stall_threshold),
+            alert_rules_file,
+            audit_log_directory,
+            audit_log_max_file_size,
+            audit_log_max_file_age: Duration::from_secs(audit_log_max_file_age),
+            audit_log_max_files,
         }
     };
 
@@ -1,6 +1,7 @@
 use std::{net::IpAddr, path::PathBuf};
 
-use clap::Args;
+use clap::{Args, ValueEnum};
+use kallax_primitives::AddressScope;
 
 use crate::consts;
 
@@ -30,10 +31,193 @@ pub struct Options {
     )]
     pub allow_peer_in_loopback_network: bool,
 
+    #[clap(
+        long = "accept-address-scopes",
+        value_delimiter = ',',
+        help = "Scopes of the peer addresses accepted on registration (unspecified, loopback, \
+                link_local, docker_bridge, private, shared_nat, public or dns), every scope \
+                except unspecified and loopback if omitted"
+    )]
+    pub accept_address_scopes: Vec<AddressScope>,
+
+    #[clap(
+        long = "scope-addresses-by-caller",
+        help = "Hand out peer addresses of local networks only to callers which are not on the \
+                public internet"
+    )]
+    pub scope_addresses_by_caller: bool,
+
+    #[clap(
+        long = "record-observed-addresses",
+        help = "Also hand out peer addresses with private IPs rewritten to the public source IP \
+                they registered from"
+    )]
+    pub record_observed_addresses: bool,
+
     #[clap(
         long = "peer-time-to-live",
         default_value = consts::TRACKER_DEFAULT_PEER_TIME_TO_LIVE_SECONDS.to_string(),
         help = "Time-to-live of Peer in seconds"
     )]
     pub peer_time_to_live: u64,
+
+    #[clap(
+        long = "min-surviving-peers-per-chain",
+        default_value = consts::TRACKER_DEFAULT_MIN_SURVIVING_PEERS_PER_CHAIN.to_string(),
+        help = "Number of the freshest peers of a chain which are kept as stale instead of \
+                evicted, 0 evicts every expired peer"
+    )]
+    pub min_surviving_peers_per_chain: usize,
+
+    #[clap(
+        long = "tombstone-time-to-live",
+        default_value = consts::TRACKER_DEFAULT_TOMBSTONE_TIME_TO_LIVE_SECONDS.to_string(),
+        help = "Seconds evicted or cleared peers are reported to sidecars as tombstones"
+    )]
+    pub tombstone_time_to_live: u64,
+
+    #[clap(
+        long = "rate-limit-per-second",
+        default_value = consts::TRACKER_DEFAULT_RATE_LIMIT_PER_SECOND.to_string(),
+        help = "Requests per second allowed from a single source IP, 0 disables rate limiting. \
+                Clients behind a proxy or NAT share the source IP of the proxy"
+    )]
+    pub rate_limit_per_second: u32,
+
+    #[clap(
+        long = "rate-limit-burst",
+        default_value = consts::TRACKER_DEFAULT_RATE_LIMIT_BURST.to_string(),
+        help = "Requests a single source IP may burst above the rate limit"
+    )]
+    pub rate_limit_burst: u32,
+
+    #[clap(
+        long = "max-chains-per-layer",
+        default_value = consts::TRACKER_DEFAULT_MAX_CHAINS_PER_LAYER.to_string(),
+        help = "Maximum number of chains tracked per blockchain layer"
+    )]
+    pub max_chains_per_layer: usize,
+
+    #[clap(
+        long = "max-peers-per-chain",
+        default_value = consts::TRACKER_DEFAULT_MAX_PEERS_PER_CHAIN.to_string(),
+        help = "Maximum number of peers tracked per chain"
+    )]
+    pub max_peers_per_chain: usize,
+
+    #[clap(
+        long = "max-addresses-per-peer",
+        default_value = consts::TRACKER_DEFAULT_MAX_ADDRESSES_PER_PEER.to_string(),
+        help = "Maximum number of addresses tracked per peer ID"
+    )]
+    pub max_addresses_per_peer: usize,
+
+    #[clap(
+        long = "max-components",
+        default_value = consts::TRACKER_DEFAULT_MAX_COMPONENTS.to_string(),
+        help = "Maximum number of components registered, e.g. bridge relayers and offline workers"
+    )]
+    pub max_components: usize,
+
+    #[clap(
+        long = "peer-policy-file",
+        help = "File to persist the peer blocklist and allowlist, kept in memory only if omitted"
+    )]
+    pub peer_policy_file: Option<PathBuf>,
+
+    #[clap(
+        long = "admin-token",
+        env = consts::KALLAX_TRACKER_ADMIN_TOKEN_ENV,
+        help = "Bearer token required by the admin API, the admin API is disabled if omitted"
+    )]
+    pub admin_token: Option<String>,
+
+    #[clap(
+        long = "peer-verification",
+        value_enum,
+        default_value = "disabled",
+        help = "Dial back advertised addresses and filter the handed out addresses by the result"
+    )]
+    pub peer_verification: PeerVerificationMode,
+
+    #[clap(
+        long = "peer-verification-timeout",
+        default_value = consts::TRACKER_DEFAULT_PEER_VERIFICATION_TIMEOUT_SECONDS.to_string(),
+        help = "Timeout of dialing back an advertised address in seconds"
+    )]
+    pub peer_verification_timeout: u64,
+
+    #[clap(
+        long = "peer-verification-concurrency",
+        default_value = consts::TRACKER_DEFAULT_PEER_VERIFICATION_CONCURRENCY.to_string(),
+        help = "Maximum number of addresses dialed back concurrently"
+    )]
+    pub peer_verification_concurrency: usize,
+
+    #[clap(
+        long = "chain-stall-threshold",
+        default_value = consts::TRACKER_DEFAULT_CHAIN_STALL_THRESHOLD_SECONDS.to_string(),
+        help = "Seconds without a new best block after which a node is reported as stuck"
+    )]
+    pub chain_stall_threshold: u64,
+
+    #[clap(
+        long = "alert-rules-file",
+        help = "JSON file with alert rules and the webhook URLs alerts are posted to"
+    )]
+    pub alert_rules_file: Option<PathBuf>,
+
+    #[clap(
+        long = "audit-log-directory",
+        help = "Directory to write the JSON-lines audit log of peer and chain spec mutations to"
+    )]
+    pub audit_log_directory: Option<PathBuf>,
+
+    #[clap(
+        long = "audit-log-max-file-size",
+        default_value = consts::TRACKER_DEFAULT_AUDIT_LOG_MAX_FILE_SIZE.to_string(),
+        help = "Size in bytes after which the audit log file is rotated"
+    )]
+    pub audit_log_max_file_size: u64,
+
+    #[clap(
+        long = "audit-log-max-file-age",
+        default_value = consts::TRACKER_DEFAULT_AUDIT_LOG_MAX_FILE_AGE_SECONDS.to_string(),
+        help = "Seconds after which the audit log file is rotated"
+    )]
+    pub audit_log_max_file_age: u64,
+
+    #[clap(
+        long = "audit-log-max-files",
+        default_value = consts::TRACKER_DEFAULT_AUDIT_LOG_MAX_FILES.to_string(),
+        help = "Number of rotated audit log files kept, older files are deleted"
+    )]
+    pub audit_log_max_files: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum PeerVerificationMode {
+    /// Never dial back advertised addresses
+    #[default]
+    Disabled,
+
+    /// Dial back advertised addresses, but hand out every address
+    Observe,
+
+    /// Hand out only the verified addresses of peers which have one
+    Prefer,
+
+    /// Hand out verified addresses only
+    Require,
+}
+
+impl From<PeerVerificationMode> for kallax_tracker_server::VerificationMode {
+    fn from(mode: PeerVerificationMode) -> Self {
+        match mode {
+            PeerVerificationMode::Disabled => Self::Disabled,
+            PeerVerificationMode::Observe => Self::Observe,
+            PeerVerificationMode::Prefer => Self::Prefer,
+            PeerVerificationMode::Require => Self::Require,
+        }
+    }
 }
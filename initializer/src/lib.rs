@@ -152,9 +152,7 @@ use std::{
 };
 
 use kallax_primitives::BlockchainLayer;
-use kallax_tracker_grpc_client::{
-    Client as TrackerClient, Config as TrackerClientConfig, LeafchainSpec, RootchainSpec,
-};
+use kallax_tracker_grpc_client::{Client as TrackerClient, Config as TrackerClientConfig, Tracker};
 use snafu::ResultExt;
 use sp_application_crypto::KeyTypeId;
 
@@ -243,14 +241,10 @@ where
     C: fmt::Display + Send + Sync,
     P: AsRef<Path>,
 {
-    let chain_spec = match blockchain_layer {
-        BlockchainLayer::Rootchain => RootchainSpec::get(tracker_client, &chain_name)
-            .await
-            .map_err(|e| Error::GetChainSpec { error_message: e.to_string() })?,
-        BlockchainLayer::Leafchain => LeafchainSpec::get(tracker_client, &chain_name)
-            .await
-            .map_err(|e| Error::GetChainSpec { error_message: e.to_string() })?,
-    };
+    let chain_spec = tracker_client
+        .get_chain_spec(blockchain_layer, &chain_name)
+        .await
+        .map_err(|e| Error::GetChainSpec { error_message: e.to_string() })?;
 
     tokio::fs::write(&chain_spec_file_path, chain_spec).await.with_context(|_| {
         error::WriteFileSnafu { path: chain_spec_file_path.as_ref().to_path_buf() }
@@ -159,6 +159,15 @@ use self::peer_discoverer::PeerDiscoverer;
 pub struct Config {
     pub tracker_api_endpoint: http::Uri,
 
+    /// Timeout of a single attempt of a request to the tracker.
+    pub tracker_request_timeout: Duration,
+
+    pub tracker_max_retries: usize,
+
+    /// Delay before the first retry of a request to the tracker, doubled after
+    /// every retry.
+    pub tracker_retry_backoff: Duration,
+
     pub polling_interval: Duration,
 
     pub nodes: Vec<Node>,
@@ -170,9 +179,9 @@ pub struct Node {
 
     pub leafchain_endpoint: Option<ChainEndpoint>,
 
-    pub external_rootchain_p2p_endpoint: Option<ExternalEndpoint>,
+    pub external_rootchain_p2p_endpoints: Vec<ExternalEndpoint>,
 
-    pub external_leafchain_p2p_endpoint: Option<ExternalEndpoint>,
+    pub external_leafchain_p2p_endpoints: Vec<ExternalEndpoint>,
 }
 
 #[derive(Clone, Debug)]
@@ -192,11 +201,22 @@ pub struct PeerDiscoverers {
 /// This function returns an error if the server is not connected.
 #[allow(clippy::significant_drop_tightening)]
 pub async fn serve(config: Config) -> Result<()> {
-    let Config { tracker_api_endpoint, polling_interval, nodes } = config;
+    let Config {
+        tracker_api_endpoint,
+        tracker_request_timeout,
+        tracker_max_retries,
+        tracker_retry_backoff,
+        polling_interval,
+        nodes,
+    } = config;
 
-    let tracker_client =
-        TrackerClient::new(TrackerClientConfig { api_endpoint: tracker_api_endpoint.clone() })
-            .with_context(|_| error::ConnectTrackerSnafu { uri: tracker_api_endpoint })?;
+    let tracker_client = TrackerClient::new(TrackerClientConfig {
+        api_endpoint: tracker_api_endpoint.clone(),
+        timeout: tracker_request_timeout,
+        max_retries: tracker_max_retries,
+        retry_backoff: tracker_retry_backoff,
+    })
+    .with_context(|_| error::ConnectTrackerSnafu { uri: tracker_api_endpoint })?;
 
     let lifecycle_manager = sigfinn::LifecycleManager::new();
     let _handle = lifecycle_manager.spawn("Network-Broker", {
@@ -207,8 +227,8 @@ pub async fn serve(config: Config) -> Result<()> {
                     |Node {
                          rootchain_endpoint,
                          leafchain_endpoint,
-                         external_rootchain_p2p_endpoint,
-                         external_leafchain_p2p_endpoint,
+                         external_rootchain_p2p_endpoints,
+                         external_leafchain_p2p_endpoints,
                      }| {
                         let rootchain_peer_discoverer = {
                             let ChainEndpoint { chain_id, websocket_endpoint } = rootchain_endpoint;
@@ -218,7 +238,7 @@ pub async fn serve(config: Config) -> Result<()> {
                                 BlockchainLayer::Rootchain,
                                 websocket_endpoint,
                                 tracker_client.clone(),
-                                external_rootchain_p2p_endpoint,
+                                external_rootchain_p2p_endpoints,
                             )
                         };
 
@@ -229,7 +249,7 @@ pub async fn serve(config: Config) -> Result<()> {
                                     BlockchainLayer::Leafchain,
                                     websocket_endpoint,
                                     tracker_client.clone(),
-                                    external_leafchain_p2p_endpoint,
+                                    external_leafchain_p2p_endpoints,
                                 )
                             },
                         );
@@ -1,7 +1,7 @@
 use std::{collections::HashSet, str::FromStr};
 
 use kallax_primitives::{BlockchainLayer, ExternalEndpoint, PeerAddress};
-use kallax_tracker_api_client::{Client as TrackerClient, LeafchainPeer, RootchainPeer};
+use kallax_tracker_api_client::{Client as TrackerClient, Tracker};
 use snafu::ResultExt;
 use substrate_rpc_client::{
     ws_client as connect_substrate_websocket_endpoint, SystemApi, WsClient,
@@ -27,7 +27,7 @@ pub struct PeerDiscoverer {
 
     substrate_client: Option<WsClient>,
 
-    pub external_endpoint: Option<ExternalEndpoint>,
+    pub external_endpoints: Vec<ExternalEndpoint>,
 }
 
 impl PeerDiscoverer {
@@ -38,7 +38,7 @@ impl PeerDiscoverer {
         blockchain_layer: BlockchainLayer,
         substrate_websocket_endpoint: http::Uri,
         tracker_client: TrackerClient,
-        external_endpoint: Option<ExternalEndpoint>,
+        external_endpoints: Vec<ExternalEndpoint>,
     ) -> Self {
         Self {
             chain_id,
@@ -46,7 +46,7 @@ impl PeerDiscoverer {
             substrate_websocket_endpoint,
             tracker_client,
             substrate_client: None,
-            external_endpoint,
+            external_endpoints,
         }
     }
 
@@ -97,24 +97,14 @@ impl PeerDiscoverer {
         tracing::debug!("Current reserved peers: {current_reserved_peers:?}");
 
         // fetch new peer addresses from tracker
-        let mut potential_new_peers = {
-            let blockchain_layer = self.blockchain_layer;
-
-            match blockchain_layer {
-                BlockchainLayer::Rootchain => {
-                    RootchainPeer::get(&self.tracker_client, &self.chain_id)
-                        .await
-                        .map_err(|err| tracing::error!("{err}"))
-                        .unwrap_or_default()
-                }
-                BlockchainLayer::Leafchain => {
-                    LeafchainPeer::get(&self.tracker_client, &self.chain_id)
-                        .await
-                        .map_err(|err| tracing::error!("{err}"))
-                        .unwrap_or_default()
-                }
-            }
-        };
+        let mut potential_new_peers = self
+            .tracker_client
+            .get(self.blockchain_layer, &self.chain_id)
+            .await
+            .map_err(|err| {
+                tracing::error!("Error occurs while fetching peers from Tracker, error: {err}");
+            })
+            .unwrap_or_default();
         tracing::debug!("Peers advertised from tracker: {potential_new_peers:?}");
 
         let stalled_peers = {
@@ -194,37 +184,18 @@ impl PeerDiscoverer {
         }
 
         // advertise local address via tracker
-        if self.external_endpoint.is_some() {
+        if !self.external_endpoints.is_empty() {
             tracing::info!("Advertise local address via tracker");
-            let res = {
-                let blockchain_layer = self.blockchain_layer;
-                match blockchain_layer {
-                    BlockchainLayer::Rootchain => futures::future::try_join_all(
-                        listen_addresses.iter().map(|local_address| {
-                            RootchainPeer::insert(
-                                &self.tracker_client,
-                                &self.chain_id,
-                                local_address,
-                                &self.external_endpoint,
-                            )
-                        }),
-                    )
-                    .await
-                    .map_err(|e| e.to_string()),
-                    BlockchainLayer::Leafchain => futures::future::try_join_all(
-                        listen_addresses.iter().map(|local_address| {
-                            LeafchainPeer::insert(
-                                &self.tracker_client,
-                                &self.chain_id,
-                                local_address,
-                                &self.external_endpoint,
-                            )
-                        }),
-                    )
-                    .await
-                    .map_err(|e| e.to_string()),
-                }
-            };
+            let res = futures::future::try_join_all(listen_addresses.iter().map(|local_address| {
+                self.tracker_client.insert(
+                    self.blockchain_layer,
+                    &self.chain_id,
+                    local_address,
+                    &self.external_endpoints,
+                )
+            }))
+            .await
+            .map_err(|e| e.to_string());
 
             if let Err(err) = res {
                 tracing::error!("Error occurs while advertising peers to Tracker, error: {err}");
@@ -0,0 +1,236 @@
+use std::{collections::BTreeSet, fmt, net::IpAddr, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error, error::Error, PeerAddress};
+
+/// Network from which an address is reachable, derived from the first
+/// component of the address.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressScope {
+    /// `0.0.0.0`, `::`, or an address without a host.
+    Unspecified,
+
+    /// `127.0.0.0/8` or `::1`.
+    Loopback,
+
+    /// `169.254.0.0/16` or `fe80::/10`.
+    LinkLocal,
+
+    /// `172.17.0.0/16`, the default Docker bridge network.
+    DockerBridge,
+
+    /// RFC 1918 ranges, or unique-local IPv6 addresses in `fc00::/7`.
+    Private,
+
+    /// `100.64.0.0/10`, the shared address space of carrier-grade NAT.
+    SharedNat,
+
+    /// Any other IP address.
+    Public,
+
+    /// Host name, resolved by the dialing peer.
+    Dns,
+}
+
+impl AddressScope {
+    pub const ALL: [Self; 8] = [
+        Self::Unspecified,
+        Self::Loopback,
+        Self::LinkLocal,
+        Self::DockerBridge,
+        Self::Private,
+        Self::SharedNat,
+        Self::Public,
+        Self::Dns,
+    ];
+
+    #[must_use]
+    pub fn of_ip(ip: IpAddr) -> Self {
+        let ip = match ip {
+            IpAddr::V6(ip) => ip.to_ipv4_mapped().map_or(IpAddr::V6(ip), IpAddr::V4),
+            ip @ IpAddr::V4(_) => ip,
+        };
+        match ip {
+            IpAddr::V4(ip) => {
+                let [a, b, ..] = ip.octets();
+                if ip.is_unspecified() {
+                    Self::Unspecified
+                } else if ip.is_loopback() {
+                    Self::Loopback
+                } else if ip.is_link_local() {
+                    Self::LinkLocal
+                } else if a == 172 && b == 17 {
+                    Self::DockerBridge
+                } else if ip.is_private() {
+                    Self::Private
+                } else if a == 100 && (b & 0b1100_0000) == 64 {
+                    Self::SharedNat
+                } else {
+                    Self::Public
+                }
+            }
+            IpAddr::V6(ip) => {
+                if ip.is_unspecified() {
+                    Self::Unspecified
+                } else if ip.is_loopback() {
+                    Self::Loopback
+                } else if ip.is_unicast_link_local() {
+                    Self::LinkLocal
+                } else if ip.is_unique_local() {
+                    Self::Private
+                } else {
+                    Self::Public
+                }
+            }
+        }
+    }
+
+    /// Whether the address is only reachable within a local network, such as
+    /// a LAN, a container network or behind carrier-grade NAT.
+    #[must_use]
+    pub const fn is_local_network(self) -> bool {
+        matches!(self, Self::LinkLocal | Self::DockerBridge | Self::Private | Self::SharedNat)
+    }
+
+    /// Whether an address of this scope may be reachable by a caller whose IP
+    /// address is of scope `caller`. Public addresses are reachable from
+    /// everywhere, loopback addresses from the same host only, and other
+    /// local addresses from callers which are not on the public internet
+    /// either.
+    #[must_use]
+    pub const fn is_reachable_from(self, caller: Self) -> bool {
+        match self {
+            Self::Public | Self::Dns => true,
+            Self::Unspecified => false,
+            Self::Loopback => matches!(caller, Self::Loopback),
+            Self::LinkLocal | Self::DockerBridge | Self::Private | Self::SharedNat => {
+                !matches!(caller, Self::Public | Self::Dns)
+            }
+        }
+    }
+}
+
+impl FromStr for AddressScope {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "unspecified" => Ok(Self::Unspecified),
+            "loopback" => Ok(Self::Loopback),
+            "link_local" => Ok(Self::LinkLocal),
+            "docker_bridge" => Ok(Self::DockerBridge),
+            "private" => Ok(Self::Private),
+            "shared_nat" => Ok(Self::SharedNat),
+            "public" => Ok(Self::Public),
+            "dns" => Ok(Self::Dns),
+            _ => error::UnknownValueSnafu { value: s.to_string() }.fail(),
+        }
+    }
+}
+
+impl fmt::Display for AddressScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = match self {
+            Self::Unspecified => "unspecified",
+            Self::Loopback => "loopback",
+            Self::LinkLocal => "link_local",
+            Self::DockerBridge => "docker_bridge",
+            Self::Private => "private",
+            Self::SharedNat => "shared_nat",
+            Self::Public => "public",
+            Self::Dns => "dns",
+        };
+        f.write_str(data)
+    }
+}
+
+/// Address scopes which are permitted, for example advertised by the sidecar
+/// or accepted by the tracker.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct AddressPolicy {
+    scopes: BTreeSet<AddressScope>,
+}
+
+impl AddressPolicy {
+    pub fn new<I>(scopes: I) -> Self
+    where
+        I: IntoIterator<Item = AddressScope>,
+    {
+        Self { scopes: scopes.into_iter().collect() }
+    }
+
+    /// Every scope except unspecified and loopback addresses.
+    #[must_use]
+    pub fn routable() -> Self {
+        Self::new(
+            AddressScope::ALL.into_iter().filter(|scope| {
+                !matches!(scope, AddressScope::Unspecified | AddressScope::Loopback)
+            }),
+        )
+    }
+
+    #[must_use]
+    pub fn with(mut self, scope: AddressScope) -> Self {
+        self.scopes.insert(scope);
+        self
+    }
+
+    #[must_use]
+    pub fn permits(&self, address: &PeerAddress) -> bool {
+        self.scopes.contains(&address.scope())
+    }
+
+    pub fn scopes(&self) -> impl Iterator<Item = AddressScope> + '_ {
+        self.scopes.iter().copied()
+    }
+}
+
+impl Default for AddressPolicy {
+    fn default() -> Self {
+        Self::routable()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(address: &str) -> AddressScope {
+        PeerAddress::from_str(address).unwrap().scope()
+    }
+
+    #[test]
+    fn test_scope() {
+        assert_eq!(scope("/ip4/127.0.0.2/tcp/30333"), AddressScope::Loopback);
+        assert_eq!(scope("/ip4/0.0.0.0/tcp/30333"), AddressScope::Unspecified);
+        assert_eq!(scope("/ip4/169.254.1.1/tcp/30333"), AddressScope::LinkLocal);
+        assert_eq!(scope("/ip4/172.17.0.2/tcp/30333"), AddressScope::DockerBridge);
+        assert_eq!(scope("/ip4/172.18.0.2/tcp/30333"), AddressScope::Private);
+        assert_eq!(scope("/ip4/192.168.1.1/tcp/30333"), AddressScope::Private);
+        assert_eq!(scope("/ip4/100.100.0.1/tcp/30333"), AddressScope::SharedNat);
+        assert_eq!(scope("/ip4/100.128.0.1/tcp/30333"), AddressScope::Public);
+        assert_eq!(scope("/ip6/::1/tcp/30333"), AddressScope::Loopback);
+        assert_eq!(scope("/ip6/fe80::1/tcp/30333"), AddressScope::LinkLocal);
+        assert_eq!(scope("/ip6/fd00::1/tcp/30333"), AddressScope::Private);
+        assert_eq!(scope("/ip6/::ffff:10.0.0.1/tcp/30333"), AddressScope::Private);
+        assert_eq!(scope("/ip6/2001:db8::1/tcp/30333"), AddressScope::Public);
+        assert_eq!(scope("/dns/node.example.com/tcp/30333"), AddressScope::Dns);
+    }
+
+    #[test]
+    fn test_policy() {
+        let policy = AddressPolicy::routable();
+        assert!(!policy.permits(&PeerAddress::from_str("/ip4/127.0.0.1/tcp/30333").unwrap()));
+        assert!(policy.permits(&PeerAddress::from_str("/ip4/10.0.0.1/tcp/30333").unwrap()));
+
+        let policy = AddressPolicy::new(["public".parse().unwrap(), "link-local".parse().unwrap()]);
+        assert_eq!(
+            policy.scopes().collect::<Vec<_>>(),
+            [AddressScope::LinkLocal, AddressScope::Public]
+        );
+        assert_eq!(serde_json::to_string(&policy).unwrap(), r#"["link_local","public"]"#);
+    }
+}
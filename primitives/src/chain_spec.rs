@@ -1,11 +1,14 @@
+use std::str::FromStr;
+
 use serde::Deserialize;
 use snafu::ResultExt;
 
-use crate::{error, Error};
+use crate::{error, Error, PeerAddress};
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct ChainSpec {
     id: String,
+    boot_nodes: Vec<PeerAddress>,
     body: Vec<u8>,
 }
 
@@ -14,6 +17,13 @@ impl ChainSpec {
     pub fn id(&self) -> &str {
         self.id.as_str()
     }
+
+    /// Static `bootNodes` of the spec, entries which are not valid peer
+    /// addresses are skipped.
+    #[must_use]
+    pub fn boot_nodes(&self) -> &[PeerAddress] {
+        self.boot_nodes.as_slice()
+    }
 }
 
 impl TryFrom<&[u8]> for ChainSpec {
@@ -24,6 +34,8 @@ impl TryFrom<&[u8]> for ChainSpec {
         #[derive(Debug, Deserialize)]
         struct Model {
             id: String,
+            #[serde(default, rename = "bootNodes")]
+            boot_nodes: Vec<String>,
         }
 
         let json: Model = serde_json::from_slice(body).context(error::DeserializeChainSpecSnafu)?;
@@ -32,7 +44,13 @@ impl TryFrom<&[u8]> for ChainSpec {
             return Err(Error::MissingChainId);
         }
 
-        Ok(Self { id: json.id, body: body.to_vec() })
+        let boot_nodes = json
+            .boot_nodes
+            .iter()
+            .filter_map(|boot_node| PeerAddress::from_str(boot_node).ok())
+            .collect();
+
+        Ok(Self { id: json.id, boot_nodes, body: body.to_vec() })
     }
 }
 
@@ -57,4 +75,20 @@ mod tests {
 
         assert!(chain_spec.is_err());
     }
+
+    #[test]
+    fn test_boot_nodes() {
+        let chain_spec = ChainSpec::try_from(
+            br#"{
+                "id": "lmt_testnet",
+                "bootNodes": [
+                    "/ip4/10.0.0.1/tcp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo",
+                    "not an address"
+                ]
+            }"#
+            .as_ref(),
+        )
+        .unwrap();
+        assert_eq!(chain_spec.boot_nodes().len(), 1);
+    }
 }
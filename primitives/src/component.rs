@@ -0,0 +1,123 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use snafu::OptionExt;
+#[cfg(feature = "openapi")]
+use utoipa::ToSchema;
+
+use crate::{error, error::Error, BlockchainLayer};
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BlockchainComponent {
+    Rootchain,
+    Leafchain,
+    Bridge,
+    OfflineWorker,
+}
+
+impl BlockchainComponent {
+    /// Whether the component is a chain, whose nodes are tracked as peers
+    /// instead of as components.
+    #[must_use]
+    pub const fn is_chain(self) -> bool {
+        matches!(self, Self::Rootchain | Self::Leafchain)
+    }
+}
+
+impl FromStr for BlockchainComponent {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rootchain" => Ok(Self::Rootchain),
+            "leafchain" => Ok(Self::Leafchain),
+            "bridge" => Ok(Self::Bridge),
+            "offline_worker" => Ok(Self::OfflineWorker),
+            _ => error::UnknownValueSnafu { value: s.to_string() }.fail(),
+        }
+    }
+}
+
+impl fmt::Display for BlockchainComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = match self {
+            Self::Rootchain => "Rootchain",
+            Self::Leafchain => "Leafchain",
+            Self::Bridge => "Bridge",
+            Self::OfflineWorker => "Offline Worker",
+        };
+        f.write_str(data)
+    }
+}
+
+/// Chain a component serves, written as `<layer>:<chain_id>`, e.g.
+/// `leafchain:thx-mainnet`.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ServedChain {
+    pub layer: BlockchainLayer,
+
+    pub chain_id: String,
+}
+
+impl FromStr for ServedChain {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (layer, chain_id) = s
+            .split_once(':')
+            .filter(|(_, chain_id)| !chain_id.is_empty())
+            .context(error::UnknownValueSnafu { value: s.to_string() })?;
+        Ok(Self { layer: layer.parse()?, chain_id: chain_id.to_string() })
+    }
+}
+
+impl fmt::Display for ServedChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let layer = match self.layer {
+            BlockchainLayer::Rootchain => "rootchain",
+            BlockchainLayer::Leafchain => "leafchain",
+        };
+        write!(f, "{layer}:{}", self.chain_id)
+    }
+}
+
+/// A component registered to the tracker, e.g. a bridge relayer serving a
+/// rootchain and a leafchain.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ComponentRegistration {
+    pub kind: BlockchainComponent,
+
+    /// Unique among the components of the same kind, a registration replaces
+    /// the previous one of the same name.
+    pub name: String,
+
+    /// URLs the component is reachable on, e.g. its metrics endpoint.
+    #[serde(default)]
+    pub endpoints: Vec<String>,
+
+    #[serde(default)]
+    pub chains: Vec<ServedChain>,
+
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_served_chain_from_str() {
+        let chain = ServedChain::from_str("leafchain:thx-mainnet").unwrap();
+        assert_eq!(chain.layer, BlockchainLayer::Leafchain);
+        assert_eq!(chain.chain_id, "thx-mainnet");
+        assert_eq!(chain.to_string(), "leafchain:thx-mainnet");
+
+        for s in ["leafchain", "leafchain:", "bridge:thx-mainnet"] {
+            assert!(ServedChain::from_str(s).is_err(), "{s}");
+        }
+    }
+}
@@ -1,33 +1,141 @@
-use std::{fmt, str::FromStr};
+use std::{collections::BTreeMap, fmt, net::Ipv6Addr, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 use snafu::OptionExt;
+#[cfg(feature = "openapi")]
+use utoipa::ToSchema;
 
 use crate::{error, error::Error};
 
-#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+/// Transport a node listens on, identified by the components following the
+/// host of a listen address.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Transport {
+    /// `/tcp/<port>`
+    Tcp,
+
+    /// `/tcp/<port>/ws`
+    Ws,
+
+    /// `/tcp/<port>/wss` or `/tcp/<port>/tls/ws`
+    Wss,
+
+    /// `/udp/<port>/quic` or `/udp/<port>/quic-v1`
+    Quic,
+}
+
+impl FromStr for Transport {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "tcp" => Ok(Self::Tcp),
+            "ws" => Ok(Self::Ws),
+            "wss" => Ok(Self::Wss),
+            "quic" => Ok(Self::Quic),
+            _ => error::UnknownValueSnafu { value: s.to_string() }.fail(),
+        }
+    }
+}
+
+impl fmt::Display for Transport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = match self {
+            Self::Tcp => "tcp",
+            Self::Ws => "ws",
+            Self::Wss => "wss",
+            Self::Quic => "quic",
+        };
+        f.write_str(data)
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
 pub struct ExternalEndpoint {
     pub host: String,
+
+    /// External port of TCP, and of every transport without a port in
+    /// `transport_ports`.
     pub port: u16,
+
+    /// External ports of transports which are mapped to another port than
+    /// `port`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub transport_ports: BTreeMap<Transport, u16>,
+}
+
+impl ExternalEndpoint {
+    #[must_use]
+    pub const fn new(host: String, port: u16) -> Self {
+        Self { host, port, transport_ports: BTreeMap::new() }
+    }
+
+    #[must_use]
+    pub fn with_transport_port(mut self, transport: Transport, port: u16) -> Self {
+        self.transport_ports.insert(transport, port);
+        self
+    }
+
+    /// Returns the external port of `transport`.
+    #[must_use]
+    pub fn port_of(&self, transport: Transport) -> u16 {
+        self.transport_ports.get(&transport).copied().unwrap_or(self.port)
+    }
 }
 
 impl FromStr for ExternalEndpoint {
     type Err = Error;
 
+    /// Parses `<host>:<port>`, IPv6 hosts are enclosed in brackets, e.g.
+    /// `[2001:db8::1]:30333`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split(':').collect();
-        if parts.len() != 2 {
-            return error::InvalidEndpointSnafu { value: s.to_string() }.fail();
-        }
-        let host = parts[0].to_string();
+        let (host, port) =
+            s.rsplit_once(':').context(error::InvalidEndpointSnafu { value: s.to_string() })?;
+        let host = match host.strip_prefix('[').and_then(|host| host.strip_suffix(']')) {
+            Some(host) if host.parse::<Ipv6Addr>().is_ok() => host,
+            Some(_) => return error::InvalidEndpointSnafu { value: s.to_string() }.fail(),
+            None if host.is_empty() || host.contains(':') => {
+                return error::InvalidEndpointSnafu { value: s.to_string() }.fail()
+            }
+            None => host,
+        };
         let port =
-            parts[1].parse().ok().context(error::InvalidEndpointSnafu { value: s.to_string() })?;
-        Ok(Self { host, port })
+            port.parse().ok().context(error::InvalidEndpointSnafu { value: s.to_string() })?;
+        Ok(Self::new(host.to_string(), port))
     }
 }
 
 impl fmt::Display for ExternalEndpoint {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}:{}", self.host, self.port)
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        for (s, host, port) in [
+            ("node.example.com:30333", "node.example.com", 30333),
+            ("203.0.113.7:30333", "203.0.113.7", 30333),
+            ("[2001:db8::7]:30333", "2001:db8::7", 30333),
+        ] {
+            let endpoint = ExternalEndpoint::from_str(s).unwrap();
+            assert_eq!(endpoint, ExternalEndpoint::new(host.to_string(), port));
+            assert_eq!(endpoint.to_string(), s);
+        }
+
+        for s in ["node.example.com", "2001:db8::7:30333", "[node]:30333", ":30333", "node:port"] {
+            assert!(ExternalEndpoint::from_str(s).is_err(), "{s}");
+        }
     }
 }
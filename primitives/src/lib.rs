@@ -142,42 +142,48 @@
     allow(clippy::module_name_repetitions,)
 )]
 
+mod address_scope;
 mod chain_spec;
+mod component;
 mod error;
 mod external_endpoint;
 mod peer_address;
+mod peer_metadata;
 
-use std::fmt;
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "openapi")]
+use utoipa::ToSchema;
 
 pub use self::{
-    chain_spec::ChainSpec, error::Error, external_endpoint::ExternalEndpoint,
+    address_scope::{AddressPolicy, AddressScope},
+    chain_spec::ChainSpec,
+    component::{BlockchainComponent, ComponentRegistration, ServedChain},
+    error::Error,
+    external_endpoint::{ExternalEndpoint, Transport},
     peer_address::PeerAddress,
+    peer_metadata::{NodeRole, PeerMetadata, SyncState},
 };
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub enum BlockchainComponent {
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BlockchainLayer {
     Rootchain,
     Leafchain,
-    Bridge,
-    OfflineWorker,
 }
 
-impl fmt::Display for BlockchainComponent {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let data = match self {
-            Self::Rootchain => "Rootchain",
-            Self::Leafchain => "Leafchain",
-            Self::Bridge => "Bridge",
-            Self::OfflineWorker => "Offline Worker",
-        };
-        f.write_str(data)
-    }
-}
+impl FromStr for BlockchainLayer {
+    type Err = Error;
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub enum BlockchainLayer {
-    Rootchain,
-    Leafchain,
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rootchain" => Ok(Self::Rootchain),
+            "leafchain" => Ok(Self::Leafchain),
+            _ => error::UnknownValueSnafu { value: s.to_string() }.fail(),
+        }
+    }
 }
 
 impl fmt::Display for BlockchainLayer {
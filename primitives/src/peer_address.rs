@@ -1,45 +1,112 @@
 use std::{
     fmt,
-    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    net::{IpAddr, SocketAddr},
     str::FromStr,
 };
 
 use sc_network::multiaddr::Protocol;
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
+#[cfg(feature = "openapi")]
+use utoipa::ToSchema;
 
-use crate::{error, error::Error, ExternalEndpoint};
+use crate::{error, error::Error, AddressScope, ExternalEndpoint, Transport};
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "openapi",
+    derive(ToSchema),
+    schema(
+        value_type = String,
+        example = "/ip4/10.0.0.1/tcp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo"
+    )
+)]
 pub struct PeerAddress(pub sc_network::Multiaddr);
 
 impl PeerAddress {
+    #[must_use]
+    pub fn scope(&self) -> AddressScope {
+        match self.0.iter().next() {
+            Some(Protocol::Ip4(ip)) => AddressScope::of_ip(ip.into()),
+            Some(Protocol::Ip6(ip)) => AddressScope::of_ip(ip.into()),
+            Some(
+                Protocol::Dns(_) | Protocol::Dns4(_) | Protocol::Dns6(_) | Protocol::Dnsaddr(_),
+            ) => AddressScope::Dns,
+            _ => AddressScope::Unspecified,
+        }
+    }
+
     #[must_use]
     pub fn is_loopback(&self) -> bool {
-        let is_ipv4_loopback =
-            self.0.iter().take(1).all(|component| component == Protocol::Ip4(Ipv4Addr::LOCALHOST));
-        let is_ipv6_loopback =
-            self.0.iter().take(1).all(|component| component == Protocol::Ip6(Ipv6Addr::LOCALHOST));
-        is_ipv4_loopback || is_ipv6_loopback
+        self.scope() == AddressScope::Loopback
+    }
+
+    /// Returns the transport of the address, `None` if the address does not
+    /// start with a host followed by a known transport.
+    #[must_use]
+    pub fn transport(&self) -> Option<Transport> {
+        // `tls` and `quic-v1` are matched by name, as they are missing from
+        // the multiaddr version of older `sc-network` releases
+        let is = |protocol: &Protocol<'_>, name: &str| protocol.to_string() == name;
+
+        let mut components = self.0.iter().skip(1);
+        match components.next()? {
+            Protocol::Tcp(_) => match components.next() {
+                Some(Protocol::Ws(_)) => Some(Transport::Ws),
+                Some(Protocol::Wss(_)) => Some(Transport::Wss),
+                Some(protocol) if is(&protocol, "/tls") => {
+                    matches!(components.next(), Some(Protocol::Ws(_))).then_some(Transport::Wss)
+                }
+                _ => Some(Transport::Tcp),
+            },
+            Protocol::Udp(_) => match components.next()? {
+                Protocol::Quic => Some(Transport::Quic),
+                protocol if is(&protocol, "/quic-v1") => Some(Transport::Quic),
+                _ => None,
+            },
+            _ => None,
+        }
     }
 
+    /// Rewrites the address to be reached via `endpoint`, the host is replaced
+    /// by the external host and the port by the external port of the
+    /// transport. Host names keep the IP version of `/dns4` and `/dns6`
+    /// addresses. Returns `None` if the address does not start with a host
+    /// followed by a known transport, see [`Self::transport`].
     #[must_use]
-    pub fn exposed(&self, ExternalEndpoint { host, port }: &ExternalEndpoint) -> Option<Self> {
+    pub fn exposed(&self, endpoint: &ExternalEndpoint) -> Option<Self> {
+        let transport = self.transport()?;
+        let port = endpoint.port_of(transport);
+        let host = match endpoint.host.parse::<IpAddr>() {
+            Ok(IpAddr::V4(ip)) => Protocol::Ip4(ip),
+            Ok(IpAddr::V6(ip)) => Protocol::Ip6(ip),
+            Err(_) => {
+                let name = endpoint.host.clone().into();
+                match self.0.iter().next()? {
+                    Protocol::Dns4(_) => Protocol::Dns4(name),
+                    Protocol::Dns6(_) => Protocol::Dns6(name),
+                    _ => Protocol::Dns(name),
+                }
+            }
+        };
+
         let new_addr = self
             .0
             .replace(0, |protocol| {
-                if matches!(protocol, Protocol::Ip4(..) | Protocol::Ip6(..)) {
-                    Some(Protocol::Dns(host.to_string().into()))
-                } else {
-                    None
-                }
+                matches!(
+                    protocol,
+                    Protocol::Ip4(..)
+                        | Protocol::Ip6(..)
+                        | Protocol::Dns(..)
+                        | Protocol::Dns4(..)
+                        | Protocol::Dns6(..)
+                )
+                .then(|| host.clone())
             })?
-            .replace(1, |protocol| {
-                if matches!(protocol, Protocol::Tcp(..)) {
-                    Some(Protocol::Tcp(*port))
-                } else {
-                    None
-                }
+            .replace(1, |protocol| match protocol {
+                Protocol::Tcp(_) => Some(Protocol::Tcp(port)),
+                Protocol::Udp(_) => Some(Protocol::Udp(port)),
+                _ => None,
             })?;
 
         Some(Self(new_addr))
@@ -66,6 +133,22 @@ impl PeerAddress {
         }
     }
 
+    /// Returns the address as observed from `source`, the IP address a peer
+    /// connected from. Only addresses of a local network are rewritten, see
+    /// [`AddressScope::is_local_network`], and only to a public source, as a
+    /// private source is the address of a NAT of the local network, e.g. a
+    /// Kubernetes node. `None` if the address would not change.
+    #[must_use]
+    pub fn observed(&self, source: IpAddr) -> Option<Self> {
+        if !self.scope().is_local_network() || AddressScope::of_ip(source) != AddressScope::Public {
+            return None;
+        }
+
+        let mut observed = self.clone();
+        observed.try_make_public(SocketAddr::new(source, 0));
+        (observed != *self).then_some(observed)
+    }
+
     #[must_use]
     pub fn id(&self) -> String {
         for protocol in &self.0 {
@@ -98,7 +181,7 @@ impl fmt::Display for PeerAddress {
 mod tests {
     use std::str::FromStr;
 
-    use crate::{ExternalEndpoint, PeerAddress};
+    use crate::{ExternalEndpoint, PeerAddress, Transport};
 
     #[test]
     fn test_exposed() {
@@ -106,12 +189,91 @@ mod tests {
             "/ip4/127.0.0.1/tcp/50001/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo",
         )
         .unwrap();
-        let exposed = addr.exposed(&ExternalEndpoint {
-            host: "node.testnet.thxnet.org".to_string(),
-            port: 54321,
-        });
+        let exposed =
+            addr.exposed(&ExternalEndpoint::new("node.testnet.thxnet.org".to_string(), 54321));
         let expected = "/dns/node.testnet.thxnet.org/tcp/54321/p2p/\
                         12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo";
         assert_eq!(expected, exposed.unwrap().to_string());
     }
+
+    #[test]
+    fn test_exposed_transports() {
+        let peer_id = "12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo";
+        let listen_hosts = [
+            ("/ip4/10.0.0.1", "/dns"),
+            ("/ip6/fd00::1", "/dns"),
+            ("/dns/node.internal", "/dns"),
+            ("/dns4/node.internal", "/dns4"),
+            ("/dns6/node.internal", "/dns6"),
+        ];
+        // listen transport, external transport with the `{port}` placeholder, transport
+        let transports = [
+            ("/tcp/30333", "/tcp/{port}", Transport::Tcp),
+            ("/tcp/30334/ws", "/tcp/{port}/ws", Transport::Ws),
+            ("/tcp/30335/wss", "/tcp/{port}/wss", Transport::Wss),
+            ("/tcp/30335/tls/ws", "/tcp/{port}/tls/ws", Transport::Wss),
+            ("/udp/30336/quic", "/udp/{port}/quic", Transport::Quic),
+            ("/udp/30336/quic-v1", "/udp/{port}/quic-v1", Transport::Quic),
+        ];
+        let endpoints = [
+            ("node.example.com", None),
+            ("203.0.113.7", Some("/ip4/203.0.113.7")),
+            ("2001:db8::7", Some("/ip6/2001:db8::7")),
+        ];
+
+        for (listen_host, name_protocol) in listen_hosts {
+            for (listen_transport, external_transport, transport) in transports {
+                for (external_host, ip_host) in endpoints {
+                    let address = PeerAddress::from_str(&format!(
+                        "{listen_host}{listen_transport}/p2p/{peer_id}"
+                    ))
+                    .unwrap();
+                    assert_eq!(address.transport(), Some(transport), "{address}");
+
+                    let expected_host = ip_host
+                        .map_or_else(|| format!("{name_protocol}/{external_host}"), String::from);
+                    let endpoint = ExternalEndpoint::new(external_host.to_string(), 40000);
+                    let expected = format!("{expected_host}{external_transport}/p2p/{peer_id}");
+                    assert_eq!(
+                        address.exposed(&endpoint).unwrap().to_string(),
+                        expected.replace("{port}", "40000"),
+                        "{address}"
+                    );
+
+                    let endpoint = endpoint.with_transport_port(transport, 40001);
+                    assert_eq!(
+                        address.exposed(&endpoint).unwrap().to_string(),
+                        expected.replace("{port}", "40001"),
+                        "{address}"
+                    );
+                }
+            }
+        }
+
+        let endpoint = ExternalEndpoint::new("node.example.com".to_string(), 40000);
+        for address in ["/ip4/10.0.0.1/udp/30333", "/ip4/10.0.0.1", "/p2p/{peer_id}"] {
+            let address = PeerAddress::from_str(&address.replace("{peer_id}", peer_id)).unwrap();
+            assert_eq!(address.transport(), None, "{address}");
+            assert_eq!(address.exposed(&endpoint), None, "{address}");
+        }
+    }
+
+    #[test]
+    fn test_observed() {
+        let peer_id = "12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo";
+        let private =
+            PeerAddress::from_str(&format!("/ip4/10.0.0.1/tcp/30333/p2p/{peer_id}")).unwrap();
+        let public =
+            PeerAddress::from_str(&format!("/ip4/8.8.8.8/tcp/30333/p2p/{peer_id}")).unwrap();
+
+        let observed = private.observed("203.0.113.7".parse().unwrap()).unwrap();
+        assert_eq!(observed.to_string(), format!("/ip4/203.0.113.7/tcp/30333/p2p/{peer_id}"));
+        assert_eq!(private.observed("127.0.0.1".parse().unwrap()), None);
+        assert_eq!(private.observed("10.0.0.1".parse().unwrap()), None);
+        // sources of the local network are NATs, e.g. a Kubernetes node
+        assert_eq!(private.observed("192.168.1.5".parse().unwrap()), None);
+        assert_eq!(private.observed("100.64.0.1".parse().unwrap()), None);
+        assert_eq!(private.observed("172.17.0.1".parse().unwrap()), None);
+        assert_eq!(public.observed("203.0.113.7".parse().unwrap()), None);
+    }
 }
@@ -0,0 +1,114 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "openapi")]
+use utoipa::ToSchema;
+
+use crate::{error, error::Error};
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum NodeRole {
+    Validator,
+    Collator,
+    Archive,
+    Full,
+}
+
+impl FromStr for NodeRole {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "validator" => Ok(Self::Validator),
+            "collator" => Ok(Self::Collator),
+            "archive" => Ok(Self::Archive),
+            "full" => Ok(Self::Full),
+            _ => error::UnknownValueSnafu { value: s.to_string() }.fail(),
+        }
+    }
+}
+
+impl fmt::Display for NodeRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = match self {
+            Self::Validator => "validator",
+            Self::Collator => "collator",
+            Self::Archive => "archive",
+            Self::Full => "full",
+        };
+        f.write_str(data)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SyncState {
+    Syncing,
+    Idle,
+}
+
+/// Self-reported information about a node, sent along with its addresses.
+///
+/// Every field is optional, as older clients do not send any of them.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct PeerMetadata {
+    #[serde(default)]
+    pub role: Option<NodeRole>,
+
+    #[serde(default)]
+    pub node_name: Option<String>,
+
+    #[serde(default)]
+    pub client_version: Option<String>,
+
+    #[serde(default)]
+    pub best_block_number: Option<u64>,
+
+    #[serde(default)]
+    pub finalized_block_number: Option<u64>,
+
+    #[serde(default)]
+    pub sync_state: Option<SyncState>,
+
+    /// Number of peers the node is connected to.
+    #[serde(default)]
+    pub peer_count: Option<u32>,
+
+    /// Hex encoded hash of the block at `finalized_block_number`.
+    #[serde(default)]
+    pub finalized_block_hash: Option<String>,
+
+    /// Peer IDs the node is connected to.
+    #[serde(default)]
+    pub connected_peers: Vec<String>,
+
+    /// Peer IDs configured as reserved peers of the node.
+    #[serde(default)]
+    pub reserved_peers: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn node_role_round_trips_through_string() {
+        for role in [NodeRole::Validator, NodeRole::Collator, NodeRole::Archive, NodeRole::Full] {
+            assert_eq!(NodeRole::from_str(&role.to_string()).unwrap(), role);
+        }
+        assert_eq!(NodeRole::from_str("Archive").unwrap(), NodeRole::Archive);
+        assert!(NodeRole::from_str("light").is_err());
+    }
+
+    #[test]
+    fn metadata_fields_are_optional() {
+        let metadata: PeerMetadata = serde_json::from_str(r#"{"role":"collator"}"#).unwrap();
+        assert_eq!(metadata, PeerMetadata { role: Some(NodeRole::Collator), ..Default::default() });
+    }
+}
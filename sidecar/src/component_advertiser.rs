@@ -0,0 +1,56 @@
+use kallax_primitives::ComponentRegistration;
+use kallax_tracker_grpc_client::{Client as TrackerClient, Tracker};
+
+use crate::error::{Error, Result};
+
+/// Keeps a component which is not a node of a chain, e.g. a bridge relayer,
+/// registered to the tracker.
+#[derive(Debug)]
+pub struct ComponentAdvertiser {
+    registration: ComponentRegistration,
+
+    tracker_client: TrackerClient,
+
+    /// Lease of the registration, `None` until the component is registered.
+    lease_id: Option<String>,
+}
+
+impl ComponentAdvertiser {
+    #[inline]
+    #[must_use]
+    pub const fn new(registration: ComponentRegistration, tracker_client: TrackerClient) -> Self {
+        Self { registration, tracker_client, lease_id: None }
+    }
+
+    /// Keeps the lease of the registration alive, or registers the component
+    /// under a new lease if the lease expired.
+    pub async fn execute(&mut self) -> Result<()> {
+        if let Some(lease_id) = self.lease_id.as_deref() {
+            match self.tracker_client.keep_alive_component(lease_id).await {
+                Ok(_) => return Ok(()),
+                Err(err) if err.is_lease_expired() => {
+                    tracing::info!("Lease `{lease_id}` expired, register again");
+                }
+                Err(err) => {
+                    return Err(Error::KeepAliveComponent { message: err.to_string() });
+                }
+            }
+        }
+
+        self.lease_id = None;
+        let lease = self
+            .tracker_client
+            .register_component(&self.registration)
+            .await
+            .map_err(|err| Error::RegisterComponent { message: err.to_string() })?;
+        tracing::info!(
+            "Registered {} `{}` with lease `{}`, TTL: {:?}",
+            self.registration.kind,
+            self.registration.name,
+            lease.lease_id,
+            lease.ttl
+        );
+        self.lease_id = Some(lease.lease_id);
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use std::{net::SocketAddr, time::Instant};
 
 use axum::{extract::Extension, http::StatusCode, response::IntoResponse, routing, Json, Router};
+use kallax_primitives::AddressPolicy;
 use serde::Serialize;
 
 use crate::peer_discoverer::{DiagnosticSnapshot, ErrorEntry, SharedDiagnostic, SharedErrorRing};
@@ -13,8 +14,9 @@ pub struct SidecarDiagnosticConfig {
     pub rootchain_id: String,
     pub leafchain_id: Option<String>,
     pub allow_loopback_ip: bool,
-    pub external_rootchain_p2p_endpoint: Option<String>,
-    pub external_leafchain_p2p_endpoint: Option<String>,
+    pub advertise_address_scopes: AddressPolicy,
+    pub external_rootchain_p2p_endpoints: Vec<String>,
+    pub external_leafchain_p2p_endpoints: Vec<String>,
     pub polling_interval_ms: u64,
     pub detected_public_ip: Option<String>,
 }
@@ -26,4 +26,12 @@ pub enum Error {
 
     #[snafu(display("Error occurs while fetching peer addresses from Tracker, error: {source}"))]
     GetPeerAddressesFromTracker { source: kallax_tracker_grpc_client::Error },
+
+    #[snafu(display("Error occurs while registering component to Tracker, error: {message}"))]
+    RegisterComponent { message: String },
+
+    #[snafu(display(
+        "Error occurs while keeping alive the lease of component on Tracker, error: {message}"
+    ))]
+    KeepAliveComponent { message: String },
 }
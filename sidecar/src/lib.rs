@@ -142,6 +142,7 @@
     allow(clippy::multiple_crate_versions,)
 )]
 
+mod component_advertiser;
 mod diagnostic;
 mod error;
 mod peer_discoverer;
@@ -149,13 +150,18 @@ mod peer_discoverer;
 use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 use futures::{future, future::Either, FutureExt, StreamExt};
-use kallax_primitives::{BlockchainLayer, ExternalEndpoint};
+use kallax_primitives::{
+    AddressPolicy, BlockchainLayer, ComponentRegistration, ExternalEndpoint, NodeRole,
+};
 use kallax_tracker_grpc_client::{Client as TrackerClient, Config as TrackerClientConfig};
 use snafu::ResultExt;
 use tokio::sync::Mutex;
 
 pub use self::error::{Error, Result};
-use self::peer_discoverer::{ErrorRing, PeerDiscoverer};
+use self::{
+    component_advertiser::ComponentAdvertiser,
+    peer_discoverer::{ErrorRing, PeerDiscoverer},
+};
 
 #[derive(Clone, Debug)]
 pub struct Config {
@@ -169,13 +175,36 @@ pub struct Config {
 
     pub allow_loopback_ip: bool,
 
-    pub external_rootchain_p2p_endpoint: Option<ExternalEndpoint>,
+    /// Scopes of the local listen addresses which are registered to the
+    /// tracker.
+    pub advertise_address_scopes: AddressPolicy,
 
-    pub external_leafchain_p2p_endpoint: Option<ExternalEndpoint>,
+    pub external_rootchain_p2p_endpoints: Vec<ExternalEndpoint>,
+
+    pub external_leafchain_p2p_endpoints: Vec<ExternalEndpoint>,
 
     pub diagnostic_listen_address: SocketAddr,
 
     pub detected_public_ip: Option<String>,
+
+    pub node_name: Option<String>,
+
+    pub rootchain_node_role: Option<NodeRole>,
+
+    pub leafchain_node_role: Option<NodeRole>,
+}
+
+/// Configuration of the sidecar of a component which is not a node of a chain,
+/// e.g. a bridge relayer or an offline worker.
+#[derive(Clone, Debug)]
+pub struct ComponentConfig {
+    pub tracker_grpc_endpoint: http::Uri,
+
+    /// Interval of keeping the registration alive, must be shorter than the
+    /// TTL of the tracker.
+    pub keep_alive_interval: Duration,
+
+    pub registration: ComponentRegistration,
 }
 
 #[derive(Clone, Debug)]
@@ -196,10 +225,14 @@ pub async fn serve(config: Config) -> Result<()> {
         rootchain_endpoint,
         leafchain_endpoint,
         allow_loopback_ip,
-        external_rootchain_p2p_endpoint,
-        external_leafchain_p2p_endpoint,
+        advertise_address_scopes,
+        external_rootchain_p2p_endpoints,
+        external_leafchain_p2p_endpoints,
         diagnostic_listen_address,
         detected_public_ip,
+        node_name,
+        rootchain_node_role,
+        leafchain_node_role,
     } = config;
 
     let tracker_client =
@@ -218,12 +251,15 @@ pub async fn serve(config: Config) -> Result<()> {
         rootchain_id: rootchain_endpoint.chain_id.clone(),
         leafchain_id: leafchain_endpoint.as_ref().map(|e| e.chain_id.clone()),
         allow_loopback_ip,
-        external_rootchain_p2p_endpoint: external_rootchain_p2p_endpoint
-            .as_ref()
-            .map(ToString::to_string),
-        external_leafchain_p2p_endpoint: external_leafchain_p2p_endpoint
-            .as_ref()
-            .map(ToString::to_string),
+        advertise_address_scopes: advertise_address_scopes.clone(),
+        external_rootchain_p2p_endpoints: external_rootchain_p2p_endpoints
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+        external_leafchain_p2p_endpoints: external_leafchain_p2p_endpoints
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
         polling_interval_ms: polling_interval.as_millis() as u64,
         detected_public_ip: detected_public_ip.clone(),
     };
@@ -260,10 +296,13 @@ pub async fn serve(config: Config) -> Result<()> {
                     rootchain_endpoint.websocket_endpoint,
                     tracker_client.clone(),
                     allow_loopback_ip,
-                    external_rootchain_p2p_endpoint,
+                    advertise_address_scopes.clone(),
+                    external_rootchain_p2p_endpoints,
                     rootchain_diag,
                     detected_public_ip.clone(),
                     rootchain_err,
+                    node_name.clone(),
+                    rootchain_node_role,
                 );
                 let mut leafchain_peer_discoverer = leafchain_endpoint.map(move |endpoint| {
                     PeerDiscoverer::new(
@@ -272,10 +311,13 @@ pub async fn serve(config: Config) -> Result<()> {
                         endpoint.websocket_endpoint,
                         tracker_client,
                         allow_loopback_ip,
-                        external_leafchain_p2p_endpoint,
+                        advertise_address_scopes,
+                        external_leafchain_p2p_endpoints,
                         leafchain_diag,
                         detected_public_ip,
                         leafchain_err,
+                        node_name,
+                        leafchain_node_role,
                     )
                 });
 
@@ -324,3 +366,50 @@ pub async fn serve(config: Config) -> Result<()> {
 
     Ok(())
 }
+
+/// Runs the sidecar of a component, which keeps the component registered to
+/// the tracker until shutdown.
+///
+/// # Errors
+///
+/// This function returns an error if the server is not connected.
+pub async fn serve_component(config: ComponentConfig) -> Result<()> {
+    let ComponentConfig { tracker_grpc_endpoint, keep_alive_interval, registration } = config;
+
+    let tracker_client =
+        TrackerClient::new(TrackerClientConfig { grpc_endpoint: tracker_grpc_endpoint.clone() })
+            .await
+            .with_context(|_| error::ConnectTrackerSnafu { uri: tracker_grpc_endpoint.clone() })?;
+
+    let lifecycle_manager = sigfinn::LifecycleManager::new();
+    let _handle = lifecycle_manager.spawn("Component Sidecar", move |shutdown| async move {
+        let mut advertiser = ComponentAdvertiser::new(registration, tracker_client);
+        let mut shutdown_signal = shutdown.into_stream();
+
+        loop {
+            if let Err(err) = advertiser.execute().await {
+                tracing::warn!("Error occurs while advertising component, error: {err}");
+            }
+
+            if let Either::Left(_) = future::select(
+                shutdown_signal.next().boxed(),
+                tokio::time::sleep(keep_alive_interval).boxed(),
+            )
+            .await
+            {
+                tracing::info!("Shutting down");
+                break;
+            }
+        }
+
+        tracing::info!("Component sidecar is down");
+
+        sigfinn::ExitStatus::Success
+    });
+
+    if let Ok(Err(err)) = lifecycle_manager.serve().await {
+        return Err(err);
+    }
+
+    Ok(())
+}
@@ -1,15 +1,18 @@
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
     str::FromStr,
     sync::Arc,
 };
 
-use kallax_primitives::{BlockchainLayer, ExternalEndpoint, PeerAddress};
-use kallax_tracker_grpc_client::{Client as TrackerClient, LeafchainPeer, RootchainPeer};
+use kallax_primitives::{
+    AddressPolicy, BlockchainLayer, ExternalEndpoint, NodeRole, PeerAddress, PeerMetadata,
+    SyncState,
+};
+use kallax_tracker_grpc_client::{Client as TrackerClient, PeerAddresses, Tracker};
 use serde::Serialize;
 use snafu::ResultExt;
 use substrate_rpc_client::{
-    ws_client as connect_substrate_websocket_endpoint, SystemApi, WsClient,
+    ws_client as connect_substrate_websocket_endpoint, ChainApi, SystemApi, WsClient,
 };
 use tokio::sync::Mutex;
 
@@ -20,8 +23,11 @@ use crate::{
 
 type Hash = sp_core::H256;
 type BlockNumber = u128;
+type Header = serde_json::Value;
+type SignedBlock = serde_json::Value;
 
-/// Consecutive polling cycles a peer must be absent before removal.
+/// Consecutive polling cycles a peer must be absent before removal, unless the
+/// tracker reports it as tombstoned.
 /// With POLLING_INTERVAL=1s and tracker TTL=120s, 60 cycles = 60s grace period.
 const STALE_THRESHOLD: u32 = 60;
 
@@ -50,8 +56,13 @@ pub struct IdentityInfo {
 pub struct RegistrationInfo {
     pub chain_id: String,
     pub blockchain_layer: String,
-    pub external_endpoint: Option<String>,
+    pub external_endpoints: Vec<String>,
     pub registered_addresses_count: usize,
+    pub metadata: PeerMetadata,
+    /// Warnings returned by the tracker, e.g. about a shared node key.
+    pub warnings: Vec<String>,
+    /// Whether the tracker stopped handing out this node for maintenance.
+    pub is_draining: bool,
 }
 
 #[derive(Clone, Debug, Default, Serialize)]
@@ -121,6 +132,16 @@ impl ErrorRing {
     }
 }
 
+#[derive(Clone, Debug)]
+struct TrackerLease {
+    lease_id: String,
+
+    /// Registered addresses, a change requires a new registration.
+    addresses: BTreeSet<PeerAddress>,
+
+    warnings: Vec<String>,
+}
+
 pub type SharedErrorRing = Arc<Mutex<ErrorRing>>;
 pub type SharedDiagnostic = Arc<Mutex<Option<DiagnosticSnapshot>>>;
 
@@ -138,7 +159,10 @@ pub struct PeerDiscoverer {
 
     allow_loopback_ip: bool,
 
-    external_endpoint: Option<ExternalEndpoint>,
+    /// Scopes of the local listen addresses which are registered.
+    advertise_address_scopes: AddressPolicy,
+
+    external_endpoints: Vec<ExternalEndpoint>,
 
     stale_counters: HashMap<String, u32>,
 
@@ -153,6 +177,14 @@ pub struct PeerDiscoverer {
     cycle_count: u64,
 
     cached_peer_id: Option<String>,
+
+    /// Lease of the addresses registered to the tracker.
+    lease: Option<TrackerLease>,
+
+    node_name: Option<String>,
+
+    /// Overrides the role derived from `system_nodeRoles`.
+    node_role: Option<NodeRole>,
 }
 
 impl PeerDiscoverer {
@@ -165,10 +197,13 @@ impl PeerDiscoverer {
         substrate_websocket_endpoint: http::Uri,
         tracker_client: TrackerClient,
         allow_loopback_ip: bool,
-        external_endpoint: Option<ExternalEndpoint>,
+        advertise_address_scopes: AddressPolicy,
+        external_endpoints: Vec<ExternalEndpoint>,
         diagnostic: SharedDiagnostic,
         detected_public_ip: Option<String>,
         error_ring: SharedErrorRing,
+        node_name: Option<String>,
+        node_role: Option<NodeRole>,
     ) -> Self {
         Self {
             chain_id,
@@ -176,8 +211,9 @@ impl PeerDiscoverer {
             substrate_websocket_endpoint,
             tracker_client,
             allow_loopback_ip,
+            advertise_address_scopes,
             substrate_client: None,
-            external_endpoint,
+            external_endpoints,
             stale_counters: HashMap::new(),
             diagnostic,
             detected_public_ip,
@@ -185,9 +221,66 @@ impl PeerDiscoverer {
             error_ring,
             cycle_count: 0,
             cached_peer_id: None,
+            lease: None,
+            node_name,
+            node_role,
         }
     }
 
+    /// Keeps the lease of the registered addresses alive, or registers the
+    /// addresses under a new lease if they changed or the lease expired.
+    /// Returns the warnings of the tracker about the registration.
+    async fn advertise(
+        &mut self,
+        listen_addresses: &HashSet<PeerAddress>,
+        metadata: &PeerMetadata,
+    ) -> std::result::Result<Vec<String>, String> {
+        let addresses = listen_addresses.iter().cloned().collect::<BTreeSet<_>>();
+        if addresses.is_empty() {
+            self.lease = None;
+            return Ok(Vec::new());
+        }
+
+        if let Some(lease) = self.lease.as_ref().filter(|lease| lease.addresses == addresses) {
+            let res = self
+                .tracker_client
+                .keep_alive(self.blockchain_layer, &lease.lease_id, metadata)
+                .await
+                .map_err(|err| (err.is_lease_expired(), err.to_string()));
+            match res {
+                Ok(_) => return Ok(lease.warnings.clone()),
+                Err((true, _)) => {
+                    tracing::info!("Lease `{}` expired, register again", lease.lease_id);
+                }
+                Err((false, err)) => return Err(err),
+            }
+        }
+
+        self.lease = None;
+        let addrs = addresses.iter().cloned().collect::<Vec<_>>();
+        let lease = self
+            .tracker_client
+            .register(
+                self.blockchain_layer,
+                &self.chain_id,
+                &addrs,
+                &self.external_endpoints,
+                metadata,
+            )
+            .await
+            .map_err(|err| err.to_string())?;
+        tracing::info!(
+            "Registered {} addresses with lease `{}`, TTL: {:?}",
+            addrs.len(),
+            lease.lease_id,
+            lease.ttl
+        );
+        let warnings = lease.warnings.clone();
+        self.lease =
+            Some(TrackerLease { lease_id: lease.lease_id, addresses, warnings: lease.warnings });
+        Ok(warnings)
+    }
+
     async fn record_error(&self, category: &str, message: &str) {
         let entry = ErrorEntry {
             timestamp: time::OffsetDateTime::now_utc().to_string(),
@@ -198,6 +291,75 @@ impl PeerDiscoverer {
         self.error_ring.lock().await.push(entry);
     }
 
+    /// Collects the metadata reported to the tracker along with the local
+    /// addresses, fields which could not be fetched are left empty.
+    async fn collect_metadata(
+        &self,
+        substrate_client: &WsClient,
+        connections: &ConnectionStatus,
+    ) -> PeerMetadata {
+        let role = match self.node_role {
+            Some(role) => Some(role),
+            None => SystemApi::<Hash, BlockNumber>::system_node_roles(substrate_client)
+                .await
+                .map_err(|err| tracing::debug!("Failed to fetch system_nodeRoles: {err}"))
+                .ok()
+                .map(|roles| {
+                    // the RPC crate does not re-export its role type
+                    let is_authority = roles.iter().any(|role| format!("{role:?}") == "Authority");
+                    match (is_authority, self.blockchain_layer) {
+                        (true, BlockchainLayer::Rootchain) => NodeRole::Validator,
+                        (true, BlockchainLayer::Leafchain) => NodeRole::Collator,
+                        (false, _) => NodeRole::Full,
+                    }
+                }),
+        };
+
+        let client_version = SystemApi::<Hash, BlockNumber>::system_version(substrate_client)
+            .await
+            .map_err(|err| tracing::debug!("Failed to fetch system_version: {err}"))
+            .ok();
+
+        let best_block_number = SystemApi::<Hash, BlockNumber>::system_sync_state(substrate_client)
+            .await
+            .map_err(|err| tracing::debug!("Failed to fetch system_syncState: {err}"))
+            .ok()
+            .and_then(|state| u64::try_from(state.current_block).ok());
+
+        let (finalized_block_number, finalized_block_hash) =
+            fetch_finalized_block(substrate_client).await.unzip();
+
+        let health = SystemApi::<Hash, BlockNumber>::system_health(substrate_client)
+            .await
+            .map_err(|err| tracing::debug!("Failed to fetch system_health: {err}"))
+            .ok();
+        let sync_state = health.as_ref().map(|health| {
+            if health.is_syncing {
+                SyncState::Syncing
+            } else {
+                SyncState::Idle
+            }
+        });
+        let peer_count = health.and_then(|health| u32::try_from(health.peers).ok());
+
+        PeerMetadata {
+            role,
+            node_name: self.node_name.clone(),
+            client_version,
+            best_block_number,
+            finalized_block_number,
+            sync_state,
+            peer_count,
+            finalized_block_hash,
+            connected_peers: connections
+                .connected_peers
+                .iter()
+                .map(|peer| peer.peer_id.clone())
+                .collect(),
+            reserved_peers: connections.reserved_peers.clone(),
+        }
+    }
+
     // FIXME: split the function into smaller pieces
     #[allow(clippy::too_many_lines)]
     pub async fn execute(&mut self) -> Result<()> {
@@ -287,40 +449,30 @@ impl PeerDiscoverer {
         tracing::debug!("Current reserved peers: {current_reserved_peers:?}");
 
         // fetch new peer addresses from tracker
-        let mut potential_new_peers = {
-            let blockchain_layer = self.blockchain_layer;
-
-            match blockchain_layer {
-                BlockchainLayer::Rootchain => {
-                    RootchainPeer::get(&self.tracker_client, &self.chain_id)
-                        .await
-                        .map_err(|err| {
-                            tracing::error!("{err}");
-                            err
-                        })
-                        .unwrap_or_default()
-                }
-                BlockchainLayer::Leafchain => {
-                    LeafchainPeer::get(&self.tracker_client, &self.chain_id)
-                        .await
-                        .map_err(|err| {
-                            tracing::error!("{err}");
-                            err
-                        })
-                        .unwrap_or_default()
-                }
-            }
-        };
+        let PeerAddresses { addresses: mut potential_new_peers, tombstones, draining } = self
+            .tracker_client
+            .get_peer_addresses(self.blockchain_layer, &self.chain_id, None)
+            .await
+            .map_err(|err| {
+                tracing::error!("{err}");
+                err
+            })
+            .unwrap_or_default();
         let raw_from_tracker = potential_new_peers.len();
         tracing::debug!("Peers advertised from tracker: {potential_new_peers:?}");
 
         let stalled_peers = {
-            let tracker_peer_ids: HashSet<String> =
-                potential_new_peers.iter().map(PeerAddress::id).collect();
+            // draining peers are not handed out, but connections to them are kept
+            let tracker_peer_ids: HashSet<String> = potential_new_peers
+                .iter()
+                .map(PeerAddress::id)
+                .chain(draining.iter().cloned())
+                .collect();
             detect_stalled_peers(
                 &mut self.stale_counters,
                 &current_reserved_peers,
                 &tracker_peer_ids,
+                &tombstones,
             )
         };
 
@@ -420,44 +572,6 @@ impl PeerDiscoverer {
             }
         }
 
-        // advertise local address via tracker
-        tracing::info!("Advertise local address via tracker");
-        let registered_addresses_count = listen_addresses.len();
-        let res = {
-            let blockchain_layer = self.blockchain_layer;
-            match blockchain_layer {
-                BlockchainLayer::Rootchain => {
-                    futures::future::try_join_all(listen_addresses.iter().map(|local_address| {
-                        RootchainPeer::insert(
-                            &self.tracker_client,
-                            &self.chain_id,
-                            local_address,
-                            &self.external_endpoint,
-                        )
-                    }))
-                    .await
-                    .map_err(|e| e.to_string())
-                }
-                BlockchainLayer::Leafchain => {
-                    futures::future::try_join_all(listen_addresses.iter().map(|local_address| {
-                        LeafchainPeer::insert(
-                            &self.tracker_client,
-                            &self.chain_id,
-                            local_address,
-                            &self.external_endpoint,
-                        )
-                    }))
-                    .await
-                    .map_err(|e| e.to_string())
-                }
-            }
-        };
-
-        if let Err(err) = res {
-            tracing::error!("Error occurs while advertising peers to Tracker, error: {err}");
-            self.record_error("tracker_register", &err).await;
-        }
-
         // Fetch connection info from Substrate RPC (best-effort)
         let connections = {
             let mut conn = ConnectionStatus {
@@ -494,6 +608,30 @@ impl PeerDiscoverer {
             conn
         };
 
+        // advertise local address via tracker
+        tracing::info!("Advertise local address via tracker");
+        let advertised_addresses = listen_addresses
+            .iter()
+            .filter(|address| self.advertise_address_scopes.permits(address))
+            .cloned()
+            .collect::<HashSet<_>>();
+        let registered_addresses_count = advertised_addresses.len();
+        let metadata = self.collect_metadata(&substrate_client, &connections).await;
+        let res = self.advertise(&advertised_addresses, &metadata).await;
+
+        let warnings = match res {
+            Ok(warnings) => warnings.into_iter().collect::<BTreeSet<_>>(),
+            Err(err) => {
+                tracing::error!("Error occurs while advertising peers to Tracker, error: {err}");
+                self.record_error("tracker_register", &err).await;
+                BTreeSet::new()
+            }
+        };
+        for warning in &warnings {
+            tracing::error!("Tracker warns about the registration: {warning}");
+            self.record_error("tracker_warning", warning).await;
+        }
+
         // update diagnostic snapshot
         *self.diagnostic.lock().await = Some(DiagnosticSnapshot {
             identity: IdentityInfo {
@@ -504,8 +642,18 @@ impl PeerDiscoverer {
             registration: RegistrationInfo {
                 chain_id: self.chain_id.clone(),
                 blockchain_layer: format!("{}", self.blockchain_layer),
-                external_endpoint: self.external_endpoint.as_ref().map(ToString::to_string),
+                external_endpoints: self
+                    .external_endpoints
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
                 registered_addresses_count,
+                metadata,
+                warnings: warnings.into_iter().collect(),
+                is_draining: self
+                    .cached_peer_id
+                    .as_ref()
+                    .is_some_and(|peer_id| draining.contains(peer_id)),
             },
             discovery_funnel: funnel,
             connections,
@@ -533,15 +681,43 @@ fn filter_self_addresses(
     });
 }
 
+/// Returns the number and the hex encoded hash of the finalized head.
+async fn fetch_finalized_block(substrate_client: &WsClient) -> Option<(u64, String)> {
+    let hash = ChainApi::<BlockNumber, Hash, Header, SignedBlock>::finalized_head(substrate_client)
+        .await
+        .map_err(|err| tracing::debug!("Failed to fetch chain_getFinalizedHead: {err}"))
+        .ok()?;
+    let header =
+        ChainApi::<BlockNumber, Hash, Header, SignedBlock>::header(substrate_client, Some(hash))
+            .await
+            .map_err(|err| tracing::debug!("Failed to fetch chain_getHeader: {err}"))
+            .ok()??;
+    Some((parse_block_number(&header)?, format!("{hash:?}")))
+}
+
+/// Parses the hex encoded `number` field of a block header.
+fn parse_block_number(header: &Header) -> Option<u64> {
+    let number = header.get("number")?.as_str()?;
+    u64::from_str_radix(number.trim_start_matches("0x"), 16).ok()
+}
+
+/// Returns the reserved peers which should be removed. Peers tombstoned by the
+/// tracker are removed immediately, other absent peers only after
+/// `STALE_THRESHOLD` cycles as their absence may be a tracker glitch.
 fn detect_stalled_peers(
     stale_counters: &mut HashMap<String, u32>,
     current_reserved_peers: &[String],
     tracker_peer_ids: &HashSet<String>,
+    tombstones: &HashSet<String>,
 ) -> Vec<String> {
     let mut stalled = Vec::new();
     for peer in current_reserved_peers {
         if tracker_peer_ids.contains(peer) {
             stale_counters.remove(peer);
+        } else if tombstones.contains(peer) {
+            tracing::info!(peer = %peer, "Peer is tombstoned by tracker");
+            stale_counters.remove(peer);
+            stalled.push(peer.clone());
         } else {
             let count = stale_counters.entry(peer.clone()).or_insert(0);
             *count += 1;
@@ -621,12 +797,13 @@ mod tests {
 
         // Absent for 59 cycles → not stalled
         for _ in 0..59 {
-            let stalled = detect_stalled_peers(&mut counters, &reserved, &tracker_ids);
+            let stalled =
+                detect_stalled_peers(&mut counters, &reserved, &tracker_ids, &HashSet::new());
             assert!(stalled.is_empty(), "Should not be stalled before threshold");
         }
 
         // 60th cycle → stalled
-        let stalled = detect_stalled_peers(&mut counters, &reserved, &tracker_ids);
+        let stalled = detect_stalled_peers(&mut counters, &reserved, &tracker_ids, &HashSet::new());
         assert_eq!(stalled, vec!["peer-A".to_string()]);
     }
 
@@ -638,13 +815,14 @@ mod tests {
 
         // Accumulate 5 absent cycles
         for _ in 0..5 {
-            detect_stalled_peers(&mut counters, &reserved, &empty_tracker);
+            detect_stalled_peers(&mut counters, &reserved, &empty_tracker, &HashSet::new());
         }
         assert_eq!(counters.get("peer-A"), Some(&5));
 
         // Peer reappears in tracker
         let tracker_with_peer: HashSet<String> = ["peer-A".to_string()].into_iter().collect();
-        let stalled = detect_stalled_peers(&mut counters, &reserved, &tracker_with_peer);
+        let stalled =
+            detect_stalled_peers(&mut counters, &reserved, &tracker_with_peer, &HashSet::new());
         assert!(stalled.is_empty());
         assert!(!counters.contains_key("peer-A"));
     }
@@ -657,7 +835,7 @@ mod tests {
         // old-peer is no longer in reserved list
         let reserved = vec!["new-peer".to_string()];
         let tracker_ids: HashSet<String> = HashSet::new();
-        detect_stalled_peers(&mut counters, &reserved, &tracker_ids);
+        detect_stalled_peers(&mut counters, &reserved, &tracker_ids, &HashSet::new());
 
         // old-peer counter should be cleaned up
         assert!(!counters.contains_key("old-peer"));
@@ -665,6 +843,18 @@ mod tests {
         assert_eq!(counters.get("new-peer"), Some(&1));
     }
 
+    #[test]
+    fn detect_stalled_peers_removes_tombstoned_peers_immediately() {
+        let mut counters = HashMap::new();
+        let reserved = vec!["peer-A".to_string(), "peer-B".to_string()];
+        let tombstones: HashSet<String> = ["peer-A".to_string()].into_iter().collect();
+
+        let stalled = detect_stalled_peers(&mut counters, &reserved, &HashSet::new(), &tombstones);
+        assert_eq!(stalled, vec!["peer-A".to_string()]);
+        assert!(!counters.contains_key("peer-A"));
+        assert_eq!(counters.get("peer-B"), Some(&1));
+    }
+
     #[test]
     fn error_ring_respects_capacity() {
         let mut ring = ErrorRing::new();
@@ -681,4 +871,11 @@ mod tests {
         // oldest should have been evicted
         assert_eq!(entries[0].cycle, 10);
     }
+
+    #[test]
+    fn parse_block_number_reads_hex_header_number() {
+        let header = serde_json::json!({ "number": "0x1a2b", "parentHash": "0x00" });
+        assert_eq!(parse_block_number(&header), Some(0x1a2b));
+        assert_eq!(parse_block_number(&serde_json::json!({})), None);
+    }
 }
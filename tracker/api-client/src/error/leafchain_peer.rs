@@ -1,8 +1,27 @@
 use core::fmt;
 
+use kallax_tracker_server::Problem;
+
 #[derive(Debug)]
 pub enum GetLeafchainPeerAddressError {
-    Primitives { source: kallax_primitives::Error },
+    Primitives {
+        source: kallax_primitives::Error,
+    },
+
+    /// The tracker responded with an error.
+    Tracker {
+        problem: Problem,
+    },
+
+    /// The tracker did not respond, e.g. the request timed out.
+    Http {
+        source: reqwest::Error,
+    },
+
+    /// The response of the tracker is malformed.
+    Decode {
+        source: reqwest::Error,
+    },
 }
 
 impl From<kallax_primitives::Error> for GetLeafchainPeerAddressError {
@@ -12,17 +31,55 @@ impl From<kallax_primitives::Error> for GetLeafchainPeerAddressError {
     }
 }
 
+impl From<Problem> for GetLeafchainPeerAddressError {
+    #[inline]
+    fn from(problem: Problem) -> Self {
+        Self::Tracker { problem }
+    }
+}
+
+impl From<reqwest::Error> for GetLeafchainPeerAddressError {
+    #[inline]
+    fn from(source: reqwest::Error) -> Self {
+        Self::Http { source }
+    }
+}
+
 impl fmt::Display for GetLeafchainPeerAddressError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Primitives { source } => source.fmt(f),
+            Self::Tracker { problem } => problem.fmt(f),
+            Self::Http { source } => {
+                write!(f, "Error occurs while requesting tracker, error: {source}")
+            }
+            Self::Decode { source } => {
+                write!(f, "Error occurs while decoding response of tracker, error: {source}")
+            }
         }
     }
 }
 
 #[derive(Debug)]
 pub enum InsertLeafchainPeerAddressError {
-    Primitives { source: kallax_primitives::Error },
+    Primitives {
+        source: kallax_primitives::Error,
+    },
+
+    /// The tracker responded with an error.
+    Tracker {
+        problem: Problem,
+    },
+
+    /// The tracker did not respond, e.g. the request timed out.
+    Http {
+        source: reqwest::Error,
+    },
+
+    /// The response of the tracker is malformed.
+    Decode {
+        source: reqwest::Error,
+    },
 }
 
 impl From<kallax_primitives::Error> for InsertLeafchainPeerAddressError {
@@ -32,10 +89,31 @@ impl From<kallax_primitives::Error> for InsertLeafchainPeerAddressError {
     }
 }
 
+impl From<Problem> for InsertLeafchainPeerAddressError {
+    #[inline]
+    fn from(problem: Problem) -> Self {
+        Self::Tracker { problem }
+    }
+}
+
+impl From<reqwest::Error> for InsertLeafchainPeerAddressError {
+    #[inline]
+    fn from(source: reqwest::Error) -> Self {
+        Self::Http { source }
+    }
+}
+
 impl fmt::Display for InsertLeafchainPeerAddressError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Primitives { source } => source.fmt(f),
+            Self::Tracker { problem } => problem.fmt(f),
+            Self::Http { source } => {
+                write!(f, "Error occurs while requesting tracker, error: {source}")
+            }
+            Self::Decode { source } => {
+                write!(f, "Error occurs while decoding response of tracker, error: {source}")
+            }
         }
     }
 }
@@ -1,11 +1,13 @@
 mod leafchain_peer;
 mod rootchain_peer;
+mod tracker;
 
 use snafu::{Backtrace, Snafu};
 
 pub use self::{
     leafchain_peer::{GetLeafchainPeerAddressError, InsertLeafchainPeerAddressError},
     rootchain_peer::{GetRootchainPeerAddressError, InsertRootchainPeerAddressError},
+    tracker::{GetPeerAddressError, InsertPeerAddressError},
 };
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -17,4 +19,7 @@ pub enum Error {
         "Error occurs while connecting to tracker endpoint `{endpoint}`, error: {source}"
     ))]
     ConnectToTrackerApi { endpoint: http::Uri, source: reqwest::Error, backtrace: Backtrace },
+
+    #[snafu(display("Tracker endpoint `{endpoint}` is not a valid URL, error: {source}"))]
+    InvalidTrackerApiEndpoint { endpoint: http::Uri, source: url::ParseError, backtrace: Backtrace },
 }
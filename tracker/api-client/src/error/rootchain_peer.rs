@@ -1,8 +1,27 @@
 use core::fmt;
 
+use kallax_tracker_server::Problem;
+
 #[derive(Debug)]
 pub enum GetRootchainPeerAddressError {
-    Primitives { source: kallax_primitives::Error },
+    Primitives {
+        source: kallax_primitives::Error,
+    },
+
+    /// The tracker responded with an error.
+    Tracker {
+        problem: Problem,
+    },
+
+    /// The tracker did not respond, e.g. the request timed out.
+    Http {
+        source: reqwest::Error,
+    },
+
+    /// The response of the tracker is malformed.
+    Decode {
+        source: reqwest::Error,
+    },
 }
 
 impl From<kallax_primitives::Error> for GetRootchainPeerAddressError {
@@ -12,17 +31,55 @@ impl From<kallax_primitives::Error> for GetRootchainPeerAddressError {
     }
 }
 
+impl From<Problem> for GetRootchainPeerAddressError {
+    #[inline]
+    fn from(problem: Problem) -> Self {
+        Self::Tracker { problem }
+    }
+}
+
+impl From<reqwest::Error> for GetRootchainPeerAddressError {
+    #[inline]
+    fn from(source: reqwest::Error) -> Self {
+        Self::Http { source }
+    }
+}
+
 impl fmt::Display for GetRootchainPeerAddressError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Primitives { source } => source.fmt(f),
+            Self::Tracker { problem } => problem.fmt(f),
+            Self::Http { source } => {
+                write!(f, "Error occurs while requesting tracker, error: {source}")
+            }
+            Self::Decode { source } => {
+                write!(f, "Error occurs while decoding response of tracker, error: {source}")
+            }
         }
     }
 }
 
 #[derive(Debug)]
 pub enum InsertRootchainPeerAddressError {
-    Primitives { source: kallax_primitives::Error },
+    Primitives {
+        source: kallax_primitives::Error,
+    },
+
+    /// The tracker responded with an error.
+    Tracker {
+        problem: Problem,
+    },
+
+    /// The tracker did not respond, e.g. the request timed out.
+    Http {
+        source: reqwest::Error,
+    },
+
+    /// The response of the tracker is malformed.
+    Decode {
+        source: reqwest::Error,
+    },
 }
 
 impl From<kallax_primitives::Error> for InsertRootchainPeerAddressError {
@@ -32,10 +89,31 @@ impl From<kallax_primitives::Error> for InsertRootchainPeerAddressError {
     }
 }
 
+impl From<Problem> for InsertRootchainPeerAddressError {
+    #[inline]
+    fn from(problem: Problem) -> Self {
+        Self::Tracker { problem }
+    }
+}
+
+impl From<reqwest::Error> for InsertRootchainPeerAddressError {
+    #[inline]
+    fn from(source: reqwest::Error) -> Self {
+        Self::Http { source }
+    }
+}
+
 impl fmt::Display for InsertRootchainPeerAddressError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Primitives { source } => source.fmt(f),
+            Self::Tracker { problem } => problem.fmt(f),
+            Self::Http { source } => {
+                write!(f, "Error occurs while requesting tracker, error: {source}")
+            }
+            Self::Decode { source } => {
+                write!(f, "Error occurs while decoding response of tracker, error: {source}")
+            }
         }
     }
 }
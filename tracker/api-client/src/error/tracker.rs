@@ -0,0 +1,119 @@
+use core::fmt;
+
+use kallax_tracker_server::Problem;
+
+#[derive(Debug)]
+pub enum GetPeerAddressError {
+    Primitives {
+        source: kallax_primitives::Error,
+    },
+
+    /// The tracker responded with an error.
+    Tracker {
+        problem: Problem,
+    },
+
+    /// The tracker did not respond, e.g. the request timed out.
+    Http {
+        source: reqwest::Error,
+    },
+
+    /// The response of the tracker is malformed.
+    Decode {
+        source: reqwest::Error,
+    },
+}
+
+impl From<kallax_primitives::Error> for GetPeerAddressError {
+    #[inline]
+    fn from(source: kallax_primitives::Error) -> Self {
+        Self::Primitives { source }
+    }
+}
+
+impl From<Problem> for GetPeerAddressError {
+    #[inline]
+    fn from(problem: Problem) -> Self {
+        Self::Tracker { problem }
+    }
+}
+
+impl From<reqwest::Error> for GetPeerAddressError {
+    #[inline]
+    fn from(source: reqwest::Error) -> Self {
+        Self::Http { source }
+    }
+}
+
+impl fmt::Display for GetPeerAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Primitives { source } => source.fmt(f),
+            Self::Tracker { problem } => problem.fmt(f),
+            Self::Http { source } => {
+                write!(f, "Error occurs while requesting tracker, error: {source}")
+            }
+            Self::Decode { source } => {
+                write!(f, "Error occurs while decoding response of tracker, error: {source}")
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum InsertPeerAddressError {
+    Primitives {
+        source: kallax_primitives::Error,
+    },
+
+    /// The tracker responded with an error.
+    Tracker {
+        problem: Problem,
+    },
+
+    /// The tracker did not respond, e.g. the request timed out.
+    Http {
+        source: reqwest::Error,
+    },
+
+    /// The response of the tracker is malformed.
+    Decode {
+        source: reqwest::Error,
+    },
+}
+
+impl From<kallax_primitives::Error> for InsertPeerAddressError {
+    #[inline]
+    fn from(source: kallax_primitives::Error) -> Self {
+        Self::Primitives { source }
+    }
+}
+
+impl From<Problem> for InsertPeerAddressError {
+    #[inline]
+    fn from(problem: Problem) -> Self {
+        Self::Tracker { problem }
+    }
+}
+
+impl From<reqwest::Error> for InsertPeerAddressError {
+    #[inline]
+    fn from(source: reqwest::Error) -> Self {
+        Self::Http { source }
+    }
+}
+
+impl fmt::Display for InsertPeerAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Primitives { source } => source.fmt(f),
+            Self::Tracker { problem } => problem.fmt(f),
+            Self::Http { source } => {
+                write!(f, "Error occurs while requesting tracker, error: {source}")
+            }
+            Self::Decode { source } => {
+                write!(f, "Error occurs while decoding response of tracker, error: {source}")
+            }
+        }
+    }
+}
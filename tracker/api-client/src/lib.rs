@@ -149,30 +149,275 @@
 mod error;
 mod leafchain_peer;
 mod rootchain_peer;
+mod tracker;
+
+use std::time::Duration;
+
+use kallax_tracker_server::{Problem, ProblemCode};
+use snafu::ResultExt;
 
 pub use self::{
     error::{Error, Result},
     leafchain_peer::LeafchainPeer,
     rootchain_peer::RootchainPeer,
+    tracker::Tracker,
 };
 
 #[derive(Clone, Debug)]
 pub struct Config {
     pub api_endpoint: http::Uri,
+
+    /// Timeout of a single attempt of a request.
+    pub timeout: Duration,
+
+    /// Number of times a request is retried after the tracker did not respond
+    /// or responded with a server error.
+    pub max_retries: usize,
+
+    /// Delay before the first retry, doubled after every retry.
+    pub retry_backoff: Duration,
 }
 
 #[derive(Clone, Debug)]
 pub struct Client {
     client: reqwest::Client,
-    api_endpoint: http::Uri,
+    api_endpoint: reqwest::Url,
+    max_retries: usize,
+    retry_backoff: Duration,
 }
 
 impl Client {
     /// # Errors
     ///
-    /// This function will an error if the server is not connected.
-    pub fn new(Config { api_endpoint }: Config) -> Result<Self> {
-        let client = reqwest::Client::new();
-        Ok(Self { client, api_endpoint })
+    /// This function will an error if the endpoint is not a valid URL or the
+    /// HTTP client is not created.
+    pub fn new(
+        Config { api_endpoint, timeout, max_retries, retry_backoff }: Config,
+    ) -> Result<Self> {
+        let url = reqwest::Url::parse(api_endpoint.to_string().as_str()).with_context(|_| {
+            error::InvalidTrackerApiEndpointSnafu { endpoint: api_endpoint.clone() }
+        })?;
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .context(error::ConnectToTrackerApiSnafu { endpoint: api_endpoint })?;
+        Ok(Self { client, api_endpoint: url, max_retries, retry_backoff })
+    }
+
+    fn url(&self, path: &str) -> reqwest::Url {
+        let mut url = self.api_endpoint.clone();
+        url.set_path(path);
+        url
+    }
+
+    /// Sends the request built by `request`, retrying with exponential
+    /// backoff while the failure is transient.
+    async fn send<F, E>(&self, request: F) -> std::result::Result<reqwest::Response, E>
+    where
+        F: Fn(&reqwest::Client) -> reqwest::RequestBuilder + Send + Sync,
+        E: From<Problem> + From<reqwest::Error>,
+    {
+        let mut retries_left = self.max_retries;
+        let mut backoff = self.retry_backoff;
+        loop {
+            let err = match request(&self.client).send().await {
+                Ok(response) => match problem_of(response).await {
+                    Ok(response) => return Ok(response),
+                    Err(problem) if retries_left > 0 && is_transient(&problem) => {
+                        problem.to_string()
+                    }
+                    Err(problem) => return Err(problem.into()),
+                },
+                Err(err) if retries_left > 0 && !err.is_builder() => err.to_string(),
+                Err(err) => return Err(err.into()),
+            };
+
+            tracing::warn!(
+                "Request to tracker failed, retry in {backoff:?} ({retries_left} retries left), \
+                 error: {err}"
+            );
+            tokio::time::sleep(backoff).await;
+            retries_left -= 1;
+            backoff = backoff.saturating_mul(2);
+        }
+    }
+}
+
+/// Server errors and rate limiting may pass, other problems recur on every
+/// retry, including a tracker at capacity.
+const fn is_transient(problem: &Problem) -> bool {
+    matches!(problem.code, ProblemCode::RateLimited) || problem.status >= 500
+}
+
+/// Returns the response if the request succeeded, or the problem details
+/// responded by the tracker otherwise.
+async fn problem_of(
+    response: reqwest::Response,
+) -> std::result::Result<reqwest::Response, Problem> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    Err(serde_json::from_str::<Problem>(&body).unwrap_or_else(|_| {
+        // trackers before problem details respond with plain text
+        Problem {
+            type_uri: "about:blank".to_string(),
+            title: status.canonical_reason().unwrap_or_default().to_string(),
+            status: status.as_u16(),
+            detail: Some(body).filter(|body| !body.is_empty()),
+            code: ProblemCode::Unknown,
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::TcpListener,
+        str::FromStr,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        time::Duration,
+    };
+
+    use axum::{response::IntoResponse, routing, Json, Router};
+    use kallax_primitives::PeerAddress;
+    use kallax_tracker_server::{Problem, ProblemCode};
+
+    use super::{Client, Config, RootchainPeer};
+    use crate::error::{GetRootchainPeerAddressError, InsertRootchainPeerAddressError};
+
+    const MAX_RETRIES: usize = 2;
+
+    const PEER_ADDR: &str =
+        "/ip4/10.0.0.1/tcp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo";
+
+    fn serve(router: Router) -> http::Uri {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = axum::Server::from_tcp(listener).unwrap().serve(router.into_make_service());
+        drop(tokio::spawn(server));
+        format!("http://{addr}").parse().unwrap()
+    }
+
+    fn client(api_endpoint: http::Uri) -> Client {
+        Client::new(Config {
+            api_endpoint,
+            timeout: Duration::from_millis(200),
+            max_retries: MAX_RETRIES,
+            retry_backoff: Duration::from_millis(1),
+        })
+        .unwrap()
+    }
+
+    /// Serves `problem` on the rootchain peers route, returns the endpoint and
+    /// the number of requests received.
+    fn serve_problem(problem: Problem) -> (http::Uri, Arc<AtomicUsize>) {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let router = Router::new().route(
+            "/api/v1/rootchain/:chain_id/peers",
+            routing::get({
+                let requests = requests.clone();
+                move || async move {
+                    let _ = requests.fetch_add(1, Ordering::SeqCst);
+                    problem.into_response()
+                }
+            }),
+        );
+        (serve(router), requests)
+    }
+
+    #[tokio::test]
+    async fn unreachable_tracker_is_http_error() {
+        let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let client = client(format!("http://{addr}").parse().unwrap());
+
+        let err = client.get("thx").await.unwrap_err();
+        assert!(matches!(err, GetRootchainPeerAddressError::Http { .. }), "{err}");
+    }
+
+    #[tokio::test]
+    async fn timed_out_request_is_http_error() {
+        let router = Router::new().route(
+            "/api/v1/rootchain/:chain_id/peers",
+            routing::get(|| async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Json(Vec::<PeerAddress>::new())
+            }),
+        );
+        let client = client(serve(router));
+
+        let err = client.get("thx").await.unwrap_err();
+        assert!(
+            matches!(&err, GetRootchainPeerAddressError::Http { source } if source.is_timeout()),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn malformed_body_is_decode_error() {
+        let router = Router::new()
+            .route("/api/v1/rootchain/:chain_id/peers", routing::get(|| async { "not json" }));
+        let client = client(serve(router));
+
+        let err = client.get("thx").await.unwrap_err();
+        assert!(matches!(err, GetRootchainPeerAddressError::Decode { .. }), "{err}");
+    }
+
+    #[tokio::test]
+    async fn transient_problems_are_retried_up_to_max_retries() {
+        for code in [ProblemCode::Internal, ProblemCode::RateLimited] {
+            let (endpoint, requests) = serve_problem(Problem::from(code));
+
+            let err = client(endpoint).get("thx").await.unwrap_err();
+            assert!(
+                matches!(&err, GetRootchainPeerAddressError::Tracker { problem } if problem.code == code),
+                "{err}"
+            );
+            assert_eq!(requests.load(Ordering::SeqCst), MAX_RETRIES + 1, "{code:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried() {
+        for code in [ProblemCode::CapacityExceeded, ProblemCode::ChainNotFound] {
+            let (endpoint, requests) = serve_problem(Problem::from(code));
+
+            let err = client(endpoint).get("thx").await.unwrap_err();
+            assert!(
+                matches!(&err, GetRootchainPeerAddressError::Tracker { problem } if problem.code == code),
+                "{err}"
+            );
+            assert_eq!(requests.load(Ordering::SeqCst), 1, "{code:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn insert_without_external_endpoints() {
+        let body = Arc::new(Mutex::new(None));
+        let router = Router::new().route(
+            "/api/v1/rootchain/:chain_id/insert",
+            routing::post({
+                let body = body.clone();
+                move |Json(request): Json<serde_json::Value>| async move {
+                    *body.lock().unwrap() = Some(request);
+                }
+            }),
+        );
+        let client = client(serve(router));
+        let addr = PeerAddress::from_str(PEER_ADDR).unwrap();
+
+        let result: Result<(), InsertRootchainPeerAddressError> =
+            client.insert("thx", &addr, &[]).await;
+        assert!(result.is_ok());
+
+        let body = body.lock().unwrap().take().unwrap();
+        assert_eq!(body["peer_address"], PEER_ADDR);
+        assert!(body.get("external_endpoint").is_none());
+        assert_eq!(body["external_endpoints"], serde_json::json!([]));
     }
 }
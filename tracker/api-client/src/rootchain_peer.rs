@@ -1,9 +1,8 @@
 use std::{collections::HashSet, fmt};
 
 use async_trait::async_trait;
-use kallax_primitives::{ExternalEndpoint, PeerAddress};
+use kallax_primitives::{ExternalEndpoint, PeerAddress, PeerMetadata};
 use kallax_tracker_server::InsertRootchainPeerAddressRequest;
-use reqwest::Url;
 
 use crate::{
     error::{GetRootchainPeerAddressError, InsertRootchainPeerAddressError},
@@ -23,7 +22,7 @@ pub trait RootchainPeer {
         &self,
         chain_id: S,
         addr: &PeerAddress,
-        external_endpoint: &Option<ExternalEndpoint>,
+        external_endpoints: &[ExternalEndpoint],
     ) -> Result<(), InsertRootchainPeerAddressError>
     where
         S: fmt::Display + Send + Sync;
@@ -38,21 +37,14 @@ impl RootchainPeer for Client {
     where
         S: fmt::Display + Send + Sync,
     {
-        let Self { client: api_client, api_endpoint } = self;
+        let url = self.url(format!("/api/v1/rootchain/{chain_id}/peers").as_str());
 
-        let mut url =
-            Url::parse(api_endpoint.to_string().as_str()).expect("parse url error: {api_endpoint}");
-
-        url.set_path(format!("/api/v1/rootchain/{chain_id}/peers").as_str());
-
-        let peers = api_client
-            .get(url)
-            .send()
-            .await
-            .expect("get response error")
+        let peers = self
+            .send::<_, GetRootchainPeerAddressError>(|client| client.get(url.clone()))
+            .await?
             .json::<Vec<PeerAddress>>()
             .await
-            .expect("parse json error")
+            .map_err(|source| GetRootchainPeerAddressError::Decode { source })?
             .into_iter()
             .collect::<HashSet<PeerAddress>>();
 
@@ -63,27 +55,25 @@ impl RootchainPeer for Client {
         &self,
         chain_id: S,
         addr: &PeerAddress,
-        external_endpoint: &Option<ExternalEndpoint>,
+        external_endpoints: &[ExternalEndpoint],
     ) -> Result<(), InsertRootchainPeerAddressError>
     where
         S: fmt::Display + Send + Sync,
     {
-        let Self { client: api_client, api_endpoint } = self;
+        let url = self.url(format!("/api/v1/rootchain/{chain_id}/insert").as_str());
+        let request = InsertRootchainPeerAddressRequest {
+            peer_address: addr.clone(),
+            // older trackers only read the first endpoint
+            external_endpoint: external_endpoints.first().cloned(),
+            external_endpoints: external_endpoints.to_vec(),
+            metadata: PeerMetadata::default(),
+        };
 
-        let mut url =
-            Url::parse(api_endpoint.to_string().as_str()).expect("parse url error: {api_endpoint}");
-
-        url.set_path(format!("/api/v1/rootchain/{chain_id}/insert").as_str());
-
-        api_client
-            .post(url)
-            .json(&InsertRootchainPeerAddressRequest {
-                peer_address: addr.clone(),
-                external_endpoint: external_endpoint.clone().unwrap(),
+        let _ = self
+            .send::<_, InsertRootchainPeerAddressError>(|client| {
+                client.post(url.clone()).json(&request)
             })
-            .send()
-            .await
-            .expect("get response error");
+            .await?;
 
         Ok(())
     }
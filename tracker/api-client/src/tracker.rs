@@ -0,0 +1,107 @@
+use std::{collections::HashSet, fmt};
+
+use async_trait::async_trait;
+use kallax_primitives::{BlockchainLayer, ExternalEndpoint, PeerAddress, PeerMetadata};
+use kallax_tracker_server::{InsertPeerAddressRequest, InsertPeerAddressResponse};
+use serde::Deserialize;
+
+use crate::{
+    error::{GetPeerAddressError, InsertPeerAddressError},
+    Client,
+};
+
+/// Peers of every blockchain layer, served by the v2 API of the tracker.
+#[async_trait]
+pub trait Tracker {
+    async fn get<S>(
+        &self,
+        layer: BlockchainLayer,
+        chain_id: S,
+    ) -> Result<HashSet<PeerAddress>, GetPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync;
+
+    /// Returns the warnings of the tracker, e.g. when the peer ID is also
+    /// registered by another node.
+    async fn insert<S>(
+        &self,
+        layer: BlockchainLayer,
+        chain_id: S,
+        addr: &PeerAddress,
+        external_endpoints: &[ExternalEndpoint],
+    ) -> Result<Vec<String>, InsertPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync;
+}
+
+#[derive(Debug, Deserialize)]
+struct GetPeersResponse {
+    peers: Vec<PeerAddressGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PeerAddressGroup {
+    addresses: Vec<PeerAddress>,
+}
+
+/// Path segment of the blockchain layer in the v2 API.
+const fn layer_path(layer: BlockchainLayer) -> &'static str {
+    match layer {
+        BlockchainLayer::Rootchain => "rootchain",
+        BlockchainLayer::Leafchain => "leafchain",
+    }
+}
+
+#[async_trait]
+impl Tracker for Client {
+    async fn get<S>(
+        &self,
+        layer: BlockchainLayer,
+        chain_id: S,
+    ) -> Result<HashSet<PeerAddress>, GetPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync,
+    {
+        let url = self.url(format!("/api/v2/{}/{chain_id}/peers", layer_path(layer)).as_str());
+
+        let peers = self
+            .send::<_, GetPeerAddressError>(|client| client.get(url.clone()))
+            .await?
+            .json::<GetPeersResponse>()
+            .await
+            .map_err(|source| GetPeerAddressError::Decode { source })?
+            .peers
+            .into_iter()
+            .flat_map(|group| group.addresses)
+            .collect::<HashSet<PeerAddress>>();
+
+        Ok(peers)
+    }
+
+    async fn insert<S>(
+        &self,
+        layer: BlockchainLayer,
+        chain_id: S,
+        addr: &PeerAddress,
+        external_endpoints: &[ExternalEndpoint],
+    ) -> Result<Vec<String>, InsertPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync,
+    {
+        let url = self.url(format!("/api/v2/{}/{chain_id}/insert", layer_path(layer)).as_str());
+        let request = InsertPeerAddressRequest {
+            peer_address: addr.clone(),
+            external_endpoints: external_endpoints.to_vec(),
+            metadata: PeerMetadata::default(),
+        };
+
+        let InsertPeerAddressResponse { warnings } = self
+            .send::<_, InsertPeerAddressError>(|client| client.post(url.clone()).json(&request))
+            .await?
+            .json()
+            .await
+            .map_err(|source| InsertPeerAddressError::Decode { source })?;
+
+        Ok(warnings)
+    }
+}
@@ -48,3 +48,52 @@ impl fmt::Display for ClearLeafchainPeerAddressError {
         }
     }
 }
+
+#[derive(Debug)]
+pub enum RegisterLeafchainPeerError {
+    Status { source: tonic::Status },
+}
+
+impl fmt::Display for RegisterLeafchainPeerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum KeepAliveLeafchainPeerError {
+    /// The lease is unknown or expired, the peer should register again.
+    LeaseExpired,
+
+    Status {
+        source: tonic::Status,
+    },
+}
+
+impl fmt::Display for KeepAliveLeafchainPeerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LeaseExpired => f.write_str("Lease is unknown or expired"),
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
+
+impl KeepAliveLeafchainPeerError {
+    #[must_use]
+    pub const fn is_lease_expired(&self) -> bool {
+        matches!(self, Self::LeaseExpired)
+    }
+}
+
+impl From<tonic::Status> for KeepAliveLeafchainPeerError {
+    fn from(source: tonic::Status) -> Self {
+        if source.code() == tonic::Code::NotFound {
+            Self::LeaseExpired
+        } else {
+            Self::Status { source }
+        }
+    }
+}
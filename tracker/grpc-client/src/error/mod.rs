@@ -2,20 +2,26 @@ mod leafchain_peer;
 mod leafchain_spec;
 mod rootchain_peer;
 mod rootchain_spec;
+mod tracker;
 
 use snafu::{Backtrace, Snafu};
 
 pub use self::{
     leafchain_peer::{
         ClearLeafchainPeerAddressError, GetLeafchainPeerAddressError,
-        InsertLeafchainPeerAddressError,
+        InsertLeafchainPeerAddressError, KeepAliveLeafchainPeerError, RegisterLeafchainPeerError,
     },
     leafchain_spec::GetLeafchainSpecError,
     rootchain_peer::{
         ClearRootchainPeerAddressError, GetRootchainPeerAddressError,
-        InsertRootchainPeerAddressError,
+        InsertRootchainPeerAddressError, KeepAliveRootchainPeerError, RegisterRootchainPeerError,
     },
     rootchain_spec::GetRootchainSpecError,
+    tracker::{
+        ClearPeerAddressError, GetChainSpecError, GetComponentError, GetPeerAddressError,
+        InsertPeerAddressError, KeepAliveComponentError, KeepAlivePeerError,
+        RegisterComponentError, RegisterPeerError,
+    },
 };
 
 pub type Result<T> = std::result::Result<T, Error>;
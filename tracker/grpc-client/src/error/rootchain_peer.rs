@@ -48,3 +48,52 @@ impl fmt::Display for ClearRootchainPeerAddressError {
         }
     }
 }
+
+#[derive(Debug)]
+pub enum RegisterRootchainPeerError {
+    Status { source: tonic::Status },
+}
+
+impl fmt::Display for RegisterRootchainPeerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum KeepAliveRootchainPeerError {
+    /// The lease is unknown or expired, the peer should register again.
+    LeaseExpired,
+
+    Status {
+        source: tonic::Status,
+    },
+}
+
+impl fmt::Display for KeepAliveRootchainPeerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LeaseExpired => f.write_str("Lease is unknown or expired"),
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
+
+impl KeepAliveRootchainPeerError {
+    #[must_use]
+    pub const fn is_lease_expired(&self) -> bool {
+        matches!(self, Self::LeaseExpired)
+    }
+}
+
+impl From<tonic::Status> for KeepAliveRootchainPeerError {
+    fn from(source: tonic::Status) -> Self {
+        if source.code() == tonic::Code::NotFound {
+            Self::LeaseExpired
+        } else {
+            Self::Status { source }
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum GetPeerAddressError {
+    Primitives { source: kallax_primitives::Error },
+
+    Status { source: tonic::Status },
+}
+
+impl fmt::Display for GetPeerAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Primitives { source } => source.fmt(f),
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
+
+impl From<kallax_primitives::Error> for GetPeerAddressError {
+    #[inline]
+    fn from(source: kallax_primitives::Error) -> Self {
+        Self::Primitives { source }
+    }
+}
+
+#[derive(Debug)]
+pub enum InsertPeerAddressError {
+    Status { source: tonic::Status },
+}
+
+impl fmt::Display for InsertPeerAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ClearPeerAddressError {
+    Status { source: tonic::Status },
+}
+
+impl fmt::Display for ClearPeerAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RegisterPeerError {
+    Status { source: tonic::Status },
+}
+
+impl fmt::Display for RegisterPeerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum KeepAlivePeerError {
+    /// The lease is unknown or expired, the peer should register again.
+    LeaseExpired,
+
+    Status {
+        source: tonic::Status,
+    },
+}
+
+impl fmt::Display for KeepAlivePeerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LeaseExpired => f.write_str("Lease is unknown or expired"),
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
+
+impl KeepAlivePeerError {
+    #[must_use]
+    pub const fn is_lease_expired(&self) -> bool {
+        matches!(self, Self::LeaseExpired)
+    }
+}
+
+impl From<tonic::Status> for KeepAlivePeerError {
+    fn from(source: tonic::Status) -> Self {
+        if source.code() == tonic::Code::NotFound {
+            Self::LeaseExpired
+        } else {
+            Self::Status { source }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum GetChainSpecError {
+    Primitives { source: kallax_primitives::Error },
+
+    Status { source: tonic::Status },
+}
+
+impl From<kallax_primitives::Error> for GetChainSpecError {
+    #[inline]
+    fn from(source: kallax_primitives::Error) -> Self {
+        Self::Primitives { source }
+    }
+}
+
+impl fmt::Display for GetChainSpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Primitives { source } => source.fmt(f),
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RegisterComponentError {
+    Status { source: tonic::Status },
+}
+
+impl fmt::Display for RegisterComponentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum KeepAliveComponentError {
+    /// The lease is unknown or expired, the component should register again.
+    LeaseExpired,
+
+    Status {
+        source: tonic::Status,
+    },
+}
+
+impl fmt::Display for KeepAliveComponentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LeaseExpired => f.write_str("Lease is unknown or expired"),
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
+
+impl KeepAliveComponentError {
+    #[must_use]
+    pub const fn is_lease_expired(&self) -> bool {
+        matches!(self, Self::LeaseExpired)
+    }
+}
+
+impl From<tonic::Status> for KeepAliveComponentError {
+    fn from(source: tonic::Status) -> Self {
+        if source.code() == tonic::Code::NotFound {
+            Self::LeaseExpired
+        } else {
+            Self::Status { source }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum GetComponentError {
+    Primitives { source: kallax_primitives::Error },
+
+    Status { source: tonic::Status },
+}
+
+impl fmt::Display for GetComponentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Primitives { source } => source.fmt(f),
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
+
+impl From<kallax_primitives::Error> for GetComponentError {
+    #[inline]
+    fn from(source: kallax_primitives::Error) -> Self {
+        Self::Primitives { source }
+    }
+}
@@ -151,7 +151,15 @@ mod leafchain_peer;
 mod leafchain_spec;
 mod rootchain_peer;
 mod rootchain_spec;
+mod tracker;
 
+use std::{
+    collections::HashSet,
+    time::{Duration, SystemTime},
+};
+
+use kallax_primitives::{BlockchainComponent, PeerAddress, ServedChain};
+use kallax_tracker_proto as proto;
 use snafu::ResultExt;
 
 pub use self::{
@@ -160,8 +168,111 @@ pub use self::{
     leafchain_spec::LeafchainSpec,
     rootchain_peer::RootchainPeer,
     rootchain_spec::RootchainSpec,
+    tracker::Tracker,
 };
 
+/// Peer addresses of a chain and the IDs of the peers which were evicted or
+/// cleared recently.
+#[derive(Clone, Debug, Default)]
+pub struct PeerAddresses {
+    pub addresses: HashSet<PeerAddress>,
+
+    pub tombstones: HashSet<String>,
+
+    /// Tracked peers which are not handed out, connections to them should be
+    /// kept.
+    pub draining: HashSet<String>,
+}
+
+/// Addresses of a single peer.
+#[derive(Clone, Debug)]
+pub struct PeerAddressGroup {
+    pub peer_id: String,
+
+    pub addresses: HashSet<PeerAddress>,
+
+    /// `None` for reserved peers and boot nodes.
+    pub last_seen: Option<SystemTime>,
+}
+
+impl TryFrom<proto::PeerAddressGroup> for PeerAddressGroup {
+    type Error = kallax_primitives::Error;
+
+    fn try_from(
+        proto::PeerAddressGroup { peer_id, addresses, last_seen }: proto::PeerAddressGroup,
+    ) -> std::result::Result<Self, Self::Error> {
+        let addresses = addresses
+            .into_iter()
+            .map(PeerAddress::try_from)
+            .collect::<std::result::Result<_, _>>()?;
+        let last_seen = last_seen.and_then(|last_seen| SystemTime::try_from(last_seen).ok());
+        Ok(Self { peer_id, addresses, last_seen })
+    }
+}
+
+/// Same as [`PeerAddresses`], but the addresses are grouped by peer ID.
+#[derive(Clone, Debug, Default)]
+pub struct PeerAddressGroups {
+    pub peers: Vec<PeerAddressGroup>,
+
+    pub tombstones: HashSet<String>,
+
+    pub draining: HashSet<String>,
+}
+
+/// Lease of registered addresses, see [`RootchainPeer::register`] and
+/// [`LeafchainPeer::register`].
+#[derive(Clone, Debug)]
+pub struct Lease {
+    pub lease_id: String,
+
+    /// The lease expires unless it is kept alive within this time.
+    pub ttl: Duration,
+
+    /// Warnings of the tracker, e.g. when the peer ID is also registered by
+    /// another node.
+    pub warnings: Vec<String>,
+}
+
+/// A component registered to the tracker, e.g. a bridge relayer, see
+/// [`Tracker::register_component`].
+#[derive(Clone, Debug)]
+pub struct Component {
+    pub kind: BlockchainComponent,
+
+    pub name: String,
+
+    pub endpoints: Vec<String>,
+
+    pub chains: Vec<ServedChain>,
+
+    pub version: Option<String>,
+
+    pub last_seen: Option<SystemTime>,
+}
+
+impl TryFrom<proto::v2::Component> for Component {
+    type Error = kallax_primitives::Error;
+
+    fn try_from(
+        proto::v2::Component { kind, name, endpoints, chains, version, last_seen }: proto::v2::Component,
+    ) -> std::result::Result<Self, Self::Error> {
+        let kind = proto::v2::ComponentKind::try_from(kind)
+            .unwrap_or(proto::v2::ComponentKind::Unspecified);
+        let chains =
+            chains.into_iter().map(ServedChain::try_from).collect::<std::result::Result<_, _>>()?;
+        let last_seen = last_seen.and_then(|last_seen| SystemTime::try_from(last_seen).ok());
+        Ok(Self {
+            kind: BlockchainComponent::try_from(kind)?,
+            name,
+            endpoints,
+            chains,
+            version,
+            last_seen,
+        })
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Config {
     pub grpc_endpoint: http::Uri,
@@ -1,15 +1,15 @@
-use std::{collections::HashSet, fmt};
+use std::{collections::HashSet, fmt, time::Duration};
 
 use async_trait::async_trait;
-use kallax_primitives::{ExternalEndpoint, PeerAddress};
+use kallax_primitives::{ExternalEndpoint, NodeRole, PeerAddress, PeerMetadata};
 use kallax_tracker_proto as proto;
 
 use crate::{
     error::{
         ClearRootchainPeerAddressError, GetRootchainPeerAddressError,
-        InsertRootchainPeerAddressError,
+        InsertRootchainPeerAddressError, KeepAliveRootchainPeerError, RegisterRootchainPeerError,
     },
-    Client,
+    Client, Lease, PeerAddressGroup, PeerAddressGroups, PeerAddresses,
 };
 
 #[async_trait]
@@ -21,15 +21,77 @@ pub trait RootchainPeer {
     where
         S: fmt::Display + Send + Sync;
 
+    /// Returns only peers which reported `role`, or peers of any role if
+    /// `role` is `None`.
+    async fn get_by_role<S>(
+        &self,
+        chain_id: S,
+        role: Option<NodeRole>,
+    ) -> Result<HashSet<PeerAddress>, GetRootchainPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync;
+
+    /// Returns the addresses along with the IDs of the peers which were
+    /// removed from the tracker recently or are draining.
+    async fn get_peer_addresses<S>(
+        &self,
+        chain_id: S,
+        role: Option<NodeRole>,
+    ) -> Result<PeerAddresses, GetRootchainPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync;
+
+    /// Same as [`Self::get_peer_addresses`], but the addresses are grouped
+    /// by peer ID.
+    async fn get_peer_groups<S>(
+        &self,
+        chain_id: S,
+        role: Option<NodeRole>,
+    ) -> Result<PeerAddressGroups, GetRootchainPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync;
+
     async fn insert<S>(
         &self,
         chain_id: S,
         addr: &PeerAddress,
-        external_endpoint: &Option<ExternalEndpoint>,
+        external_endpoints: &[ExternalEndpoint],
     ) -> Result<(), InsertRootchainPeerAddressError>
     where
         S: fmt::Display + Send + Sync;
 
+    /// Returns the warnings of the tracker, e.g. when the peer ID is also
+    /// registered by another node.
+    async fn insert_with_metadata<S>(
+        &self,
+        chain_id: S,
+        addr: &PeerAddress,
+        external_endpoints: &[ExternalEndpoint],
+        metadata: &PeerMetadata,
+    ) -> Result<Vec<String>, InsertRootchainPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync;
+
+    /// Registers the addresses under a lease, which expires unless it is kept
+    /// alive with [`Self::keep_alive`].
+    async fn register<S>(
+        &self,
+        chain_id: S,
+        addrs: &[PeerAddress],
+        external_endpoints: &[ExternalEndpoint],
+        metadata: &PeerMetadata,
+    ) -> Result<Lease, RegisterRootchainPeerError>
+    where
+        S: fmt::Display + Send + Sync;
+
+    /// Extends the lease and replaces the metadata of its addresses, returns
+    /// the TTL of the lease.
+    async fn keep_alive(
+        &self,
+        lease_id: &str,
+        metadata: &PeerMetadata,
+    ) -> Result<Duration, KeepAliveRootchainPeerError>;
+
     async fn clear(&self) -> Result<(), ClearRootchainPeerAddressError>;
 }
 
@@ -42,39 +104,162 @@ impl RootchainPeer for Client {
     where
         S: fmt::Display + Send + Sync,
     {
-        proto::RootchainPeerServiceClient::new(self.channel.clone())
+        self.get_by_role(chain_id, None).await
+    }
+
+    async fn get_by_role<S>(
+        &self,
+        chain_id: S,
+        role: Option<NodeRole>,
+    ) -> Result<HashSet<PeerAddress>, GetRootchainPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync,
+    {
+        Ok(self.get_peer_addresses(chain_id, role).await?.addresses)
+    }
+
+    async fn get_peer_addresses<S>(
+        &self,
+        chain_id: S,
+        role: Option<NodeRole>,
+    ) -> Result<PeerAddresses, GetRootchainPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync,
+    {
+        let role = role.map_or(proto::NodeRole::Unspecified, proto::NodeRole::from);
+        let response = proto::RootchainPeerServiceClient::new(self.channel.clone())
             .get(proto::GetRootchainPeerAddressesRequest {
                 chain_id: chain_id.to_string(),
                 prefer_exposed: false,
+                role: role.into(),
             })
             .await
             .map_err(|source| GetRootchainPeerAddressError::Status { source })?
-            .into_inner()
+            .into_inner();
+        let addresses = response
             .addresses
             .into_iter()
             .map(PeerAddress::try_from)
             .collect::<Result<HashSet<PeerAddress>, _>>()
-            .map_err(GetRootchainPeerAddressError::from)
+            .map_err(GetRootchainPeerAddressError::from)?;
+        Ok(PeerAddresses {
+            addresses,
+            tombstones: response.tombstones.into_iter().collect(),
+            draining: response.draining.into_iter().collect(),
+        })
+    }
+
+    async fn get_peer_groups<S>(
+        &self,
+        chain_id: S,
+        role: Option<NodeRole>,
+    ) -> Result<PeerAddressGroups, GetRootchainPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync,
+    {
+        let role = role.map_or(proto::NodeRole::Unspecified, proto::NodeRole::from);
+        let response = proto::RootchainPeerServiceClient::new(self.channel.clone())
+            .get_grouped(proto::GetRootchainPeerAddressesRequest {
+                chain_id: chain_id.to_string(),
+                prefer_exposed: false,
+                role: role.into(),
+            })
+            .await
+            .map_err(|source| GetRootchainPeerAddressError::Status { source })?
+            .into_inner();
+        let peers = response
+            .peers
+            .into_iter()
+            .map(PeerAddressGroup::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(GetRootchainPeerAddressError::from)?;
+        Ok(PeerAddressGroups {
+            peers,
+            tombstones: response.tombstones.into_iter().collect(),
+            draining: response.draining.into_iter().collect(),
+        })
     }
 
     async fn insert<S>(
         &self,
         chain_id: S,
         addr: &PeerAddress,
-        external_endpoint: &Option<ExternalEndpoint>,
+        external_endpoints: &[ExternalEndpoint],
     ) -> Result<(), InsertRootchainPeerAddressError>
     where
         S: fmt::Display + Send + Sync,
     {
-        proto::RootchainPeerServiceClient::new(self.channel.clone())
+        self.insert_with_metadata(chain_id, addr, external_endpoints, &PeerMetadata::default())
+            .await?;
+        Ok(())
+    }
+
+    async fn insert_with_metadata<S>(
+        &self,
+        chain_id: S,
+        addr: &PeerAddress,
+        external_endpoints: &[ExternalEndpoint],
+        metadata: &PeerMetadata,
+    ) -> Result<Vec<String>, InsertRootchainPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync,
+    {
+        let response = proto::RootchainPeerServiceClient::new(self.channel.clone())
             .insert(proto::InsertRootchainPeerAddressRequest {
                 chain_id: chain_id.to_string(),
                 address: Some(addr.clone().into()),
-                external_endpoint: external_endpoint.clone().map(proto::ExternalEndpoint::from),
+                // older trackers only read the first endpoint
+                external_endpoint: external_endpoints.first().cloned().map(Into::into),
+                external_endpoints: external_endpoints.iter().cloned().map(Into::into).collect(),
+                metadata: Some(metadata.clone().into()),
             })
             .await
             .map_err(|source| InsertRootchainPeerAddressError::Status { source })?;
-        Ok(())
+        Ok(response.into_inner().warnings)
+    }
+
+    async fn register<S>(
+        &self,
+        chain_id: S,
+        addrs: &[PeerAddress],
+        external_endpoints: &[ExternalEndpoint],
+        metadata: &PeerMetadata,
+    ) -> Result<Lease, RegisterRootchainPeerError>
+    where
+        S: fmt::Display + Send + Sync,
+    {
+        let response = proto::RootchainPeerServiceClient::new(self.channel.clone())
+            .register(proto::RegisterRootchainPeerRequest {
+                chain_id: chain_id.to_string(),
+                addresses: addrs.iter().cloned().map(proto::PeerAddress::from).collect(),
+                // older trackers only read the first endpoint
+                external_endpoint: external_endpoints.first().cloned().map(Into::into),
+                external_endpoints: external_endpoints.iter().cloned().map(Into::into).collect(),
+                metadata: Some(metadata.clone().into()),
+            })
+            .await
+            .map_err(|source| RegisterRootchainPeerError::Status { source })?
+            .into_inner();
+        Ok(Lease {
+            lease_id: response.lease_id,
+            ttl: Duration::from_secs(response.ttl_seconds),
+            warnings: response.warnings,
+        })
+    }
+
+    async fn keep_alive(
+        &self,
+        lease_id: &str,
+        metadata: &PeerMetadata,
+    ) -> Result<Duration, KeepAliveRootchainPeerError> {
+        let response = proto::RootchainPeerServiceClient::new(self.channel.clone())
+            .keep_alive(proto::KeepAliveRootchainPeerRequest {
+                lease_id: lease_id.to_string(),
+                metadata: Some(metadata.clone().into()),
+            })
+            .await?
+            .into_inner();
+        Ok(Duration::from_secs(response.ttl_seconds))
     }
 
     async fn clear(&self) -> Result<(), ClearRootchainPeerAddressError> {
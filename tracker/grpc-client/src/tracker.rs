@@ -0,0 +1,334 @@
+use std::{fmt, time::Duration};
+
+use async_trait::async_trait;
+use kallax_primitives::{
+    BlockchainComponent, BlockchainLayer, ChainSpec, ComponentRegistration, ExternalEndpoint,
+    NodeRole, PeerAddress, PeerMetadata, ServedChain,
+};
+use kallax_tracker_proto::{self as proto, v2};
+
+use crate::{
+    error::{
+        ClearPeerAddressError, GetChainSpecError, GetComponentError, GetPeerAddressError,
+        InsertPeerAddressError, KeepAliveComponentError, KeepAlivePeerError,
+        RegisterComponentError, RegisterPeerError,
+    },
+    Client, Component, Lease, PeerAddressGroup, PeerAddressGroups, PeerAddresses,
+};
+
+/// Peers and chain specs of every blockchain layer, served by the v2 API of
+/// the tracker.
+#[async_trait]
+pub trait Tracker {
+    /// Returns the addresses along with the IDs of the peers which were
+    /// removed from the tracker recently or are draining, only peers which
+    /// reported `role` are returned unless `role` is `None`.
+    async fn get_peer_addresses<S>(
+        &self,
+        layer: BlockchainLayer,
+        chain_id: S,
+        role: Option<NodeRole>,
+    ) -> Result<PeerAddresses, GetPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync;
+
+    /// Same as [`Self::get_peer_addresses`], but the addresses are grouped
+    /// by peer ID.
+    async fn get_peer_groups<S>(
+        &self,
+        layer: BlockchainLayer,
+        chain_id: S,
+        role: Option<NodeRole>,
+    ) -> Result<PeerAddressGroups, GetPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync;
+
+    /// Returns the warnings of the tracker, e.g. when the peer ID is also
+    /// registered by another node.
+    async fn insert<S>(
+        &self,
+        layer: BlockchainLayer,
+        chain_id: S,
+        addr: &PeerAddress,
+        external_endpoints: &[ExternalEndpoint],
+        metadata: &PeerMetadata,
+    ) -> Result<Vec<String>, InsertPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync;
+
+    /// Registers the addresses under a lease, which expires unless it is kept
+    /// alive with [`Self::keep_alive`].
+    async fn register<S>(
+        &self,
+        layer: BlockchainLayer,
+        chain_id: S,
+        addrs: &[PeerAddress],
+        external_endpoints: &[ExternalEndpoint],
+        metadata: &PeerMetadata,
+    ) -> Result<Lease, RegisterPeerError>
+    where
+        S: fmt::Display + Send + Sync;
+
+    /// Extends the lease and replaces the metadata of its addresses, returns
+    /// the TTL of the lease.
+    async fn keep_alive(
+        &self,
+        layer: BlockchainLayer,
+        lease_id: &str,
+        metadata: &PeerMetadata,
+    ) -> Result<Duration, KeepAlivePeerError>;
+
+    async fn clear(&self, layer: BlockchainLayer) -> Result<(), ClearPeerAddressError>;
+
+    async fn get_chain_spec<S>(
+        &self,
+        layer: BlockchainLayer,
+        chain_id: S,
+    ) -> Result<ChainSpec, GetChainSpecError>
+    where
+        S: fmt::Display + Send + Sync;
+
+    /// Registers a component which is not a node of a chain, e.g. a bridge
+    /// relayer, under a lease which expires unless it is kept alive with
+    /// [`Self::keep_alive_component`]. The registration replaces the previous
+    /// one of the component of the same kind and name.
+    async fn register_component(
+        &self,
+        registration: &ComponentRegistration,
+    ) -> Result<Lease, RegisterComponentError>;
+
+    /// Extends the lease of a component, returns the TTL of the lease.
+    async fn keep_alive_component(
+        &self,
+        lease_id: &str,
+    ) -> Result<Duration, KeepAliveComponentError>;
+
+    /// Returns the live components of `kind` serving `chain`, or of any kind
+    /// and chain if `None`.
+    async fn get_components(
+        &self,
+        kind: Option<BlockchainComponent>,
+        chain: Option<&ServedChain>,
+    ) -> Result<Vec<Component>, GetComponentError>;
+}
+
+#[async_trait]
+impl Tracker for Client {
+    async fn get_peer_addresses<S>(
+        &self,
+        layer: BlockchainLayer,
+        chain_id: S,
+        role: Option<NodeRole>,
+    ) -> Result<PeerAddresses, GetPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync,
+    {
+        let role = role.map_or(proto::NodeRole::Unspecified, proto::NodeRole::from);
+        let response = v2::PeerServiceClient::new(self.channel.clone())
+            .get(v2::GetPeerAddressesRequest {
+                layer: v2::BlockchainLayer::from(layer).into(),
+                chain_id: chain_id.to_string(),
+                role: role.into(),
+            })
+            .await
+            .map_err(|source| GetPeerAddressError::Status { source })?
+            .into_inner();
+        let addresses = response
+            .addresses
+            .into_iter()
+            .map(PeerAddress::try_from)
+            .collect::<Result<_, _>>()
+            .map_err(GetPeerAddressError::from)?;
+        Ok(PeerAddresses {
+            addresses,
+            tombstones: response.tombstones.into_iter().collect(),
+            draining: response.draining.into_iter().collect(),
+        })
+    }
+
+    async fn get_peer_groups<S>(
+        &self,
+        layer: BlockchainLayer,
+        chain_id: S,
+        role: Option<NodeRole>,
+    ) -> Result<PeerAddressGroups, GetPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync,
+    {
+        let role = role.map_or(proto::NodeRole::Unspecified, proto::NodeRole::from);
+        let response = v2::PeerServiceClient::new(self.channel.clone())
+            .get_grouped(v2::GetPeerAddressesRequest {
+                layer: v2::BlockchainLayer::from(layer).into(),
+                chain_id: chain_id.to_string(),
+                role: role.into(),
+            })
+            .await
+            .map_err(|source| GetPeerAddressError::Status { source })?
+            .into_inner();
+        let peers = response
+            .peers
+            .into_iter()
+            .map(PeerAddressGroup::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(GetPeerAddressError::from)?;
+        Ok(PeerAddressGroups {
+            peers,
+            tombstones: response.tombstones.into_iter().collect(),
+            draining: response.draining.into_iter().collect(),
+        })
+    }
+
+    async fn insert<S>(
+        &self,
+        layer: BlockchainLayer,
+        chain_id: S,
+        addr: &PeerAddress,
+        external_endpoints: &[ExternalEndpoint],
+        metadata: &PeerMetadata,
+    ) -> Result<Vec<String>, InsertPeerAddressError>
+    where
+        S: fmt::Display + Send + Sync,
+    {
+        let response = v2::PeerServiceClient::new(self.channel.clone())
+            .insert(v2::InsertPeerAddressRequest {
+                layer: v2::BlockchainLayer::from(layer).into(),
+                chain_id: chain_id.to_string(),
+                address: Some(addr.clone().into()),
+                external_endpoints: external_endpoints.iter().cloned().map(Into::into).collect(),
+                metadata: Some(metadata.clone().into()),
+            })
+            .await
+            .map_err(|source| InsertPeerAddressError::Status { source })?;
+        Ok(response.into_inner().warnings)
+    }
+
+    async fn register<S>(
+        &self,
+        layer: BlockchainLayer,
+        chain_id: S,
+        addrs: &[PeerAddress],
+        external_endpoints: &[ExternalEndpoint],
+        metadata: &PeerMetadata,
+    ) -> Result<Lease, RegisterPeerError>
+    where
+        S: fmt::Display + Send + Sync,
+    {
+        let response = v2::PeerServiceClient::new(self.channel.clone())
+            .register(v2::RegisterPeerRequest {
+                layer: v2::BlockchainLayer::from(layer).into(),
+                chain_id: chain_id.to_string(),
+                addresses: addrs.iter().cloned().map(proto::PeerAddress::from).collect(),
+                external_endpoints: external_endpoints.iter().cloned().map(Into::into).collect(),
+                metadata: Some(metadata.clone().into()),
+            })
+            .await
+            .map_err(|source| RegisterPeerError::Status { source })?
+            .into_inner();
+        Ok(Lease {
+            lease_id: response.lease_id,
+            ttl: Duration::from_secs(response.ttl_seconds),
+            warnings: response.warnings,
+        })
+    }
+
+    async fn keep_alive(
+        &self,
+        layer: BlockchainLayer,
+        lease_id: &str,
+        metadata: &PeerMetadata,
+    ) -> Result<Duration, KeepAlivePeerError> {
+        let response = v2::PeerServiceClient::new(self.channel.clone())
+            .keep_alive(v2::KeepAlivePeerRequest {
+                layer: v2::BlockchainLayer::from(layer).into(),
+                lease_id: lease_id.to_string(),
+                metadata: Some(metadata.clone().into()),
+            })
+            .await?
+            .into_inner();
+        Ok(Duration::from_secs(response.ttl_seconds))
+    }
+
+    async fn clear(&self, layer: BlockchainLayer) -> Result<(), ClearPeerAddressError> {
+        v2::PeerServiceClient::new(self.channel.clone())
+            .clear(v2::ClearPeersRequest { layer: v2::BlockchainLayer::from(layer).into() })
+            .await
+            .map_err(|source| ClearPeerAddressError::Status { source })?;
+
+        Ok(())
+    }
+
+    async fn get_chain_spec<S>(
+        &self,
+        layer: BlockchainLayer,
+        chain_id: S,
+    ) -> Result<ChainSpec, GetChainSpecError>
+    where
+        S: fmt::Display + Send + Sync,
+    {
+        let resp = v2::ChainSpecServiceClient::new(self.channel.clone())
+            .get(v2::GetChainSpecRequest {
+                layer: v2::BlockchainLayer::from(layer).into(),
+                chain_id: chain_id.to_string(),
+            })
+            .await
+            .map_err(|source| GetChainSpecError::Status { source })?;
+
+        ChainSpec::try_from(resp.into_inner().spec.as_slice()).map_err(GetChainSpecError::from)
+    }
+
+    async fn register_component(
+        &self,
+        registration: &ComponentRegistration,
+    ) -> Result<Lease, RegisterComponentError> {
+        let ComponentRegistration { kind, name, endpoints, chains, version } = registration.clone();
+        let response = v2::ComponentServiceClient::new(self.channel.clone())
+            .register(v2::RegisterComponentRequest {
+                kind: v2::ComponentKind::from(kind).into(),
+                name,
+                endpoints,
+                chains: chains.into_iter().map(v2::ServedChain::from).collect(),
+                version,
+            })
+            .await
+            .map_err(|source| RegisterComponentError::Status { source })?
+            .into_inner();
+        Ok(Lease {
+            lease_id: response.lease_id,
+            ttl: Duration::from_secs(response.ttl_seconds),
+            warnings: Vec::new(),
+        })
+    }
+
+    async fn keep_alive_component(
+        &self,
+        lease_id: &str,
+    ) -> Result<Duration, KeepAliveComponentError> {
+        let response = v2::ComponentServiceClient::new(self.channel.clone())
+            .keep_alive(v2::KeepAliveComponentRequest { lease_id: lease_id.to_string() })
+            .await?
+            .into_inner();
+        Ok(Duration::from_secs(response.ttl_seconds))
+    }
+
+    async fn get_components(
+        &self,
+        kind: Option<BlockchainComponent>,
+        chain: Option<&ServedChain>,
+    ) -> Result<Vec<Component>, GetComponentError> {
+        let kind = kind.map_or(v2::ComponentKind::Unspecified, v2::ComponentKind::from);
+        let response = v2::ComponentServiceClient::new(self.channel.clone())
+            .list(v2::ListComponentsRequest {
+                kind: kind.into(),
+                chain: chain.cloned().map(v2::ServedChain::from),
+            })
+            .await
+            .map_err(|source| GetComponentError::Status { source })?
+            .into_inner();
+        response
+            .components
+            .into_iter()
+            .map(Component::try_from)
+            .collect::<Result<_, _>>()
+            .map_err(GetComponentError::from)
+    }
+}
@@ -1,14 +1,24 @@
+use std::{env, path::PathBuf};
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tonic_build::configure().compile(
-        &[
-            "proto/ExternalEndpoint.proto",
-            "proto/LeafchainPeer.proto",
-            "proto/LeafchainSpec.proto",
-            "proto/PeerAddress.proto",
-            "proto/RootchainPeer.proto",
-            "proto/RootchainSpec.proto",
-        ],
-        &["proto/"],
-    )?;
+    let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+    tonic_build::configure()
+        .file_descriptor_set_path(out_dir.join("kallax_tracker_descriptor.bin"))
+        .compile(
+            &[
+                "proto/ExternalEndpoint.proto",
+                "proto/LeafchainPeer.proto",
+                "proto/LeafchainSpec.proto",
+                "proto/PeerAddress.proto",
+                "proto/PeerMetadata.proto",
+                "proto/RootchainPeer.proto",
+                "proto/RootchainSpec.proto",
+                "proto/v2/BlockchainLayer.proto",
+                "proto/v2/ChainSpec.proto",
+                "proto/v2/Component.proto",
+                "proto/v2/Peer.proto",
+            ],
+            &["proto/"],
+        )?;
     Ok(())
 }
@@ -165,6 +165,34 @@ mod proto {
     )]
 
     tonic::include_proto!("kallax.tracker");
+
+    pub mod v2 {
+        tonic::include_proto!("kallax.tracker.v2");
+    }
+}
+
+/// Encoded descriptors of every service and message, served by gRPC server
+/// reflection.
+pub const FILE_DESCRIPTOR_SET: &[u8] =
+    tonic::include_file_descriptor_set!("kallax_tracker_descriptor");
+
+/// Layer-generic API, every request carries the blockchain layer it targets.
+pub mod v2 {
+    pub use super::proto::v2::{
+        chain_spec_service_client::ChainSpecServiceClient,
+        chain_spec_service_server::{ChainSpecService, ChainSpecServiceServer},
+        component_service_client::ComponentServiceClient,
+        component_service_server::{ComponentService, ComponentServiceServer},
+        peer_service_client::PeerServiceClient,
+        peer_service_server::{PeerService, PeerServiceServer},
+        BlockchainLayer, ClearPeersRequest, Component, ComponentKind, GetChainSpecRequest,
+        GetChainSpecResponse, GetGroupedPeerAddressesResponse, GetPeerAddressesRequest,
+        GetPeerAddressesResponse, InsertChainSpecRequest, InsertChainSpecResponse,
+        InsertPeerAddressRequest, InsertPeerAddressResponse, KeepAliveComponentRequest,
+        KeepAliveComponentResponse, KeepAlivePeerRequest, KeepAlivePeerResponse,
+        ListComponentsRequest, ListComponentsResponse, RegisterComponentRequest,
+        RegisterComponentResponse, RegisterPeerRequest, RegisterPeerResponse, ServedChain,
+    };
 }
 
 use std::str::FromStr;
@@ -180,13 +208,18 @@ pub use self::proto::{
     rootchain_peer_service_server::{RootchainPeerService, RootchainPeerServiceServer},
     rootchain_spec_service_client::RootchainSpecServiceClient,
     rootchain_spec_service_server::{RootchainSpecService, RootchainSpecServiceServer},
-    ExternalEndpoint, GetLeafchainPeerAddressesRequest, GetLeafchainPeerAddressesResponse,
-    GetLeafchainSpecRequest, GetLeafchainSpecResponse, GetRootchainPeerAddressesRequest,
-    GetRootchainPeerAddressesResponse, GetRootchainSpecRequest, GetRootchainSpecResponse,
-    InsertLeafchainPeerAddressRequest, InsertLeafchainPeerAddressResponse,
-    InsertLeafchainSpecRequest, InsertLeafchainSpecResponse, InsertRootchainPeerAddressRequest,
-    InsertRootchainPeerAddressResponse, InsertRootchainSpecRequest, InsertRootchainSpecResponse,
-    PeerAddress,
+    ExternalEndpoint, GetGroupedLeafchainPeerAddressesResponse,
+    GetGroupedRootchainPeerAddressesResponse, GetLeafchainPeerAddressesRequest,
+    GetLeafchainPeerAddressesResponse, GetLeafchainSpecRequest, GetLeafchainSpecResponse,
+    GetRootchainPeerAddressesRequest, GetRootchainPeerAddressesResponse, GetRootchainSpecRequest,
+    GetRootchainSpecResponse, InsertLeafchainPeerAddressRequest,
+    InsertLeafchainPeerAddressResponse, InsertLeafchainSpecRequest, InsertLeafchainSpecResponse,
+    InsertRootchainPeerAddressRequest, InsertRootchainPeerAddressResponse,
+    InsertRootchainSpecRequest, InsertRootchainSpecResponse, KeepAliveLeafchainPeerRequest,
+    KeepAliveLeafchainPeerResponse, KeepAliveRootchainPeerRequest, KeepAliveRootchainPeerResponse,
+    NodeRole, PeerAddress, PeerAddressGroup, PeerMetadata, RegisterLeafchainPeerRequest,
+    RegisterLeafchainPeerResponse, RegisterRootchainPeerRequest, RegisterRootchainPeerResponse,
+    SyncState,
 };
 
 impl TryFrom<proto::PeerAddress> for primitives::PeerAddress {
@@ -207,16 +240,191 @@ impl TryFrom<proto::ExternalEndpoint> for primitives::ExternalEndpoint {
     type Error = primitives::Error;
 
     fn try_from(
-        proto::ExternalEndpoint { host, port }: proto::ExternalEndpoint,
+        proto::ExternalEndpoint { host, port, transport_ports }: proto::ExternalEndpoint,
     ) -> Result<Self, Self::Error> {
         let port = u16::try_from(port).unwrap_or_default();
-        Ok(Self { host, port })
+        let endpoint = transport_ports.into_iter().fold(
+            Self::new(host, port),
+            |endpoint, (transport, port)| match (transport.parse(), u16::try_from(port)) {
+                (Ok(transport), Ok(port)) => endpoint.with_transport_port(transport, port),
+                _ => endpoint,
+            },
+        );
+        Ok(endpoint)
     }
 }
 
 impl From<primitives::ExternalEndpoint> for proto::ExternalEndpoint {
-    fn from(primitives::ExternalEndpoint { host, port }: primitives::ExternalEndpoint) -> Self {
+    fn from(
+        primitives::ExternalEndpoint { host, port, transport_ports }: primitives::ExternalEndpoint,
+    ) -> Self {
         let port = u32::from(port);
-        Self { host, port }
+        let transport_ports = transport_ports
+            .into_iter()
+            .map(|(transport, port)| (transport.to_string(), u32::from(port)))
+            .collect();
+        Self { host, port, transport_ports }
+    }
+}
+
+impl TryFrom<proto::NodeRole> for primitives::NodeRole {
+    type Error = primitives::Error;
+
+    fn try_from(role: proto::NodeRole) -> Result<Self, Self::Error> {
+        match role {
+            proto::NodeRole::Validator => Ok(Self::Validator),
+            proto::NodeRole::Collator => Ok(Self::Collator),
+            proto::NodeRole::Archive => Ok(Self::Archive),
+            proto::NodeRole::Full => Ok(Self::Full),
+            proto::NodeRole::Unspecified => Self::from_str(role.as_str_name()),
+        }
+    }
+}
+
+impl From<primitives::NodeRole> for proto::NodeRole {
+    fn from(role: primitives::NodeRole) -> Self {
+        match role {
+            primitives::NodeRole::Validator => Self::Validator,
+            primitives::NodeRole::Collator => Self::Collator,
+            primitives::NodeRole::Archive => Self::Archive,
+            primitives::NodeRole::Full => Self::Full,
+        }
+    }
+}
+
+impl TryFrom<proto::v2::BlockchainLayer> for primitives::BlockchainLayer {
+    type Error = primitives::Error;
+
+    fn try_from(layer: proto::v2::BlockchainLayer) -> Result<Self, Self::Error> {
+        match layer {
+            proto::v2::BlockchainLayer::Rootchain => Ok(Self::Rootchain),
+            proto::v2::BlockchainLayer::Leafchain => Ok(Self::Leafchain),
+            proto::v2::BlockchainLayer::Unspecified => Self::from_str(layer.as_str_name()),
+        }
+    }
+}
+
+impl From<primitives::BlockchainLayer> for proto::v2::BlockchainLayer {
+    fn from(layer: primitives::BlockchainLayer) -> Self {
+        match layer {
+            primitives::BlockchainLayer::Rootchain => Self::Rootchain,
+            primitives::BlockchainLayer::Leafchain => Self::Leafchain,
+        }
+    }
+}
+
+impl TryFrom<proto::v2::ComponentKind> for primitives::BlockchainComponent {
+    type Error = primitives::Error;
+
+    fn try_from(kind: proto::v2::ComponentKind) -> Result<Self, Self::Error> {
+        match kind {
+            proto::v2::ComponentKind::Bridge => Ok(Self::Bridge),
+            proto::v2::ComponentKind::OfflineWorker => Ok(Self::OfflineWorker),
+            proto::v2::ComponentKind::Unspecified => Self::from_str(kind.as_str_name()),
+        }
+    }
+}
+
+impl From<primitives::BlockchainComponent> for proto::v2::ComponentKind {
+    fn from(kind: primitives::BlockchainComponent) -> Self {
+        match kind {
+            primitives::BlockchainComponent::Bridge => Self::Bridge,
+            primitives::BlockchainComponent::OfflineWorker => Self::OfflineWorker,
+            // nodes of a chain are tracked as peers
+            primitives::BlockchainComponent::Rootchain
+            | primitives::BlockchainComponent::Leafchain => Self::Unspecified,
+        }
+    }
+}
+
+impl TryFrom<proto::v2::ServedChain> for primitives::ServedChain {
+    type Error = primitives::Error;
+
+    fn try_from(
+        proto::v2::ServedChain { layer, chain_id }: proto::v2::ServedChain,
+    ) -> Result<Self, Self::Error> {
+        let layer = proto::v2::BlockchainLayer::try_from(layer)
+            .unwrap_or(proto::v2::BlockchainLayer::Unspecified);
+        Ok(Self { layer: primitives::BlockchainLayer::try_from(layer)?, chain_id })
+    }
+}
+
+impl From<primitives::ServedChain> for proto::v2::ServedChain {
+    fn from(primitives::ServedChain { layer, chain_id }: primitives::ServedChain) -> Self {
+        Self { layer: proto::v2::BlockchainLayer::from(layer).into(), chain_id }
+    }
+}
+
+impl From<proto::PeerMetadata> for primitives::PeerMetadata {
+    fn from(
+        proto::PeerMetadata {
+            role,
+            node_name,
+            client_version,
+            best_block_number,
+            finalized_block_number,
+            sync_state,
+            peer_count,
+            finalized_block_hash,
+            connected_peers,
+            reserved_peers,
+        }: proto::PeerMetadata,
+    ) -> Self {
+        let role = proto::NodeRole::try_from(role)
+            .ok()
+            .and_then(|role| primitives::NodeRole::try_from(role).ok());
+        let sync_state = match proto::SyncState::try_from(sync_state) {
+            Ok(proto::SyncState::Syncing) => Some(primitives::SyncState::Syncing),
+            Ok(proto::SyncState::Idle) => Some(primitives::SyncState::Idle),
+            Ok(proto::SyncState::Unspecified) | Err(_) => None,
+        };
+        Self {
+            role,
+            node_name,
+            client_version,
+            best_block_number,
+            finalized_block_number,
+            sync_state,
+            peer_count,
+            finalized_block_hash,
+            connected_peers,
+            reserved_peers,
+        }
+    }
+}
+
+impl From<primitives::PeerMetadata> for proto::PeerMetadata {
+    fn from(
+        primitives::PeerMetadata {
+            role,
+            node_name,
+            client_version,
+            best_block_number,
+            finalized_block_number,
+            sync_state,
+            peer_count,
+            finalized_block_hash,
+            connected_peers,
+            reserved_peers,
+        }: primitives::PeerMetadata,
+    ) -> Self {
+        let role = role.map_or(proto::NodeRole::Unspecified, proto::NodeRole::from);
+        let sync_state = match sync_state {
+            Some(primitives::SyncState::Syncing) => proto::SyncState::Syncing,
+            Some(primitives::SyncState::Idle) => proto::SyncState::Idle,
+            None => proto::SyncState::Unspecified,
+        };
+        Self {
+            role: role.into(),
+            node_name,
+            client_version,
+            best_block_number,
+            finalized_block_number,
+            sync_state: sync_state.into(),
+            peer_count,
+            finalized_block_hash,
+            connected_peers,
+            reserved_peers,
+        }
     }
 }
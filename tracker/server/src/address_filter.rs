@@ -0,0 +1,91 @@
+use std::net::IpAddr;
+
+use kallax_primitives::{AddressPolicy, AddressScope, PeerAddress};
+
+use crate::peer_address_book::PeerAddressGroup;
+
+/// Decides which registered addresses are accepted, and which addresses are
+/// handed out to which callers.
+#[derive(Clone, Debug, Default)]
+pub struct AddressFilter {
+    accept: AddressPolicy,
+
+    is_scoped_by_caller: bool,
+}
+
+impl AddressFilter {
+    /// Addresses outside of `accept` are skipped on registration. If
+    /// `is_scoped_by_caller` is set, addresses of a local network are only
+    /// handed out to callers which are not on the public internet, see
+    /// [`AddressScope::is_reachable_from`].
+    #[must_use]
+    pub const fn new(accept: AddressPolicy, is_scoped_by_caller: bool) -> Self {
+        Self { accept, is_scoped_by_caller }
+    }
+
+    #[must_use]
+    pub fn accepts(&self, address: &PeerAddress) -> bool {
+        self.accept.permits(address)
+    }
+
+    #[must_use]
+    pub fn serves(&self, address: &PeerAddress, caller: Option<IpAddr>) -> bool {
+        match caller {
+            Some(caller) if self.is_scoped_by_caller => {
+                address.scope().is_reachable_from(AddressScope::of_ip(caller))
+            }
+            _ => true,
+        }
+    }
+
+    /// Drops the addresses which are not handed out to `caller`.
+    #[must_use]
+    pub fn served(
+        &self,
+        mut addresses: Vec<PeerAddress>,
+        caller: Option<IpAddr>,
+    ) -> Vec<PeerAddress> {
+        addresses.retain(|address| self.serves(address, caller));
+        addresses
+    }
+
+    /// Drops the addresses which are not handed out to `caller`, and the
+    /// groups left without any address.
+    #[must_use]
+    pub fn served_groups(
+        &self,
+        mut groups: Vec<PeerAddressGroup>,
+        caller: Option<IpAddr>,
+    ) -> Vec<PeerAddressGroup> {
+        for group in &mut groups {
+            group.addresses.retain(|address| self.serves(address, caller));
+        }
+        groups.retain(|group| !group.addresses.is_empty());
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn local_addresses_are_not_served_to_public_callers() {
+        let private = PeerAddress::from_str("/ip4/10.0.0.1/tcp/30333").unwrap();
+        let public = PeerAddress::from_str("/dns/node.example.com/tcp/30333").unwrap();
+        let addresses = vec![private.clone(), public.clone()];
+        let public_caller = Some(IpAddr::from([203, 0, 113, 7]));
+        let private_caller = Some(IpAddr::from([10, 0, 0, 2]));
+
+        let filter = AddressFilter::new(AddressPolicy::routable(), true);
+        assert_eq!(filter.served(addresses.clone(), public_caller), vec![public]);
+        assert_eq!(filter.served(addresses.clone(), private_caller), addresses);
+
+        let filter = AddressFilter::default();
+        assert_eq!(filter.served(addresses.clone(), public_caller), addresses);
+        assert!(filter.accepts(&private));
+        assert!(!filter.accepts(&PeerAddress::from_str("/ip4/127.0.0.1/tcp/30333").unwrap()));
+    }
+}
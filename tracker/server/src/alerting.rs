@@ -0,0 +1,329 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use kallax_primitives::BlockchainLayer;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use crate::{
+    error::{self, AlertRulesError},
+    peer_address_book::PeerAddressBook,
+};
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+const WEBHOOK_MAX_ATTEMPTS: u32 = 5;
+
+const WEBHOOK_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct AlertingConfig {
+    /// URLs every alert is posted to.
+    #[serde(default)]
+    pub webhooks: Vec<String>,
+
+    #[serde(default)]
+    pub rules: Vec<AlertRule>,
+}
+
+impl AlertingConfig {
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be read or parsed.
+    pub async fn load(path: PathBuf) -> Result<Self, AlertRulesError> {
+        let content = tokio::fs::read(&path)
+            .await
+            .with_context(|_| error::ReadAlertRulesSnafu { path: path.clone() })?;
+        let config: Self = serde_json::from_slice(&content)
+            .with_context(|_| error::ParseAlertRulesSnafu { path: path.clone() })?;
+        tracing::info!(
+            "Loaded {} alert rules and {} webhooks from `{}`",
+            config.rules.len(),
+            config.webhooks.len(),
+            path.display()
+        );
+        Ok(config)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AlertRule {
+    pub name: String,
+
+    pub layer: BlockchainLayer,
+
+    pub chain_id: String,
+
+    #[serde(flatten)]
+    pub condition: AlertCondition,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "condition", rename_all = "snake_case")]
+pub enum AlertCondition {
+    /// Fewer than `threshold` distinct peer IDs are registered.
+    PeerCountBelow { threshold: usize },
+
+    /// The peer ID is not registered, for example after being flushed.
+    PeerMissing { peer_id: String },
+
+    /// No peer has been registered for `minutes`.
+    NoPeersFor { minutes: u64 },
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertStatus {
+    Firing,
+    Resolved,
+}
+
+/// Body posted to the webhooks whenever a rule starts or stops firing.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Alert {
+    pub rule: String,
+
+    pub status: AlertStatus,
+
+    pub layer: BlockchainLayer,
+
+    pub chain_id: String,
+
+    pub message: String,
+
+    pub timestamp: String,
+}
+
+/// IDs of the live peers per chain of each layer.
+pub type PeerIdSnapshot = HashMap<BlockchainLayer, HashMap<String, HashSet<String>>>;
+
+/// Evaluates the alert rules and notifies the webhooks about state changes,
+/// a rule which keeps firing is reported only once.
+#[derive(Debug)]
+pub struct AlertEvaluator {
+    rules: Vec<AlertRule>,
+
+    notifier: WebhookNotifier,
+
+    firing: HashSet<usize>,
+
+    /// Last time the chain of each rule had any peer.
+    last_populated: HashMap<usize, Instant>,
+}
+
+impl AlertEvaluator {
+    pub fn new(AlertingConfig { webhooks, rules }: AlertingConfig) -> Self {
+        let now = Instant::now();
+        let last_populated = (0..rules.len()).map(|index| (index, now)).collect();
+        Self {
+            rules,
+            notifier: WebhookNotifier::new(webhooks),
+            firing: HashSet::new(),
+            last_populated,
+        }
+    }
+
+    pub async fn evaluate(&mut self, rootchain: &PeerAddressBook, leafchain: &PeerAddressBook) {
+        let snapshot = HashMap::from([
+            (BlockchainLayer::Rootchain, rootchain.live_peer_ids().await),
+            (BlockchainLayer::Leafchain, leafchain.live_peer_ids().await),
+        ]);
+        for alert in self.transitions(&snapshot, Instant::now()) {
+            match alert.status {
+                AlertStatus::Firing => {
+                    tracing::warn!("Alert `{}` fired: {}", alert.rule, alert.message);
+                }
+                AlertStatus::Resolved => {
+                    tracing::info!("Alert `{}` resolved: {}", alert.rule, alert.message);
+                }
+            }
+            self.notifier.notify(alert);
+        }
+    }
+
+    /// Returns an alert for every rule which started or stopped firing since
+    /// the last evaluation.
+    fn transitions(&mut self, snapshot: &PeerIdSnapshot, now: Instant) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        for (index, rule) in self.rules.iter().enumerate() {
+            let peer_ids = snapshot.get(&rule.layer).and_then(|chains| chains.get(&rule.chain_id));
+            let peer_count = peer_ids.map_or(0, HashSet::len);
+            if peer_count > 0 {
+                self.last_populated.insert(index, now);
+            }
+
+            let (is_firing, message) = match &rule.condition {
+                AlertCondition::PeerCountBelow { threshold } => (
+                    peer_count < *threshold,
+                    format!("chain has {peer_count} peers, expected at least {threshold}"),
+                ),
+                AlertCondition::PeerMissing { peer_id } => {
+                    let is_missing = peer_ids.is_none_or(|ids| !ids.contains(peer_id));
+                    let state = if is_missing { "missing" } else { "registered" };
+                    (is_missing, format!("peer `{peer_id}` is {state}"))
+                }
+                AlertCondition::NoPeersFor { minutes } => {
+                    let empty_for = now.saturating_duration_since(self.last_populated[&index]);
+                    (
+                        empty_for >= Duration::from_secs(minutes.saturating_mul(60)),
+                        format!("chain has had no peers for {} seconds", empty_for.as_secs()),
+                    )
+                }
+            };
+
+            let status = match (is_firing, self.firing.contains(&index)) {
+                (true, false) => {
+                    self.firing.insert(index);
+                    AlertStatus::Firing
+                }
+                (false, true) => {
+                    self.firing.remove(&index);
+                    AlertStatus::Resolved
+                }
+                _ => continue,
+            };
+            alerts.push(Alert {
+                rule: rule.name.clone(),
+                status,
+                layer: rule.layer,
+                chain_id: rule.chain_id.clone(),
+                message,
+                timestamp: time::OffsetDateTime::now_utc().to_string(),
+            });
+        }
+        alerts
+    }
+}
+
+#[derive(Clone, Debug)]
+struct WebhookNotifier {
+    client: reqwest::Client,
+
+    urls: Arc<[String]>,
+}
+
+impl WebhookNotifier {
+    fn new(urls: Vec<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .expect("reqwest client should always build successfully");
+        Self { client, urls: urls.into() }
+    }
+
+    /// Posts the alert to every webhook in the background.
+    fn notify(&self, alert: Alert) {
+        for url in self.urls.iter() {
+            let client = self.client.clone();
+            let url = url.clone();
+            let alert = alert.clone();
+            drop(tokio::spawn(async move { post_with_retry(&client, &url, &alert).await }));
+        }
+    }
+}
+
+async fn post_with_retry(client: &reqwest::Client, url: &str, alert: &Alert) {
+    let mut backoff = WEBHOOK_INITIAL_BACKOFF;
+    for attempt in 1..=WEBHOOK_MAX_ATTEMPTS {
+        match client
+            .post(url)
+            .json(alert)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+        {
+            Ok(_) => return,
+            Err(err) => {
+                tracing::warn!(
+                    "Failed to post alert `{}` to `{url}` (attempt {attempt}/{WEBHOOK_MAX_ATTEMPTS}), \
+                     error: {err}",
+                    alert.rule
+                );
+            }
+        }
+        if attempt < WEBHOOK_MAX_ATTEMPTS {
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+    tracing::error!("Giving up posting alert `{}` to `{url}`", alert.rule);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluator(condition: AlertCondition) -> AlertEvaluator {
+        AlertEvaluator::new(AlertingConfig {
+            webhooks: Vec::new(),
+            rules: vec![AlertRule {
+                name: "collators".to_string(),
+                layer: BlockchainLayer::Leafchain,
+                chain_id: "chain-1".to_string(),
+                condition,
+            }],
+        })
+    }
+
+    fn snapshot(peer_ids: &[&str]) -> PeerIdSnapshot {
+        let peer_ids = peer_ids.iter().map(ToString::to_string).collect();
+        HashMap::from([(
+            BlockchainLayer::Leafchain,
+            HashMap::from([("chain-1".to_string(), peer_ids)]),
+        )])
+    }
+
+    fn statuses(alerts: &[Alert]) -> Vec<AlertStatus> {
+        alerts.iter().map(|alert| alert.status).collect()
+    }
+
+    #[test]
+    fn rule_fires_once_and_resolves() {
+        let mut evaluator = evaluator(AlertCondition::PeerCountBelow { threshold: 2 });
+        let now = Instant::now();
+
+        assert_eq!(statuses(&evaluator.transitions(&snapshot(&["a"]), now)), [AlertStatus::Firing]);
+        assert!(evaluator.transitions(&snapshot(&["a"]), now).is_empty());
+        assert_eq!(
+            statuses(&evaluator.transitions(&snapshot(&["a", "b"]), now)),
+            [AlertStatus::Resolved]
+        );
+    }
+
+    #[test]
+    fn peer_missing_and_no_peers_for() {
+        let mut missing = evaluator(AlertCondition::PeerMissing { peer_id: "a".to_string() });
+        let now = Instant::now();
+        assert!(missing.transitions(&snapshot(&["a"]), now).is_empty());
+        assert_eq!(statuses(&missing.transitions(&snapshot(&["b"]), now)), [AlertStatus::Firing]);
+
+        let mut empty = evaluator(AlertCondition::NoPeersFor { minutes: 5 });
+        assert!(empty.transitions(&snapshot(&[]), now + Duration::from_secs(60)).is_empty());
+        assert_eq!(
+            statuses(&empty.transitions(&snapshot(&[]), now + Duration::from_secs(301))),
+            [AlertStatus::Firing]
+        );
+    }
+
+    #[test]
+    fn rules_are_parsed_from_json() {
+        let config: AlertingConfig = serde_json::from_str(
+            r#"{
+                "webhooks": ["http://127.0.0.1:9000/alerts"],
+                "rules": [{
+                    "name": "collators",
+                    "layer": "leafchain",
+                    "chain_id": "chain-1",
+                    "condition": "peer_count_below",
+                    "threshold": 3
+                }]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.rules[0].condition, AlertCondition::PeerCountBelow { threshold: 3 });
+    }
+}
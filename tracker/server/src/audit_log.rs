@@ -0,0 +1,541 @@
+use std::{
+    collections::VecDeque,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use kallax_primitives::{BlockchainLayer, PeerAddress};
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use time::OffsetDateTime;
+use tokio::{
+    fs,
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    sync::Mutex,
+};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    error::{self, AuditLogError},
+    peer_address_book::{InsertOutcome, Registration},
+};
+
+const ACTIVE_FILE_NAME: &str = "audit.jsonl";
+
+const ROTATED_FILE_PREFIX: &str = "audit-";
+
+const FILE_EXTENSION: &str = ".jsonl";
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    InsertPeer,
+    EvictPeer,
+    ClearPeers,
+    AddChainSpec,
+    ReplaceChainSpec,
+}
+
+/// A single line of the audit log.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+pub struct AuditEvent {
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+
+    pub action: AuditAction,
+
+    /// Address of the client which caused the mutation, `None` for mutations
+    /// made by the tracker itself.
+    #[schema(value_type = Option<String>)]
+    pub caller: Option<SocketAddr>,
+
+    pub layer: BlockchainLayer,
+
+    /// `None` for mutations affecting every chain of the layer.
+    pub chain_id: Option<String>,
+
+    pub peer_id: Option<String>,
+
+    pub address: Option<String>,
+}
+
+impl AuditEvent {
+    pub fn new(action: AuditAction, layer: BlockchainLayer, caller: Option<SocketAddr>) -> Self {
+        Self {
+            timestamp: OffsetDateTime::now_utc(),
+            action,
+            caller,
+            layer,
+            chain_id: None,
+            peer_id: None,
+            address: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_chain_id(mut self, chain_id: impl Into<String>) -> Self {
+        self.chain_id = Some(chain_id.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_peer(mut self, address: &PeerAddress) -> Self {
+        self.peer_id = Some(address.id());
+        self.address = Some(address.to_string());
+        self
+    }
+}
+
+/// Filter of [`AuditLog::query`], every condition is optional.
+#[derive(Clone, Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    pub chain_id: Option<String>,
+
+    pub layer: Option<BlockchainLayer>,
+
+    /// Inclusive lower bound of the event timestamps, in RFC 3339.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub from: Option<OffsetDateTime>,
+
+    /// Exclusive upper bound of the event timestamps, in RFC 3339.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub to: Option<OffsetDateTime>,
+
+    /// Maximum number of events returned, the most recent ones are kept.
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    fn matches(&self, event: &AuditEvent) -> bool {
+        self.chain_id.as_ref().is_none_or(|chain_id| event.chain_id.as_ref() == Some(chain_id))
+            && self.layer.is_none_or(|layer| event.layer == layer)
+            && self.from.is_none_or(|from| event.timestamp >= from)
+            && self.to.is_none_or(|to| event.timestamp < to)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AuditLogConfig {
+    /// Directory holding the active file and the rotated files.
+    pub directory: PathBuf,
+
+    /// The active file is rotated before it grows beyond this size in bytes.
+    pub max_file_size: u64,
+
+    /// The active file is rotated once it has been written to for this long.
+    pub max_file_age: Duration,
+
+    /// The oldest rotated files are deleted once there are more than this
+    /// many.
+    pub max_files: usize,
+}
+
+/// Append-only JSON-lines log of the mutations of the tracker state. The
+/// default log is disabled and discards every event.
+#[derive(Clone, Debug, Default)]
+pub struct AuditLog {
+    inner: Option<Arc<Inner>>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    config: AuditLogConfig,
+
+    active_file: Mutex<Option<ActiveFile>>,
+}
+
+#[derive(Debug)]
+struct ActiveFile {
+    file: fs::File,
+
+    size: u64,
+
+    opened_at: OffsetDateTime,
+}
+
+impl AuditLog {
+    /// # Errors
+    ///
+    /// Returns an error if the directory could not be created.
+    pub async fn open(config: AuditLogConfig) -> Result<Self, AuditLogError> {
+        fs::create_dir_all(&config.directory).await.with_context(|_| {
+            error::CreateAuditLogDirectorySnafu { path: config.directory.clone() }
+        })?;
+        tracing::info!("Write audit log to `{}`", config.directory.display());
+        Ok(Self { inner: Some(Arc::new(Inner { config, active_file: Mutex::new(None) })) })
+    }
+
+    /// Appends the event, failures are logged instead of being returned so
+    /// that the audited operation is never rejected.
+    pub async fn record(&self, event: AuditEvent) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        if let Err(err) = inner.append(&event).await {
+            tracing::error!("Failed to write audit event {event:?}, error: {err}");
+        }
+    }
+
+    /// Records the mutations made by [`PeerAddressBook::insert`]: the address
+    /// if it is new, and the addresses it replaced.
+    ///
+    /// [`PeerAddressBook::insert`]: crate::peer_address_book::PeerAddressBook::insert
+    pub async fn record_insert(
+        &self,
+        layer: BlockchainLayer,
+        caller: Option<SocketAddr>,
+        chain_id: &str,
+        address: &PeerAddress,
+        outcome: &InsertOutcome,
+    ) {
+        for replaced in &outcome.replaced {
+            self.record(
+                AuditEvent::new(AuditAction::EvictPeer, layer, caller)
+                    .with_chain_id(chain_id)
+                    .with_peer(replaced),
+            )
+            .await;
+        }
+        if outcome.is_new {
+            self.record(
+                AuditEvent::new(AuditAction::InsertPeer, layer, caller)
+                    .with_chain_id(chain_id)
+                    .with_peer(address),
+            )
+            .await;
+        }
+    }
+
+    /// Records the mutations made by [`PeerAddressBook::register`].
+    ///
+    /// [`PeerAddressBook::register`]: crate::peer_address_book::PeerAddressBook::register
+    pub async fn record_registration(
+        &self,
+        layer: BlockchainLayer,
+        caller: Option<SocketAddr>,
+        chain_id: &str,
+        registration: &Registration,
+    ) {
+        for replaced in &registration.replaced {
+            self.record(
+                AuditEvent::new(AuditAction::EvictPeer, layer, caller)
+                    .with_chain_id(chain_id)
+                    .with_peer(replaced),
+            )
+            .await;
+        }
+        for (address, outcome) in &registration.outcomes {
+            self.record_insert(layer, caller, chain_id, address, outcome).await;
+        }
+    }
+
+    /// Returns the matching events of the active and the rotated files,
+    /// ordered by timestamp. Files are read from the newest one, older files
+    /// are not read once `limit` events are found. Events are recorded
+    /// meanwhile, the events recorded after the query started may be missed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file could not be read.
+    pub async fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEvent>, AuditLogError> {
+        let Some(inner) = &self.inner else {
+            return Ok(Vec::new());
+        };
+        let limit = query.limit.unwrap_or(usize::MAX);
+
+        // the files are opened under the lock, so that none is half written,
+        // rotated or removed meanwhile, and read once it is released, so that
+        // a large query does not stall `record`
+        let active_file = inner.active_file.lock().await;
+        let mut files = Vec::new();
+        for path in inner.files().await? {
+            let file = fs::File::open(&path)
+                .await
+                .with_context(|_| error::ReadAuditLogSnafu { path: path.clone() })?;
+            files.push((path, file));
+        }
+        drop(active_file);
+
+        let mut events = Vec::new();
+        for (path, file) in files {
+            if events.len() >= limit {
+                break;
+            }
+            events.extend(read_events(file, &path, query, limit - events.len()).await?);
+        }
+        events.sort_by_key(|event| event.timestamp);
+        Ok(events)
+    }
+}
+
+impl Inner {
+    fn active_path(&self) -> PathBuf {
+        self.config.directory.join(ACTIVE_FILE_NAME)
+    }
+
+    async fn append(&self, event: &AuditEvent) -> Result<(), AuditLogError> {
+        let mut line =
+            serde_json::to_vec(event).expect("audit event should always serialize successfully");
+        line.push(b'\n');
+        let len = line.len() as u64;
+
+        let now = OffsetDateTime::now_utc();
+        let mut active_file = self.active_file.lock().await;
+        if active_file.is_none() {
+            *active_file = Some(self.open_active_file(now).await?);
+        }
+        if let Some(ActiveFile { size, opened_at, .. }) = active_file.as_ref() {
+            let is_full = *size > 0 && size + len > self.config.max_file_size;
+            let is_expired = (now - *opened_at).unsigned_abs() >= self.config.max_file_age;
+            if is_full || is_expired {
+                *active_file = None;
+                self.rotate(now).await?;
+                if let Err(err) = self.remove_old_files().await {
+                    tracing::warn!("Failed to remove old audit logs, error: {err}");
+                }
+            }
+        }
+        let active_file = match active_file.as_mut() {
+            Some(active_file) => active_file,
+            None => active_file.insert(self.open_active_file(now).await?),
+        };
+
+        let path = self.active_path();
+        active_file
+            .file
+            .write_all(&line)
+            .await
+            .with_context(|_| error::WriteAuditLogSnafu { path: path.clone() })?;
+        active_file.file.flush().await.with_context(|_| error::WriteAuditLogSnafu { path })?;
+        active_file.size += len;
+        Ok(())
+    }
+
+    async fn open_active_file(&self, now: OffsetDateTime) -> Result<ActiveFile, AuditLogError> {
+        let path = self.active_path();
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .with_context(|_| error::WriteAuditLogSnafu { path: path.clone() })?;
+        let metadata = file
+            .metadata()
+            .await
+            .with_context(|_| error::WriteAuditLogSnafu { path: path.clone() })?;
+        let size = metadata.len();
+        // a file left by a previous run keeps aging from its creation
+        let opened_at = if size == 0 {
+            now
+        } else {
+            metadata.created().or_else(|_| metadata.modified()).map_or(now, OffsetDateTime::from)
+        };
+        Ok(ActiveFile { file, size, opened_at })
+    }
+
+    async fn rotate(&self, now: OffsetDateTime) -> Result<(), AuditLogError> {
+        let path = self.active_path();
+        let rotated_path = self
+            .config
+            .directory
+            .join(format!("{ROTATED_FILE_PREFIX}{}{FILE_EXTENSION}", now.unix_timestamp_nanos()));
+        fs::rename(&path, &rotated_path)
+            .await
+            .with_context(|_| error::WriteAuditLogSnafu { path: rotated_path.clone() })?;
+        tracing::info!("Rotated audit log to `{}`", rotated_path.display());
+        Ok(())
+    }
+
+    /// Deletes the oldest rotated files beyond `max_files`.
+    async fn remove_old_files(&self) -> Result<(), AuditLogError> {
+        let files = self.files().await?;
+        let rotated_files = files.iter().filter(|path| rotated_at(path).is_some());
+        for path in rotated_files.skip(self.config.max_files) {
+            fs::remove_file(path)
+                .await
+                .with_context(|_| error::RemoveAuditLogSnafu { path: path.clone() })?;
+            tracing::info!("Removed audit log `{}`", path.display());
+        }
+        Ok(())
+    }
+
+    /// Returns the active file and the rotated files, the newest first.
+    async fn files(&self) -> Result<Vec<PathBuf>, AuditLogError> {
+        let directory = &self.config.directory;
+        let mut entries = fs::read_dir(directory)
+            .await
+            .with_context(|_| error::ReadAuditLogSnafu { path: directory.clone() })?;
+        let mut files = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .with_context(|_| error::ReadAuditLogSnafu { path: directory.clone() })?
+        {
+            if is_audit_file(&entry.path()) {
+                files.push(entry.path());
+            }
+        }
+        // the active file sorts first as it has no rotation timestamp
+        files.sort_by_key(|path| std::cmp::Reverse(rotated_at(path).unwrap_or(i128::MAX)));
+        Ok(files)
+    }
+}
+
+fn is_audit_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name == ACTIVE_FILE_NAME || rotated_at(path).is_some())
+}
+
+/// Returns the rotation timestamp, in nanoseconds, of a rotated file.
+fn rotated_at(path: &Path) -> Option<i128> {
+    path.file_name()?
+        .to_str()?
+        .strip_prefix(ROTATED_FILE_PREFIX)?
+        .strip_suffix(FILE_EXTENSION)?
+        .parse()
+        .ok()
+}
+
+/// Reads a file line by line and returns its last `limit` matching events,
+/// the most recent events of a file are at its end.
+async fn read_events(
+    file: fs::File,
+    path: &Path,
+    query: &AuditQuery,
+    limit: usize,
+) -> Result<VecDeque<AuditEvent>, AuditLogError> {
+    let mut lines = BufReader::new(file).lines();
+    let mut events = VecDeque::new();
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .with_context(|_| error::ReadAuditLogSnafu { path: path.to_path_buf() })?
+    {
+        if let Ok(event) = serde_json::from_str::<AuditEvent>(&line) {
+            if query.matches(&event) {
+                if events.len() == limit {
+                    events.pop_front();
+                }
+                events.push_back(event);
+            }
+        }
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn config(name: &str) -> AuditLogConfig {
+        let directory =
+            std::env::temp_dir().join(format!("kallax-audit-log-{name}-{}", std::process::id()));
+        let _unused = fs::remove_dir_all(&directory).await;
+        AuditLogConfig {
+            directory,
+            max_file_size: u64::MAX,
+            max_file_age: Duration::from_secs(3600),
+            max_files: usize::MAX,
+        }
+    }
+
+    async fn open(name: &str, max_file_size: u64) -> AuditLog {
+        AuditLog::open(AuditLogConfig { max_file_size, ..config(name).await }).await.unwrap()
+    }
+
+    fn event(chain_id: &str, seconds: i64) -> AuditEvent {
+        AuditEvent {
+            timestamp: OffsetDateTime::UNIX_EPOCH + time::Duration::seconds(seconds),
+            ..AuditEvent::new(AuditAction::InsertPeer, BlockchainLayer::Leafchain, None)
+                .with_chain_id(chain_id)
+        }
+    }
+
+    #[tokio::test]
+    async fn rotates_full_files_and_queries_all_of_them() {
+        let log = open("rotation", 1).await;
+        for seconds in 0..3 {
+            log.record(event("chain-1", seconds)).await;
+        }
+
+        let inner = log.inner.as_ref().unwrap();
+        assert_eq!(inner.files().await.unwrap().len(), 3);
+        let events = log.query(&AuditQuery::default()).await.unwrap();
+        assert_eq!(events, vec![event("chain-1", 0), event("chain-1", 1), event("chain-1", 2)]);
+    }
+
+    #[tokio::test]
+    async fn query_filters_on_chain_and_time_range() {
+        let log = open("query", u64::MAX).await;
+        log.record(event("chain-1", 10)).await;
+        log.record(event("chain-2", 20)).await;
+        log.record(event("chain-1", 30)).await;
+
+        let query = AuditQuery {
+            chain_id: Some("chain-1".to_string()),
+            from: Some(OffsetDateTime::UNIX_EPOCH + time::Duration::seconds(10)),
+            to: Some(OffsetDateTime::UNIX_EPOCH + time::Duration::seconds(30)),
+            ..AuditQuery::default()
+        };
+        assert_eq!(log.query(&query).await.unwrap(), vec![event("chain-1", 10)]);
+    }
+
+    #[tokio::test]
+    async fn query_returns_the_most_recent_events_up_to_limit() {
+        let log = open("limit", 1).await;
+        for seconds in 0..5 {
+            log.record(event("chain-1", seconds)).await;
+        }
+
+        let query = AuditQuery { limit: Some(2), ..AuditQuery::default() };
+        assert_eq!(
+            log.query(&query).await.unwrap(),
+            vec![event("chain-1", 3), event("chain-1", 4)]
+        );
+    }
+
+    #[tokio::test]
+    async fn query_keeps_the_most_recent_events_of_a_file() {
+        let log = open("limit-in-file", u64::MAX).await;
+        for seconds in 0..5 {
+            log.record(event("chain-1", seconds)).await;
+        }
+
+        let query = AuditQuery { limit: Some(2), ..AuditQuery::default() };
+        assert_eq!(
+            log.query(&query).await.unwrap(),
+            vec![event("chain-1", 3), event("chain-1", 4)]
+        );
+    }
+
+    #[tokio::test]
+    async fn oldest_rotated_files_are_removed() {
+        let config = AuditLogConfig { max_file_size: 1, max_files: 1, ..config("retention").await };
+        let log = AuditLog::open(config).await.unwrap();
+        for seconds in 0..4 {
+            log.record(event("chain-1", seconds)).await;
+        }
+
+        let inner = log.inner.as_ref().unwrap();
+        assert_eq!(inner.files().await.unwrap().len(), 2);
+        let events = log.query(&AuditQuery::default()).await.unwrap();
+        assert_eq!(events, vec![event("chain-1", 2), event("chain-1", 3)]);
+    }
+
+    #[tokio::test]
+    async fn reopened_file_keeps_its_age() {
+        let config =
+            AuditLogConfig { max_file_age: Duration::from_millis(100), ..config("reopen").await };
+        AuditLog::open(config.clone()).await.unwrap().record(event("chain-1", 0)).await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        // the file left by the previous log is already due for rotation
+        let log = AuditLog::open(config).await.unwrap();
+        log.record(event("chain-1", 1)).await;
+        assert_eq!(log.inner.as_ref().unwrap().files().await.unwrap().len(), 2);
+    }
+}
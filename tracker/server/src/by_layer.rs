@@ -0,0 +1,22 @@
+use kallax_primitives::BlockchainLayer;
+
+/// A value kept for each blockchain layer, e.g. the peer address books.
+#[derive(Clone, Debug)]
+pub struct ByLayer<T> {
+    pub rootchain: T,
+
+    pub leafchain: T,
+}
+
+impl<T> ByLayer<T> {
+    pub const fn new(rootchain: T, leafchain: T) -> Self {
+        Self { rootchain, leafchain }
+    }
+
+    pub const fn get(&self, layer: BlockchainLayer) -> &T {
+        match layer {
+            BlockchainLayer::Rootchain => &self.rootchain,
+            BlockchainLayer::Leafchain => &self.leafchain,
+        }
+    }
+}
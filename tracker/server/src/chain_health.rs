@@ -0,0 +1,148 @@
+use std::collections::{HashMap, HashSet};
+
+use kallax_primitives::{PeerMetadata, SyncState};
+use serde::Serialize;
+use time::{Duration, OffsetDateTime};
+use utoipa::ToSchema;
+
+/// Latest health record reported by a single node of a chain.
+#[derive(Clone, Debug)]
+pub struct NodeReport {
+    pub metadata: PeerMetadata,
+
+    /// When the reported best block number last changed.
+    pub best_block_changed_at: OffsetDateTime,
+}
+
+/// Health of a chain, aggregated over the latest reports of its nodes.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, ToSchema)]
+pub struct ChainHealth {
+    pub chain_id: String,
+
+    /// Nodes which reported a best block number.
+    pub reporting_nodes: usize,
+
+    pub syncing_nodes: usize,
+
+    pub syncing_fraction: f64,
+
+    pub lowest_best_block: Option<u64>,
+
+    pub highest_best_block: Option<u64>,
+
+    pub height_spread: u64,
+
+    pub highest_finalized_block: Option<u64>,
+
+    /// Nodes whose best block did not change within the stall threshold.
+    pub stuck_nodes: usize,
+
+    /// Every reporting node is stuck, the chain is likely not producing
+    /// blocks.
+    pub is_stalled: bool,
+
+    /// Nodes reported different hashes for the same finalized block number.
+    pub possible_fork: bool,
+}
+
+impl ChainHealth {
+    pub fn aggregate<'a, I>(
+        chain_id: &str,
+        reports: I,
+        now: OffsetDateTime,
+        stall_threshold: Duration,
+    ) -> Self
+    where
+        I: IntoIterator<Item = &'a NodeReport>,
+    {
+        let mut health = Self { chain_id: chain_id.to_string(), ..Self::default() };
+        let mut finalized_hashes = HashMap::<u64, HashSet<&str>>::new();
+
+        for NodeReport { metadata, best_block_changed_at } in reports {
+            if let (Some(number), Some(hash)) =
+                (metadata.finalized_block_number, metadata.finalized_block_hash.as_deref())
+            {
+                finalized_hashes.entry(number).or_default().insert(hash);
+            }
+            health.highest_finalized_block =
+                health.highest_finalized_block.max(metadata.finalized_block_number);
+
+            let Some(best_block) = metadata.best_block_number else {
+                continue;
+            };
+            health.reporting_nodes += 1;
+            if metadata.sync_state == Some(SyncState::Syncing) {
+                health.syncing_nodes += 1;
+            }
+            if now - *best_block_changed_at >= stall_threshold {
+                health.stuck_nodes += 1;
+            }
+            health.lowest_best_block =
+                Some(health.lowest_best_block.map_or(best_block, |lowest| lowest.min(best_block)));
+            health.highest_best_block = health.highest_best_block.max(Some(best_block));
+        }
+
+        if health.reporting_nodes > 0 {
+            #[allow(clippy::cast_precision_loss)]
+            let fraction = health.syncing_nodes as f64 / health.reporting_nodes as f64;
+            health.syncing_fraction = fraction;
+        }
+        if let (Some(lowest), Some(highest)) = (health.lowest_best_block, health.highest_best_block)
+        {
+            health.height_spread = highest - lowest;
+        }
+        health.is_stalled =
+            health.reporting_nodes > 0 && health.stuck_nodes == health.reporting_nodes;
+        health.possible_fork = finalized_hashes.values().any(|hashes| hashes.len() > 1);
+
+        health
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(best: u64, finalized: (u64, &str), changed_secs_ago: i64) -> NodeReport {
+        NodeReport {
+            metadata: PeerMetadata {
+                best_block_number: Some(best),
+                finalized_block_number: Some(finalized.0),
+                finalized_block_hash: Some(finalized.1.to_string()),
+                sync_state: Some(SyncState::Idle),
+                ..PeerMetadata::default()
+            },
+            best_block_changed_at: OffsetDateTime::UNIX_EPOCH + Duration::seconds(100)
+                - Duration::seconds(changed_secs_ago),
+        }
+    }
+
+    fn aggregate(reports: &[NodeReport]) -> ChainHealth {
+        let now = OffsetDateTime::UNIX_EPOCH + Duration::seconds(100);
+        ChainHealth::aggregate("chain-1", reports, now, Duration::seconds(60))
+    }
+
+    #[test]
+    fn aggregates_heights_and_syncing_nodes() {
+        let mut syncing = report(90, (80, "0xaa"), 0);
+        syncing.metadata.sync_state = Some(SyncState::Syncing);
+        let health = aggregate(&[report(100, (98, "0xbb"), 0), syncing]);
+
+        assert_eq!(health.reporting_nodes, 2);
+        assert_eq!(health.syncing_nodes, 1);
+        assert!((health.syncing_fraction - 0.5).abs() < f64::EPSILON);
+        assert_eq!(health.height_spread, 10);
+        assert_eq!(health.highest_finalized_block, Some(98));
+        assert!(!health.is_stalled);
+        assert!(!health.possible_fork);
+    }
+
+    #[test]
+    fn flags_stalls_and_forks() {
+        let health = aggregate(&[report(100, (98, "0xaa"), 70), report(100, (98, "0xbb"), 90)]);
+
+        assert_eq!(health.stuck_nodes, 2);
+        assert!(health.is_stalled);
+        assert!(health.possible_fork);
+    }
+}
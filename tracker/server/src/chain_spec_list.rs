@@ -1,6 +1,6 @@
 use std::{collections::HashMap, sync::Arc};
 
-use kallax_primitives::{BlockchainLayer, ChainSpec};
+use kallax_primitives::{BlockchainLayer, ChainSpec, PeerAddress};
 use tokio::sync::Mutex;
 
 #[derive(Clone, Debug)]
@@ -45,6 +45,17 @@ impl ChainSpecList {
         self.chain_specs.lock().await.get(chain_id).cloned()
     }
 
+    /// Returns the static `bootNodes` of the chain spec, which are handed
+    /// out when no peer of the chain is tracked.
+    pub async fn boot_nodes(&self, chain_id: &str) -> Vec<PeerAddress> {
+        self.chain_specs
+            .lock()
+            .await
+            .get(chain_id)
+            .map(|spec| spec.boot_nodes().to_vec())
+            .unwrap_or_default()
+    }
+
     #[allow(dead_code)]
     pub async fn chain_ids(&self) -> Vec<String> {
         self.chain_specs.lock().await.keys().cloned().collect()
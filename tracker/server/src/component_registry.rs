@@ -0,0 +1,276 @@
+use std::{collections::HashMap, net::IpAddr, sync::Arc};
+
+use kallax_primitives::{BlockchainComponent, ComponentRegistration, ServedChain};
+use serde::Serialize;
+use time::Duration;
+use tokio::sync::Mutex;
+use utoipa::ToSchema;
+
+use crate::error::{ComponentError, LeaseError};
+
+/// A registered component as it is served.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct Component {
+    pub kind: BlockchainComponent,
+
+    pub name: String,
+
+    pub endpoints: Vec<String>,
+
+    pub chains: Vec<ServedChain>,
+
+    pub version: Option<String>,
+
+    /// IP address of the client which registered the component.
+    #[schema(value_type = Option<String>)]
+    pub source: Option<IpAddr>,
+
+    #[serde(with = "time::serde::rfc3339")]
+    pub last_seen: time::OffsetDateTime,
+}
+
+#[derive(Clone, Debug)]
+struct ComponentState {
+    component: Component,
+
+    expires_at: time::OffsetDateTime,
+}
+
+/// Components which are not nodes of a chain, e.g. bridge relayers and
+/// offline workers, registered under leases which expire unless they are
+/// kept alive.
+#[derive(Clone, Debug)]
+pub struct ComponentRegistry {
+    ttl: Duration,
+
+    max_components: usize,
+
+    /// Components by lease ID.
+    components: Arc<Mutex<HashMap<String, ComponentState>>>,
+}
+
+impl Default for ComponentRegistry {
+    fn default() -> Self {
+        Self::with_ttl(std::time::Duration::from_secs(120))
+    }
+}
+
+impl ComponentRegistry {
+    pub fn with_ttl(ttl: std::time::Duration) -> Self {
+        let ttl = Duration::new(i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX), 0);
+        Self { ttl, max_components: usize::MAX, components: Arc::default() }
+    }
+
+    /// New components are rejected once `max_components` are registered,
+    /// replacing a registration is always allowed.
+    #[must_use]
+    pub const fn with_max_components(mut self, max_components: usize) -> Self {
+        self.max_components = max_components;
+        self
+    }
+
+    /// Registers a component under a new lease, replacing the registration
+    /// of the component of the same kind and name. Returns the lease ID and
+    /// the TTL of the lease.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the component is a chain or has no name, or if the
+    /// registry is full.
+    pub async fn register(
+        &self,
+        ComponentRegistration { kind, name, endpoints, chains, version }: ComponentRegistration,
+        source: Option<IpAddr>,
+    ) -> Result<(String, std::time::Duration), ComponentError> {
+        if kind.is_chain() {
+            return Err(ComponentError::ChainIsNotComponent { kind });
+        }
+        if name.is_empty() {
+            return Err(ComponentError::MissingComponentName);
+        }
+
+        let now = time::OffsetDateTime::now_utc();
+        let lease_id = format!("{:032x}", rand::random::<u128>());
+        let component =
+            Component { kind, name, endpoints, chains, version, source, last_seen: now };
+
+        let mut components = self.components.lock().await;
+        let is_registered = |state: &ComponentState| {
+            state.component.kind == component.kind && state.component.name == component.name
+        };
+        if components.len() >= self.max_components && !components.values().any(is_registered) {
+            return Err(ComponentError::TooManyComponents { limit: self.max_components });
+        }
+        components.retain(|_, state| !is_registered(state));
+        tracing::info!("{kind} `{}` is registered with lease `{lease_id}`", component.name);
+        let _ = components.insert(
+            lease_id.clone(),
+            ComponentState { component, expires_at: now.saturating_add(self.ttl) },
+        );
+        drop(components);
+
+        Ok((lease_id, self.ttl.unsigned_abs()))
+    }
+
+    /// Extends a lease created by [`Self::register`], returns the TTL of the
+    /// lease.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lease is unknown or expired, the component
+    /// should register again.
+    pub async fn keep_alive(&self, lease_id: &str) -> Result<std::time::Duration, LeaseError> {
+        let now = time::OffsetDateTime::now_utc();
+        match self.components.lock().await.get_mut(lease_id) {
+            Some(state) if state.expires_at > now => {
+                state.component.last_seen = now;
+                state.expires_at = now.saturating_add(self.ttl);
+                Ok(self.ttl.unsigned_abs())
+            }
+            _ => Err(LeaseError::LeaseNotFound { lease_id: lease_id.to_string() }),
+        }
+    }
+
+    /// Returns the live components of `kind` serving `chain`, or of any kind
+    /// and chain if `None`, ordered by kind and name.
+    pub async fn list(
+        &self,
+        kind: Option<BlockchainComponent>,
+        chain: Option<&ServedChain>,
+    ) -> Vec<Component> {
+        let now = time::OffsetDateTime::now_utc();
+        let mut components = self
+            .components
+            .lock()
+            .await
+            .values()
+            .filter(|state| state.expires_at > now)
+            .map(|state| &state.component)
+            .filter(|component| kind.is_none_or(|kind| component.kind == kind))
+            .filter(|component| chain.is_none_or(|chain| component.chains.contains(chain)))
+            .cloned()
+            .collect::<Vec<_>>();
+        components.sort_by(|a, b| (a.kind, &a.name).cmp(&(b.kind, &b.name)));
+        components
+    }
+
+    /// Removes the components whose lease expired, and returns them.
+    pub async fn flush(&self) -> Vec<Component> {
+        let now = time::OffsetDateTime::now_utc();
+        let mut expired = Vec::new();
+        self.components.lock().await.retain(|_, state| {
+            let is_alive = state.expires_at > now;
+            if !is_alive {
+                expired.push(state.component.clone());
+            }
+            is_alive
+        });
+        for component in &expired {
+            tracing::info!("{} `{}` is expired", component.kind, component.name);
+        }
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kallax_primitives::{
+        BlockchainComponent, BlockchainLayer, ComponentRegistration, ServedChain,
+    };
+
+    use super::ComponentRegistry;
+    use crate::error::ComponentError;
+
+    fn bridge(name: &str) -> ComponentRegistration {
+        ComponentRegistration {
+            kind: BlockchainComponent::Bridge,
+            name: name.to_string(),
+            endpoints: vec!["http://relayer:9615/metrics".to_string()],
+            chains: vec![
+                ServedChain { layer: BlockchainLayer::Rootchain, chain_id: "root".to_string() },
+                ServedChain { layer: BlockchainLayer::Leafchain, chain_id: "leaf".to_string() },
+            ],
+            version: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn components_are_listed_by_kind_and_chain() {
+        let registry = ComponentRegistry::default();
+        let _ = registry.register(bridge("relayer"), None).await.unwrap();
+        let _ = registry
+            .register(
+                ComponentRegistration {
+                    kind: BlockchainComponent::OfflineWorker,
+                    chains: Vec::new(),
+                    ..bridge("worker")
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(registry.list(None, None).await.len(), 2);
+        let bridges = registry.list(Some(BlockchainComponent::Bridge), None).await;
+        assert_eq!(bridges.len(), 1);
+        assert_eq!(bridges[0].name, "relayer");
+
+        let leaf = ServedChain { layer: BlockchainLayer::Leafchain, chain_id: "leaf".to_string() };
+        assert_eq!(registry.list(None, Some(&leaf)).await.len(), 1);
+        let other =
+            ServedChain { layer: BlockchainLayer::Leafchain, chain_id: "other".to_string() };
+        assert!(registry.list(None, Some(&other)).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn registration_replaces_the_previous_one() {
+        let registry = ComponentRegistry::default();
+        let (previous, _) = registry.register(bridge("relayer"), None).await.unwrap();
+        let (lease_id, _) = registry.register(bridge("relayer"), None).await.unwrap();
+
+        assert_eq!(registry.list(None, None).await.len(), 1);
+        assert!(registry.keep_alive(&previous).await.is_err());
+        assert!(registry.keep_alive(&lease_id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn registrations_are_limited() {
+        let registry = ComponentRegistry::default().with_max_components(1);
+        let _ = registry.register(bridge("relayer"), None).await.unwrap();
+
+        assert_eq!(
+            registry.register(bridge("other"), None).await,
+            Err(ComponentError::TooManyComponents { limit: 1 })
+        );
+        // replacing the registration does not add a component
+        assert!(registry.register(bridge("relayer"), None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn overflowing_ttl_never_expires() {
+        let registry = ComponentRegistry::with_ttl(std::time::Duration::MAX);
+        let (lease_id, ttl) = registry.register(bridge("relayer"), None).await.unwrap();
+
+        assert!(ttl > std::time::Duration::from_secs(u64::from(u32::MAX)));
+        assert!(registry.keep_alive(&lease_id).await.is_ok());
+        assert!(registry.flush().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn chains_are_not_components() {
+        let registry = ComponentRegistry::default();
+        let registration =
+            ComponentRegistration { kind: BlockchainComponent::Rootchain, ..bridge("node") };
+        assert!(registry.register(registration, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn expired_components_are_flushed() {
+        let registry = ComponentRegistry::with_ttl(std::time::Duration::ZERO);
+        let (lease_id, _) = registry.register(bridge("relayer"), None).await.unwrap();
+
+        assert!(registry.list(None, None).await.is_empty());
+        assert!(registry.keep_alive(&lease_id).await.is_err());
+        assert_eq!(registry.flush().await.len(), 1);
+    }
+}
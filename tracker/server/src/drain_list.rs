@@ -0,0 +1,100 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tokio::sync::Mutex;
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, Default, Deserialize, ToSchema)]
+pub struct DrainRequest {
+    /// The peer stops draining after this many seconds, it drains until
+    /// undrained if `None`.
+    #[serde(default)]
+    pub expires_in_seconds: Option<u64>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, ToSchema)]
+pub struct DrainEntry {
+    pub peer_id: String,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+/// Peer IDs which are not handed out to other peers, for example during
+/// planned maintenance. Draining peers keep their registration.
+#[derive(Clone, Debug, Default)]
+pub struct DrainList {
+    entries: Arc<Mutex<HashMap<String, Option<OffsetDateTime>>>>,
+}
+
+impl DrainList {
+    pub async fn drain(
+        &self,
+        peer_id: String,
+        DrainRequest { expires_in_seconds }: DrainRequest,
+    ) -> DrainEntry {
+        let expires_at = expires_in_seconds.and_then(|seconds| {
+            OffsetDateTime::now_utc()
+                .checked_add(time::Duration::new(i64::try_from(seconds).unwrap_or(i64::MAX), 0))
+        });
+        self.entries.lock().await.insert(peer_id.clone(), expires_at);
+        match expires_at {
+            Some(expires_at) => tracing::info!("Peer `{peer_id}` is draining until {expires_at}"),
+            None => tracing::info!("Peer `{peer_id}` is draining"),
+        }
+        DrainEntry { peer_id, expires_at }
+    }
+
+    /// Returns `false` if the peer was not draining.
+    pub async fn undrain(&self, peer_id: &str) -> bool {
+        let is_removed = self.entries.lock().await.remove(peer_id).is_some();
+        if is_removed {
+            tracing::info!("Peer `{peer_id}` stops draining");
+        }
+        is_removed
+    }
+
+    /// Returns the draining peers sorted by peer ID, expired entries are
+    /// dropped.
+    pub async fn entries(&self) -> Vec<DrainEntry> {
+        let mut entries = self
+            .active()
+            .await
+            .into_iter()
+            .map(|(peer_id, expires_at)| DrainEntry { peer_id, expires_at })
+            .collect::<Vec<_>>();
+        entries.sort_unstable_by(|a, b| a.peer_id.cmp(&b.peer_id));
+        entries
+    }
+
+    pub async fn peer_ids(&self) -> HashSet<String> {
+        self.active().await.into_keys().collect()
+    }
+
+    async fn active(&self) -> HashMap<String, Option<OffsetDateTime>> {
+        let now = OffsetDateTime::now_utc();
+        let mut entries = self.entries.lock().await;
+        entries.retain(|_, expires_at| expires_at.is_none_or(|expires_at| expires_at > now));
+        entries.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn expired_entries_are_dropped() {
+        let list = DrainList::default();
+        list.drain("a".to_string(), DrainRequest::default()).await;
+        list.drain("b".to_string(), DrainRequest { expires_in_seconds: Some(0) }).await;
+
+        assert_eq!(list.peer_ids().await, HashSet::from(["a".to_string()]));
+        assert!(list.undrain("a").await);
+        assert!(list.entries().await.is_empty());
+    }
+}
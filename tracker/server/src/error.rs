@@ -1,6 +1,12 @@
-use std::fmt;
+use std::{collections::HashMap, fmt, path::PathBuf};
 
+use axum::response::{IntoResponse, Response};
+use kallax_primitives::BlockchainComponent;
 use snafu::{Backtrace, Snafu};
+use tonic::Code;
+use tonic_types::{ErrorDetails, StatusExt};
+
+use crate::web::problem::{Problem, ProblemCode};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -10,14 +16,316 @@ pub enum Error {
     #[snafu(display("Error occurs while starting tonic server, error: {source}"))]
     StartTonicServer { source: tonic::transport::Error, backtrace: Backtrace },
 
+    #[snafu(display("Error occurs while building gRPC reflection service, error: {source}"))]
+    BuildReflectionService { source: tonic_reflection::server::Error },
+
     #[snafu(display("Error occurs while starting api server, error: {source}"))]
     StartApiServer { source: hyper::Error },
 
     #[snafu(display("Error occurs while serving api server, error: {source}"))]
     ServeApiServer { source: hyper::Error },
+
+    #[snafu(display("Error occurs while loading peer policy, error: {source}"))]
+    LoadPeerPolicy { source: PeerPolicyError },
+
+    #[snafu(display("Error occurs while loading alert rules, error: {source}"))]
+    LoadAlertRules { source: AlertRulesError },
+
+    #[snafu(display("Error occurs while opening audit log, error: {source}"))]
+    OpenAuditLog { source: AuditLogError },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub))]
+pub enum AuditLogError {
+    #[snafu(display("Could not create audit log directory `{}`, error: {source}", path.display()))]
+    CreateAuditLogDirectory { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not write audit log `{}`, error: {source}", path.display()))]
+    WriteAuditLog { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not read audit log `{}`, error: {source}", path.display()))]
+    ReadAuditLog { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not remove audit log `{}`, error: {source}", path.display()))]
+    RemoveAuditLog { path: PathBuf, source: std::io::Error },
+}
+
+impl From<AuditLogError> for Problem {
+    fn from(err: AuditLogError) -> Self {
+        Self::new(ProblemCode::Internal, err)
+    }
 }
 
+impl IntoResponse for AuditLogError {
+    fn into_response(self) -> Response {
+        Problem::from(self).into_response()
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub))]
+pub enum AlertRulesError {
+    #[snafu(display("Could not read alert rules file `{}`, error: {source}", path.display()))]
+    ReadAlertRules { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not parse alert rules file `{}`, error: {source}", path.display()))]
+    ParseAlertRules { path: PathBuf, source: serde_json::Error },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub))]
+pub enum PeerPolicyError {
+    #[snafu(display("Could not read peer policy file `{}`, error: {source}", path.display()))]
+    ReadFile { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not parse peer policy file `{}`, error: {source}", path.display()))]
+    ParseFile { path: PathBuf, source: serde_json::Error },
+
+    #[snafu(display("Could not write peer policy file `{}`, error: {source}", path.display()))]
+    Persist { path: PathBuf, source: std::io::Error },
+}
+
+impl From<PeerPolicyError> for Problem {
+    fn from(err: PeerPolicyError) -> Self {
+        Self::new(ProblemCode::Internal, err)
+    }
+}
+
+impl IntoResponse for PeerPolicyError {
+    fn into_response(self) -> Response {
+        Problem::from(self).into_response()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Snafu)]
+#[snafu(visibility(pub))]
+#[allow(clippy::enum_variant_names)]
+pub enum CapacityError {
+    #[snafu(display("Number of chains reaches the limit {limit}"))]
+    TooManyChains { limit: usize },
+
+    #[snafu(display("Number of peers of chain `{chain_id}` reaches the limit {limit}"))]
+    TooManyPeers { chain_id: String, limit: usize },
+
+    #[snafu(display("Number of addresses of peer `{peer_id}` reaches the limit {limit}"))]
+    TooManyAddresses { peer_id: String, limit: usize },
+}
+
+impl From<CapacityError> for tonic::Status {
+    fn from(err: CapacityError) -> Self {
+        let subject = match &err {
+            CapacityError::TooManyChains { .. } => "chains".to_string(),
+            CapacityError::TooManyPeers { chain_id, .. } => format!("chain:{chain_id}"),
+            CapacityError::TooManyAddresses { peer_id, .. } => format!("peer:{peer_id}"),
+        };
+        let message = err.to_string();
+        Self::with_error_details(
+            Code::ResourceExhausted,
+            message.clone(),
+            ErrorDetails::with_quota_failure_violation(subject, message),
+        )
+    }
+}
+
+impl From<CapacityError> for Problem {
+    fn from(err: CapacityError) -> Self {
+        Self::new(ProblemCode::CapacityExceeded, err)
+    }
+}
+
+impl IntoResponse for CapacityError {
+    fn into_response(self) -> Response {
+        Problem::from(self).into_response()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Snafu)]
+#[snafu(visibility(pub))]
+pub enum InsertPeerError {
+    #[snafu(display("Peer `{peer_address}` is rejected by peer policy"))]
+    RejectedByPolicy { peer_address: String },
+
+    #[snafu(context(false), display("{source}"))]
+    Capacity { source: CapacityError },
+}
+
+impl From<InsertPeerError> for tonic::Status {
+    fn from(err: InsertPeerError) -> Self {
+        match err {
+            InsertPeerError::RejectedByPolicy { ref peer_address } => Self::with_error_details(
+                Code::PermissionDenied,
+                err.to_string(),
+                ErrorDetails::with_error_info(
+                    "REJECTED_BY_POLICY",
+                    ERROR_DOMAIN,
+                    HashMap::from([("peer_address".to_string(), peer_address.clone())]),
+                ),
+            ),
+            InsertPeerError::Capacity { source } => source.into(),
+        }
+    }
+}
+
+impl From<InsertPeerError> for Problem {
+    fn from(err: InsertPeerError) -> Self {
+        match err {
+            InsertPeerError::RejectedByPolicy { .. } => {
+                Self::new(ProblemCode::RejectedByPolicy, err)
+            }
+            InsertPeerError::Capacity { source } => source.into(),
+        }
+    }
+}
+
+impl IntoResponse for InsertPeerError {
+    fn into_response(self) -> Response {
+        Problem::from(self).into_response()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Snafu)]
+#[snafu(visibility(pub))]
+pub enum ComponentError {
+    #[snafu(display("{kind} nodes are registered as peers, not as components"))]
+    ChainIsNotComponent { kind: BlockchainComponent },
+
+    #[snafu(display("Component name must be provided"))]
+    MissingComponentName,
+
+    #[snafu(display("Number of components reaches the limit {limit}"))]
+    TooManyComponents { limit: usize },
+}
+
+impl From<ComponentError> for tonic::Status {
+    fn from(err: ComponentError) -> Self {
+        match err {
+            ComponentError::ChainIsNotComponent { .. } => invalid_field_status("kind", err),
+            ComponentError::MissingComponentName => invalid_field_status("name", err),
+            ComponentError::TooManyComponents { .. } => {
+                let message = err.to_string();
+                Self::with_error_details(
+                    Code::ResourceExhausted,
+                    message.clone(),
+                    ErrorDetails::with_quota_failure_violation("components", message),
+                )
+            }
+        }
+    }
+}
+
+impl From<ComponentError> for Problem {
+    fn from(err: ComponentError) -> Self {
+        match err {
+            ComponentError::ChainIsNotComponent { .. } | ComponentError::MissingComponentName => {
+                Self::new(ProblemCode::InvalidArgument, err)
+            }
+            ComponentError::TooManyComponents { .. } => {
+                Self::new(ProblemCode::CapacityExceeded, err)
+            }
+        }
+    }
+}
+
+impl IntoResponse for ComponentError {
+    fn into_response(self) -> Response {
+        Problem::from(self).into_response()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Snafu)]
+#[snafu(visibility(pub))]
+pub enum LeaseError {
+    #[snafu(display("Lease `{lease_id}` is unknown or expired"))]
+    LeaseNotFound { lease_id: String },
+}
+
+impl From<LeaseError> for tonic::Status {
+    fn from(err: LeaseError) -> Self {
+        let LeaseError::LeaseNotFound { ref lease_id } = err;
+        not_found_status("lease", lease_id, err.to_string())
+    }
+}
+
+/// Domain of the `ErrorInfo` details of tracker errors.
+pub const ERROR_DOMAIN: &str = "tracker.kallax";
+
+/// Returns an `INVALID_ARGUMENT` status for a missing or malformed `field`.
+#[must_use]
+pub fn into_invalid_argument_status(field: &str) -> tonic::Status {
+    invalid_field_status(field, format!("`{field}` is missing or invalid"))
+}
+
+/// Returns an `INVALID_ARGUMENT` status with a `BadRequest` violation of
+/// `field`.
+#[must_use]
+pub fn invalid_field_status(field: &str, description: impl fmt::Display) -> tonic::Status {
+    let description = description.to_string();
+    tonic::Status::with_error_details(
+        Code::InvalidArgument,
+        description.clone(),
+        ErrorDetails::with_bad_request_violation(field, description),
+    )
+}
+
+/// Returns a `NOT_FOUND` status with the `ResourceInfo` of the missing
+/// resource.
 #[must_use]
-pub fn into_invalid_argument_status(err: impl fmt::Display) -> tonic::Status {
-    tonic::Status::invalid_argument(err.to_string())
+pub fn not_found_status(
+    resource_type: &str,
+    resource_name: &str,
+    message: impl Into<String>,
+) -> tonic::Status {
+    let message = message.into();
+    tonic::Status::with_error_details(
+        Code::NotFound,
+        message.clone(),
+        ErrorDetails::with_resource_info(resource_type, resource_name, "", message),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use tonic::Code;
+    use tonic_types::StatusExt;
+
+    use super::{CapacityError, InsertPeerError, LeaseError, ERROR_DOMAIN};
+
+    const PEER_ADDR: &str =
+        "/ip4/10.0.0.1/tcp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo";
+
+    #[test]
+    fn capacity_error_carries_quota_failure() {
+        let err = CapacityError::TooManyPeers { chain_id: "thx".to_string(), limit: 2 };
+        let status = tonic::Status::from(InsertPeerError::from(err.clone()));
+
+        assert_eq!(status.code(), Code::ResourceExhausted);
+        let quota_failure = status.get_details_quota_failure().unwrap();
+        assert_eq!(quota_failure.violations.len(), 1);
+        assert_eq!(quota_failure.violations[0].subject, "chain:thx");
+        assert_eq!(quota_failure.violations[0].description, err.to_string());
+    }
+
+    #[test]
+    fn policy_rejection_carries_error_info() {
+        let status = tonic::Status::from(InsertPeerError::RejectedByPolicy {
+            peer_address: PEER_ADDR.to_string(),
+        });
+
+        assert_eq!(status.code(), Code::PermissionDenied);
+        let error_info = status.get_details_error_info().unwrap();
+        assert_eq!(error_info.reason, "REJECTED_BY_POLICY");
+        assert_eq!(error_info.domain, ERROR_DOMAIN);
+        assert_eq!(error_info.metadata["peer_address"], PEER_ADDR);
+    }
+
+    #[test]
+    fn unknown_lease_carries_resource_info() {
+        let status = tonic::Status::from(LeaseError::LeaseNotFound { lease_id: "1".to_string() });
+
+        assert_eq!(status.code(), Code::NotFound);
+        let resource_info = status.get_details_resource_info().unwrap();
+        assert_eq!(resource_info.resource_type, "lease");
+        assert_eq!(resource_info.resource_name, "1");
+    }
 }
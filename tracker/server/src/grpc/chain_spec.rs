@@ -0,0 +1,65 @@
+use kallax_primitives::ChainSpec;
+use kallax_tracker_proto::v2;
+use tonic::{Request, Response, Status};
+
+use crate::{
+    audit_log::{AuditAction, AuditEvent, AuditLog},
+    by_layer::ByLayer,
+    chain_spec_list::ChainSpecList,
+    error,
+};
+
+#[derive(Clone, Debug)]
+pub struct Service {
+    chain_spec_lists: ByLayer<ChainSpecList>,
+
+    audit_log: AuditLog,
+}
+
+impl Service {
+    pub const fn new(chain_spec_lists: ByLayer<ChainSpecList>, audit_log: AuditLog) -> Self {
+        Self { chain_spec_lists, audit_log }
+    }
+}
+
+#[tonic::async_trait]
+impl v2::ChainSpecService for Service {
+    async fn insert(
+        &self,
+        req: Request<v2::InsertChainSpecRequest>,
+    ) -> Result<Response<v2::InsertChainSpecResponse>, Status> {
+        let caller = req.remote_addr();
+        let v2::InsertChainSpecRequest { layer, chain_id, spec } = req.into_inner();
+        let layer = super::blockchain_layer(layer)
+            .ok_or_else(|| error::into_invalid_argument_status("layer"))?;
+
+        let spec = ChainSpec::try_from(spec.as_ref())
+            .map_err(|e| error::invalid_field_status("spec", e))?;
+        let action = if self.chain_spec_lists.get(layer).insert(&chain_id, spec).await {
+            AuditAction::ReplaceChainSpec
+        } else {
+            AuditAction::AddChainSpec
+        };
+        self.audit_log
+            .record(AuditEvent::new(action, layer, caller).with_chain_id(&chain_id))
+            .await;
+
+        Ok(Response::new(v2::InsertChainSpecResponse { chain_id }))
+    }
+
+    async fn get(
+        &self,
+        req: Request<v2::GetChainSpecRequest>,
+    ) -> Result<Response<v2::GetChainSpecResponse>, Status> {
+        let v2::GetChainSpecRequest { layer, chain_id } = req.into_inner();
+        let layer = super::blockchain_layer(layer)
+            .ok_or_else(|| error::into_invalid_argument_status("layer"))?;
+
+        if let Some(spec) = self.chain_spec_lists.get(layer).get(&chain_id).await {
+            Ok(Response::new(v2::GetChainSpecResponse { chain_id, spec: spec.as_ref().to_vec() }))
+        } else {
+            let message = format!("chain spec `{chain_id}` not found");
+            Err(error::not_found_status("chain-spec", &chain_id, message))
+        }
+    }
+}
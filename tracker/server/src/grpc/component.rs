@@ -0,0 +1,104 @@
+use std::time::SystemTime;
+
+use kallax_primitives::{BlockchainComponent, ComponentRegistration, ServedChain};
+use kallax_tracker_proto::v2;
+use tonic::{Request, Response, Status};
+
+use crate::{
+    component_registry::{Component, ComponentRegistry},
+    error,
+};
+
+#[derive(Clone, Debug)]
+pub struct Service {
+    component_registry: ComponentRegistry,
+}
+
+impl Service {
+    pub const fn new(component_registry: ComponentRegistry) -> Self {
+        Self { component_registry }
+    }
+}
+
+impl From<Component> for v2::Component {
+    fn from(
+        Component { kind, name, endpoints, chains, version, source: _, last_seen }: Component,
+    ) -> Self {
+        Self {
+            kind: v2::ComponentKind::from(kind).into(),
+            name,
+            endpoints,
+            chains: chains.into_iter().map(v2::ServedChain::from).collect(),
+            version,
+            last_seen: Some(SystemTime::from(last_seen).into()),
+        }
+    }
+}
+
+fn served_chains(
+    chains: Vec<v2::ServedChain>,
+) -> Result<Vec<ServedChain>, kallax_primitives::Error> {
+    chains.into_iter().map(ServedChain::try_from).collect()
+}
+
+#[tonic::async_trait]
+impl v2::ComponentService for Service {
+    async fn register(
+        &self,
+        req: Request<v2::RegisterComponentRequest>,
+    ) -> Result<Response<v2::RegisterComponentResponse>, Status> {
+        let caller = req.remote_addr().map(|caller| caller.ip());
+        let v2::RegisterComponentRequest { kind, name, endpoints, chains, version } =
+            req.into_inner();
+        let kind = v2::ComponentKind::try_from(kind)
+            .ok()
+            .and_then(|kind| BlockchainComponent::try_from(kind).ok())
+            .ok_or_else(|| error::into_invalid_argument_status("kind"))?;
+        let registration = ComponentRegistration {
+            kind,
+            name,
+            endpoints,
+            chains: served_chains(chains)
+                .map_err(|err| error::invalid_field_status("chains", err))?,
+            version,
+        };
+
+        let (lease_id, ttl) = self.component_registry.register(registration, caller).await?;
+        Ok(Response::new(v2::RegisterComponentResponse { lease_id, ttl_seconds: ttl.as_secs() }))
+    }
+
+    async fn keep_alive(
+        &self,
+        req: Request<v2::KeepAliveComponentRequest>,
+    ) -> Result<Response<v2::KeepAliveComponentResponse>, Status> {
+        let lease_id = req.into_inner().lease_id;
+        let ttl = self.component_registry.keep_alive(&lease_id).await.map_err(|err| {
+            tracing::debug!("Reject keep-alive, error: {err}");
+            Status::from(err)
+        })?;
+        Ok(Response::new(v2::KeepAliveComponentResponse { ttl_seconds: ttl.as_secs() }))
+    }
+
+    async fn list(
+        &self,
+        req: Request<v2::ListComponentsRequest>,
+    ) -> Result<Response<v2::ListComponentsResponse>, Status> {
+        let v2::ListComponentsRequest { kind, chain } = req.into_inner();
+        let kind = v2::ComponentKind::try_from(kind)
+            .ok()
+            .and_then(|kind| BlockchainComponent::try_from(kind).ok());
+        let chain = chain
+            .map(ServedChain::try_from)
+            .transpose()
+            .map_err(|e| error::invalid_field_status("chain", e))?;
+
+        let components = self
+            .component_registry
+            .list(kind, chain.as_ref())
+            .await
+            .into_iter()
+            .map(v2::Component::from)
+            .collect();
+        Ok(Response::new(v2::ListComponentsResponse { components }))
+    }
+}
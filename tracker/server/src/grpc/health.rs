@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use kallax_tracker_proto::{
+    v2, LeafchainPeerServiceServer, LeafchainSpecServiceServer, RootchainPeerServiceServer,
+    RootchainSpecServiceServer,
+};
+use tonic::server::NamedService;
+use tonic_health::{server::HealthReporter, ServingStatus};
+use tonic_reflection::server::{ServerReflection, ServerReflectionServer};
+
+/// Services whose status is reported by `grpc.health.v1.Health`, the empty
+/// name stands for the whole server.
+const SERVICE_NAMES: [&str; 8] = [
+    "",
+    <RootchainSpecServiceServer<super::rootchain_spec::Service> as NamedService>::NAME,
+    <RootchainPeerServiceServer<super::rootchain_peer::Service> as NamedService>::NAME,
+    <LeafchainSpecServiceServer<super::leafchain_spec::Service> as NamedService>::NAME,
+    <LeafchainPeerServiceServer<super::leafchain_peer::Service> as NamedService>::NAME,
+    <v2::ChainSpecServiceServer<super::chain_spec::Service> as NamedService>::NAME,
+    <v2::PeerServiceServer<super::peer::Service> as NamedService>::NAME,
+    <v2::ComponentServiceServer<super::component::Service> as NamedService>::NAME,
+];
+
+/// Sets the status of every service of the gRPC server.
+pub async fn set_serving_status(reporter: &mut HealthReporter, status: ServingStatus) {
+    for service_name in SERVICE_NAMES {
+        reporter.set_service_status(service_name, status).await;
+    }
+}
+
+/// Time the server keeps answering after reporting its services as not
+/// serving, so that health checking clients and load balancers observe the
+/// status before the listener goes away.
+pub const SHUTDOWN_DRAIN_PERIOD: Duration = Duration::from_secs(5);
+
+/// Reports every service as not serving, then waits for `drain_period`,
+/// the server should stop once it returns.
+pub async fn report_shutdown(reporter: &mut HealthReporter, drain_period: Duration) {
+    set_serving_status(reporter, ServingStatus::NotServing).await;
+    tokio::time::sleep(drain_period).await;
+}
+
+/// Builds the `grpc.reflection.v1alpha.ServerReflection` service, which
+/// describes the tracker services and the health service.
+pub fn reflection_service(
+) -> Result<ServerReflectionServer<impl ServerReflection>, tonic_reflection::server::Error> {
+    tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(kallax_tracker_proto::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, time::Duration};
+
+    use tokio::net::TcpListener;
+    use tonic::transport::{server::TcpIncoming, Channel, Server};
+    use tonic_health::{
+        pb::{
+            health_check_response::ServingStatus as ProtoServingStatus,
+            health_client::HealthClient, HealthCheckRequest,
+        },
+        server::HealthReporter,
+        ServingStatus,
+    };
+    use tonic_reflection::pb::{
+        server_reflection_client::ServerReflectionClient,
+        server_reflection_request::MessageRequest, server_reflection_response::MessageResponse,
+        ServerReflectionRequest,
+    };
+
+    use super::{reflection_service, report_shutdown, set_serving_status, SERVICE_NAMES};
+
+    /// Serves the health and reflection services the way the tracker does,
+    /// with every service reported as serving.
+    async fn serve() -> (HealthReporter, Channel) {
+        let (mut reporter, health_service) = tonic_health::server::health_reporter();
+        set_serving_status(&mut reporter, ServingStatus::Serving).await;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = Server::builder()
+            .add_service(health_service)
+            .add_service(reflection_service().unwrap())
+            .serve_with_incoming(TcpIncoming::from_listener(listener, true, None).unwrap());
+        drop(tokio::spawn(server));
+
+        let channel =
+            Channel::from_shared(format!("http://{addr}")).unwrap().connect().await.unwrap();
+        (reporter, channel)
+    }
+
+    async fn statuses(client: &mut HealthClient<Channel>) -> Vec<ProtoServingStatus> {
+        let mut statuses = Vec::new();
+        for service in SERVICE_NAMES {
+            let response = client
+                .check(HealthCheckRequest { service: service.to_string() })
+                .await
+                .unwrap()
+                .into_inner();
+            statuses.push(response.status());
+        }
+        statuses
+    }
+
+    #[tokio::test]
+    async fn every_service_is_serving_until_shutdown() {
+        let (mut reporter, channel) = serve().await;
+        let mut client = HealthClient::new(channel);
+
+        assert_eq!(statuses(&mut client).await, [ProtoServingStatus::Serving; SERVICE_NAMES.len()]);
+
+        set_serving_status(&mut reporter, ServingStatus::NotServing).await;
+        assert_eq!(
+            statuses(&mut client).await,
+            [ProtoServingStatus::NotServing; SERVICE_NAMES.len()]
+        );
+    }
+
+    #[tokio::test]
+    async fn not_serving_is_reported_while_draining() {
+        let (mut reporter, health_service) = tonic_health::server::health_reporter();
+        set_serving_status(&mut reporter, ServingStatus::Serving).await;
+        let (shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel::<()>();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(
+            Server::builder().add_service(health_service).serve_with_incoming_shutdown(
+                TcpIncoming::from_listener(listener, true, None).unwrap(),
+                async move {
+                    let _ = shutdown_receiver.await;
+                    report_shutdown(&mut reporter, Duration::from_millis(500)).await;
+                },
+            ),
+        );
+        let channel =
+            Channel::from_shared(format!("http://{addr}")).unwrap().connect().await.unwrap();
+        let mut client = HealthClient::new(channel);
+
+        shutdown_sender.send(()).unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            statuses(&mut client).await,
+            [ProtoServingStatus::NotServing; SERVICE_NAMES.len()]
+        );
+        assert!(!server.is_finished());
+
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn reflection_lists_tracker_services() {
+        let (_reporter, channel) = serve().await;
+        let mut client = ServerReflectionClient::new(channel);
+
+        let request = ServerReflectionRequest {
+            host: String::new(),
+            message_request: Some(MessageRequest::ListServices(String::new())),
+        };
+        let mut responses =
+            client.server_reflection_info(tokio_stream::once(request)).await.unwrap().into_inner();
+        let Some(MessageResponse::ListServicesResponse(list)) =
+            responses.message().await.unwrap().unwrap().message_response
+        else {
+            panic!("reflection did not list the services");
+        };
+
+        let services = list.service.into_iter().map(|service| service.name).collect::<HashSet<_>>();
+        for service in &SERVICE_NAMES[1..] {
+            assert!(services.contains(*service), "{service} is not listed");
+        }
+        assert!(services.contains("grpc.health.v1.Health"));
+    }
+}
@@ -1,16 +1,16 @@
-use kallax_primitives::ChainSpec;
-use kallax_tracker_proto as proto;
+use kallax_tracker_proto::{self as proto, v2, v2::ChainSpecService};
 use tonic::{Request, Response, Status};
 
-use crate::chain_spec_list::ChainSpecList;
+const LAYER: v2::BlockchainLayer = v2::BlockchainLayer::Leafchain;
 
+/// Serves the leafchain spec API of v1 by [`super::chain_spec::Service`].
 pub struct Service {
-    chain_spec_list: ChainSpecList,
+    inner: super::chain_spec::Service,
 }
 
 impl Service {
-    pub const fn new(chain_spec_list: ChainSpecList) -> Self {
-        Self { chain_spec_list }
+    pub const fn new(inner: super::chain_spec::Service) -> Self {
+        Self { inner }
     }
 }
 
@@ -20,17 +20,12 @@ impl proto::LeafchainSpecService for Service {
         &self,
         req: Request<proto::InsertLeafchainSpecRequest>,
     ) -> Result<Response<proto::InsertLeafchainSpecResponse>, Status> {
-        let proto::InsertLeafchainSpecRequest { chain_id, spec } = req.into_inner();
-
-        let spec = ChainSpec::try_from(spec.as_ref())
-            .map_err(|e| Status::invalid_argument(e.to_string()))?;
-
-        if self.chain_spec_list.insert(&chain_id, spec).await {
-            tracing::warn!("Leafchain spec `{chain_id}` is replaced by a new one");
-        } else {
-            tracing::info!("Leafchain spec `{chain_id}` is added");
-        }
+        let (metadata, extensions, proto::InsertLeafchainSpecRequest { chain_id, spec }) =
+            req.into_parts();
+        let req = v2::InsertChainSpecRequest { layer: LAYER.into(), chain_id, spec };
 
+        let v2::InsertChainSpecResponse { chain_id } =
+            self.inner.insert(Request::from_parts(metadata, extensions, req)).await?.into_inner();
         Ok(Response::new(proto::InsertLeafchainSpecResponse { chain_id }))
     }
 
@@ -38,16 +33,11 @@ impl proto::LeafchainSpecService for Service {
         &self,
         req: Request<proto::GetLeafchainSpecRequest>,
     ) -> Result<Response<proto::GetLeafchainSpecResponse>, Status> {
-        let chain_id = req.into_inner().chain_id;
+        let (metadata, extensions, proto::GetLeafchainSpecRequest { chain_id }) = req.into_parts();
+        let req = v2::GetChainSpecRequest { layer: LAYER.into(), chain_id };
 
-        if let Some(spec) = self.chain_spec_list.get(&chain_id).await {
-            Ok(Response::new(proto::GetLeafchainSpecResponse {
-                chain_id,
-                spec: spec.as_ref().to_vec(),
-            }))
-        } else {
-            let message = format!("chain spec `{chain_id}` not found");
-            Err(Status::not_found(message))
-        }
+        let v2::GetChainSpecResponse { chain_id, spec } =
+            self.inner.get(Request::from_parts(metadata, extensions, req)).await?.into_inner();
+        Ok(Response::new(proto::GetLeafchainSpecResponse { chain_id, spec }))
     }
 }
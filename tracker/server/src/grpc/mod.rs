@@ -1,4 +1,42 @@
+pub mod chain_spec;
+pub mod component;
+pub mod health;
 pub mod leafchain_peer;
 pub mod leafchain_spec;
+pub mod peer;
 pub mod rootchain_peer;
 pub mod rootchain_spec;
+
+use std::time::SystemTime;
+
+use kallax_primitives::{BlockchainLayer, ExternalEndpoint};
+use kallax_tracker_proto::{self as proto, v2};
+
+use crate::peer_address_book::PeerAddressGroup;
+
+impl From<PeerAddressGroup> for proto::PeerAddressGroup {
+    fn from(PeerAddressGroup { peer_id, addresses, last_seen }: PeerAddressGroup) -> Self {
+        Self {
+            peer_id,
+            addresses: addresses.into_iter().map(proto::PeerAddress::from).collect(),
+            last_seen: last_seen.map(|last_seen| SystemTime::from(last_seen).into()),
+        }
+    }
+}
+
+/// Converts the external endpoints of a request, invalid endpoints are
+/// skipped.
+fn external_endpoints(external_endpoints: Vec<proto::ExternalEndpoint>) -> Vec<ExternalEndpoint> {
+    external_endpoints
+        .into_iter()
+        .filter_map(|endpoint| ExternalEndpoint::try_from(endpoint).ok())
+        .collect()
+}
+
+/// Resolves the blockchain layer of a v2 request, `None` if it is
+/// unspecified.
+fn blockchain_layer(layer: i32) -> Option<BlockchainLayer> {
+    v2::BlockchainLayer::try_from(layer)
+        .ok()
+        .and_then(|layer| BlockchainLayer::try_from(layer).ok())
+}
@@ -0,0 +1,301 @@
+use kallax_primitives::{NodeRole, PeerAddress, PeerMetadata};
+use kallax_tracker_proto::{self as proto, v2};
+use tonic::{Request, Response, Status};
+
+use crate::{
+    address_filter::AddressFilter,
+    audit_log::{AuditAction, AuditEvent, AuditLog},
+    by_layer::ByLayer,
+    chain_spec_list::ChainSpecList,
+    error,
+    peer_address_book::{PeerAddressBook, PeerAddressGroup},
+};
+
+#[derive(Clone, Debug)]
+pub struct Service {
+    address_filter: AddressFilter,
+
+    peer_address_books: ByLayer<PeerAddressBook>,
+
+    chain_spec_lists: ByLayer<ChainSpecList>,
+
+    audit_log: AuditLog,
+}
+
+impl Service {
+    #[must_use]
+    pub const fn new(
+        address_filter: AddressFilter,
+        peer_address_books: ByLayer<PeerAddressBook>,
+        chain_spec_lists: ByLayer<ChainSpecList>,
+        audit_log: AuditLog,
+    ) -> Self {
+        Self { address_filter, peer_address_books, chain_spec_lists, audit_log }
+    }
+}
+
+#[tonic::async_trait]
+impl v2::PeerService for Service {
+    async fn get(
+        &self,
+        req: Request<v2::GetPeerAddressesRequest>,
+    ) -> Result<Response<v2::GetPeerAddressesResponse>, Status> {
+        let caller = req.remote_addr().map(|caller| caller.ip());
+        let v2::GetPeerAddressesRequest { layer, chain_id, role } = req.into_inner();
+        let layer = super::blockchain_layer(layer)
+            .ok_or_else(|| error::into_invalid_argument_status("layer"))?;
+        let role =
+            proto::NodeRole::try_from(role).ok().and_then(|role| NodeRole::try_from(role).ok());
+        let book = self.peer_address_books.get(layer);
+
+        let mut addresses =
+            self.address_filter.served(book.fetch_all_peers(&chain_id, role).await, caller);
+        if addresses.is_empty() {
+            addresses = self.chain_spec_lists.get(layer).boot_nodes(&chain_id).await;
+        }
+        let addresses = addresses.into_iter().map(proto::PeerAddress::from).collect();
+
+        let tombstones = book.tombstones(&chain_id).await;
+        let draining = book.draining_peers(&chain_id).await;
+
+        Ok(Response::new(v2::GetPeerAddressesResponse { addresses, tombstones, draining }))
+    }
+
+    async fn get_grouped(
+        &self,
+        req: Request<v2::GetPeerAddressesRequest>,
+    ) -> Result<Response<v2::GetGroupedPeerAddressesResponse>, Status> {
+        let caller = req.remote_addr().map(|caller| caller.ip());
+        let v2::GetPeerAddressesRequest { layer, chain_id, role } = req.into_inner();
+        let layer = super::blockchain_layer(layer)
+            .ok_or_else(|| error::into_invalid_argument_status("layer"))?;
+        let role =
+            proto::NodeRole::try_from(role).ok().and_then(|role| NodeRole::try_from(role).ok());
+        let book = self.peer_address_books.get(layer);
+
+        let mut groups = self
+            .address_filter
+            .served_groups(book.fetch_peer_groups(&chain_id, role).await, caller);
+        if groups.is_empty() {
+            groups = PeerAddressGroup::from_addresses(
+                self.chain_spec_lists.get(layer).boot_nodes(&chain_id).await,
+            );
+        }
+        let peers = groups.into_iter().map(proto::PeerAddressGroup::from).collect();
+
+        let tombstones = book.tombstones(&chain_id).await;
+        let draining = book.draining_peers(&chain_id).await;
+
+        Ok(Response::new(v2::GetGroupedPeerAddressesResponse { peers, tombstones, draining }))
+    }
+
+    async fn insert(
+        &self,
+        req: Request<v2::InsertPeerAddressRequest>,
+    ) -> Result<Response<v2::InsertPeerAddressResponse>, Status> {
+        let caller = req.remote_addr();
+        let v2::InsertPeerAddressRequest { layer, chain_id, address, external_endpoints, metadata } =
+            req.into_inner();
+        let layer = super::blockchain_layer(layer)
+            .ok_or_else(|| error::into_invalid_argument_status("layer"))?;
+
+        let peer_address = {
+            let address = address.ok_or_else(|| error::into_invalid_argument_status("address"))?;
+            PeerAddress::try_from(address).map_err(|e| error::invalid_field_status("address", e))?
+        };
+
+        if !self.address_filter.accepts(&peer_address) {
+            tracing::info!(
+                "New peer `{peer_address}` is in {} network, skip to insert to {layer} \
+                 `{chain_id}`",
+                peer_address.scope()
+            );
+
+            return Ok(Response::new(v2::InsertPeerAddressResponse::default()));
+        }
+
+        tracing::info!("Insert new peer `{peer_address}` to {layer} `{chain_id}`");
+
+        let outcome = self
+            .peer_address_books
+            .get(layer)
+            .insert(
+                &chain_id,
+                peer_address.clone(),
+                super::external_endpoints(external_endpoints),
+                metadata.map(PeerMetadata::from).unwrap_or_default(),
+                caller.map(|caller| caller.ip()),
+            )
+            .await
+            .map_err(|err| {
+                tracing::warn!("Reject peer `{peer_address}`, error: {err}");
+                Status::from(err)
+            })?;
+        self.audit_log.record_insert(layer, caller, &chain_id, &peer_address, &outcome).await;
+
+        let warnings = outcome.warning(&peer_address.id()).into_iter().collect();
+        Ok(Response::new(v2::InsertPeerAddressResponse { warnings }))
+    }
+
+    async fn register(
+        &self,
+        req: Request<v2::RegisterPeerRequest>,
+    ) -> Result<Response<v2::RegisterPeerResponse>, Status> {
+        let caller = req.remote_addr();
+        let v2::RegisterPeerRequest { layer, chain_id, addresses, external_endpoints, metadata } =
+            req.into_inner();
+        let layer = super::blockchain_layer(layer)
+            .ok_or_else(|| error::into_invalid_argument_status("layer"))?;
+
+        if addresses.is_empty() {
+            return Err(error::into_invalid_argument_status("addresses"));
+        }
+        let mut peer_addresses = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let peer_address = PeerAddress::try_from(address)
+                .map_err(|e| error::invalid_field_status("addresses", e))?;
+            if !self.address_filter.accepts(&peer_address) {
+                tracing::info!(
+                    "New peer `{peer_address}` is in {} network, skip to register to {layer} \
+                     `{chain_id}`",
+                    peer_address.scope()
+                );
+                continue;
+            }
+            peer_addresses.push(peer_address);
+        }
+        if peer_addresses.is_empty() {
+            // nothing to lease, keep-alives fail and the peer registers again
+            return Ok(Response::new(v2::RegisterPeerResponse::default()));
+        }
+
+        tracing::info!("Register peers {peer_addresses:?} to {layer} `{chain_id}`");
+
+        let registration = self
+            .peer_address_books
+            .get(layer)
+            .register(
+                &chain_id,
+                peer_addresses,
+                super::external_endpoints(external_endpoints),
+                metadata.map(PeerMetadata::from).unwrap_or_default(),
+                caller.map(|caller| caller.ip()),
+            )
+            .await
+            .map_err(|err| {
+                tracing::warn!("Reject registration to {layer} `{chain_id}`, error: {err}");
+                Status::from(err)
+            })?;
+        self.audit_log.record_registration(layer, caller, &chain_id, &registration).await;
+
+        let warnings = registration.warnings();
+        Ok(Response::new(v2::RegisterPeerResponse {
+            lease_id: registration.lease_id,
+            ttl_seconds: registration.ttl.as_secs(),
+            warnings,
+        }))
+    }
+
+    async fn keep_alive(
+        &self,
+        req: Request<v2::KeepAlivePeerRequest>,
+    ) -> Result<Response<v2::KeepAlivePeerResponse>, Status> {
+        let v2::KeepAlivePeerRequest { layer, lease_id, metadata } = req.into_inner();
+        let layer = super::blockchain_layer(layer)
+            .ok_or_else(|| error::into_invalid_argument_status("layer"))?;
+        let ttl = self
+            .peer_address_books
+            .get(layer)
+            .keep_alive(&lease_id, metadata.map(PeerMetadata::from))
+            .await
+            .map_err(|err| {
+                tracing::debug!("Reject keep-alive, error: {err}");
+                Status::from(err)
+            })?;
+        Ok(Response::new(v2::KeepAlivePeerResponse { ttl_seconds: ttl.as_secs() }))
+    }
+
+    async fn clear(&self, req: Request<v2::ClearPeersRequest>) -> Result<Response<()>, Status> {
+        let caller = req.remote_addr();
+        let layer = super::blockchain_layer(req.into_inner().layer)
+            .ok_or_else(|| error::into_invalid_argument_status("layer"))?;
+        self.peer_address_books.get(layer).clear().await;
+        self.audit_log.record(AuditEvent::new(AuditAction::ClearPeers, layer, caller)).await;
+        Ok(Response::new(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kallax_primitives::{AddressPolicy, BlockchainLayer};
+    use kallax_tracker_proto::{self as proto, v2, v2::PeerService};
+    use tonic::{Code, Request};
+    use tonic_types::StatusExt;
+
+    use super::Service;
+    use crate::{
+        address_filter::AddressFilter, audit_log::AuditLog, by_layer::ByLayer,
+        chain_spec_list::ChainSpecList, peer_address_book::PeerAddressBook,
+    };
+
+    const PEER_ADDR: &str =
+        "/dns/node.example.com/tcp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo";
+
+    fn service() -> Service {
+        Service::new(
+            AddressFilter::new(AddressPolicy::default(), false),
+            ByLayer::new(PeerAddressBook::new(), PeerAddressBook::new()),
+            ByLayer::new(
+                ChainSpecList::new(BlockchainLayer::Rootchain, []),
+                ChainSpecList::new(BlockchainLayer::Leafchain, []),
+            ),
+            AuditLog::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn unspecified_layer_is_rejected() {
+        let status = service()
+            .get(Request::new(v2::GetPeerAddressesRequest {
+                layer: v2::BlockchainLayer::Unspecified.into(),
+                chain_id: "chain".to_string(),
+                role: 0,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        let bad_request = status.get_details_bad_request().unwrap();
+        assert_eq!(bad_request.field_violations[0].field, "layer");
+    }
+
+    #[tokio::test]
+    async fn peers_are_kept_per_layer() {
+        let service = service();
+        let _ = service
+            .insert(Request::new(v2::InsertPeerAddressRequest {
+                layer: v2::BlockchainLayer::Leafchain.into(),
+                chain_id: "chain".to_string(),
+                address: Some(proto::PeerAddress { address: PEER_ADDR.to_string() }),
+                external_endpoints: Vec::new(),
+                metadata: None,
+            }))
+            .await
+            .unwrap();
+
+        for (layer, expected) in
+            [(v2::BlockchainLayer::Rootchain, 0), (v2::BlockchainLayer::Leafchain, 1)]
+        {
+            let addresses = service
+                .get(Request::new(v2::GetPeerAddressesRequest {
+                    layer: layer.into(),
+                    chain_id: "chain".to_string(),
+                    role: 0,
+                }))
+                .await
+                .unwrap()
+                .into_inner()
+                .addresses;
+            assert_eq!(addresses.len(), expected);
+        }
+    }
+}
@@ -1,20 +1,18 @@
-use kallax_primitives::{ExternalEndpoint, PeerAddress};
-use kallax_tracker_proto as proto;
+use kallax_tracker_proto::{self as proto, v2, v2::PeerService};
 use tonic::{Request, Response, Status};
 
-use crate::{error, peer_address_book::PeerAddressBook};
+const LAYER: v2::BlockchainLayer = v2::BlockchainLayer::Rootchain;
 
-#[derive(Clone, Debug, Default)]
+/// Serves the rootchain peer API of v1 by [`super::peer::Service`].
+#[derive(Clone, Debug)]
 pub struct Service {
-    allow_loopback_ip: bool,
-
-    peer_address_book: PeerAddressBook,
+    inner: super::peer::Service,
 }
 
 impl Service {
     #[must_use]
-    pub const fn new(allow_loopback_ip: bool, peer_address_book: PeerAddressBook) -> Self {
-        Self { allow_loopback_ip, peer_address_book }
+    pub const fn new(inner: super::peer::Service) -> Self {
+        Self { inner }
     }
 }
 
@@ -24,56 +22,109 @@ impl proto::RootchainPeerService for Service {
         &self,
         req: Request<proto::GetRootchainPeerAddressesRequest>,
     ) -> Result<Response<proto::GetRootchainPeerAddressesResponse>, Status> {
-        let proto::GetRootchainPeerAddressesRequest { chain_id, prefer_exposed: _ } =
-            req.into_inner();
+        let (metadata, extensions, req) = req.into_parts();
+        let proto::GetRootchainPeerAddressesRequest { chain_id, prefer_exposed: _, role } = req;
+        let req = v2::GetPeerAddressesRequest { layer: LAYER.into(), chain_id, role };
+
+        let v2::GetPeerAddressesResponse { addresses, tombstones, draining } =
+            self.inner.get(Request::from_parts(metadata, extensions, req)).await?.into_inner();
+        Ok(Response::new(proto::GetRootchainPeerAddressesResponse {
+            addresses,
+            tombstones,
+            draining,
+        }))
+    }
 
-        let addresses = self
-            .peer_address_book
-            .fetch_all_peers(&chain_id)
-            .await
-            .into_iter()
-            .map(proto::PeerAddress::from)
-            .collect();
+    async fn get_grouped(
+        &self,
+        req: Request<proto::GetRootchainPeerAddressesRequest>,
+    ) -> Result<Response<proto::GetGroupedRootchainPeerAddressesResponse>, Status> {
+        let (metadata, extensions, req) = req.into_parts();
+        let proto::GetRootchainPeerAddressesRequest { chain_id, prefer_exposed: _, role } = req;
+        let req = v2::GetPeerAddressesRequest { layer: LAYER.into(), chain_id, role };
 
-        Ok(Response::new(proto::GetRootchainPeerAddressesResponse { addresses }))
+        let v2::GetGroupedPeerAddressesResponse { peers, tombstones, draining } = self
+            .inner
+            .get_grouped(Request::from_parts(metadata, extensions, req))
+            .await?
+            .into_inner();
+        Ok(Response::new(proto::GetGroupedRootchainPeerAddressesResponse {
+            peers,
+            tombstones,
+            draining,
+        }))
     }
 
     async fn insert(
         &self,
         req: Request<proto::InsertRootchainPeerAddressRequest>,
     ) -> Result<Response<proto::InsertRootchainPeerAddressResponse>, Status> {
-        let proto::InsertRootchainPeerAddressRequest { chain_id, address, external_endpoint } =
-            req.into_inner();
-
-        let peer_address = {
-            let address = address.ok_or_else(|| error::into_invalid_argument_status("address"))?;
-            PeerAddress::try_from(address).map_err(|e| Status::invalid_argument(e.to_string()))?
+        let (metadata, extensions, req) = req.into_parts();
+        let proto::InsertRootchainPeerAddressRequest {
+            chain_id,
+            address,
+            external_endpoint,
+            external_endpoints,
+            metadata: peer_metadata,
+        } = req;
+        let req = v2::InsertPeerAddressRequest {
+            layer: LAYER.into(),
+            chain_id,
+            address,
+            external_endpoints: external_endpoint.into_iter().chain(external_endpoints).collect(),
+            metadata: peer_metadata,
         };
 
-        if peer_address.is_loopback() && !self.allow_loopback_ip {
-            tracing::info!(
-                "New peer `{peer_address}` is in loopback network, skip to insert to chain \
-                 `{chain_id}`"
-            );
+        let v2::InsertPeerAddressResponse { warnings } =
+            self.inner.insert(Request::from_parts(metadata, extensions, req)).await?.into_inner();
+        Ok(Response::new(proto::InsertRootchainPeerAddressResponse { warnings }))
+    }
 
-            return Ok(Response::new(proto::InsertRootchainPeerAddressResponse {}));
-        }
+    async fn register(
+        &self,
+        req: Request<proto::RegisterRootchainPeerRequest>,
+    ) -> Result<Response<proto::RegisterRootchainPeerResponse>, Status> {
+        let (metadata, extensions, req) = req.into_parts();
+        let proto::RegisterRootchainPeerRequest {
+            chain_id,
+            addresses,
+            external_endpoint,
+            external_endpoints,
+            metadata: peer_metadata,
+        } = req;
+        let req = v2::RegisterPeerRequest {
+            layer: LAYER.into(),
+            chain_id,
+            addresses,
+            external_endpoints: external_endpoint.into_iter().chain(external_endpoints).collect(),
+            metadata: peer_metadata,
+        };
 
-        tracing::info!("Insert new peer `{peer_address}` to chain `{chain_id}`");
+        let v2::RegisterPeerResponse { lease_id, ttl_seconds, warnings } =
+            self.inner.register(Request::from_parts(metadata, extensions, req)).await?.into_inner();
+        Ok(Response::new(proto::RegisterRootchainPeerResponse { lease_id, ttl_seconds, warnings }))
+    }
 
-        self.peer_address_book
-            .insert(
-                chain_id,
-                peer_address,
-                external_endpoint.and_then(|p| ExternalEndpoint::try_from(p).ok()),
-            )
-            .await;
+    async fn keep_alive(
+        &self,
+        req: Request<proto::KeepAliveRootchainPeerRequest>,
+    ) -> Result<Response<proto::KeepAliveRootchainPeerResponse>, Status> {
+        let (metadata, extensions, req) = req.into_parts();
+        let proto::KeepAliveRootchainPeerRequest { lease_id, metadata: peer_metadata } = req;
+        let req =
+            v2::KeepAlivePeerRequest { layer: LAYER.into(), lease_id, metadata: peer_metadata };
 
-        Ok(Response::new(proto::InsertRootchainPeerAddressResponse {}))
+        let v2::KeepAlivePeerResponse { ttl_seconds } = self
+            .inner
+            .keep_alive(Request::from_parts(metadata, extensions, req))
+            .await?
+            .into_inner();
+        Ok(Response::new(proto::KeepAliveRootchainPeerResponse { ttl_seconds }))
     }
 
-    async fn clear(&self, _req: Request<()>) -> Result<Response<()>, Status> {
-        self.peer_address_book.clear().await;
-        Ok(Response::new(()))
+    async fn clear(&self, req: Request<()>) -> Result<Response<()>, Status> {
+        let (metadata, extensions, ()) = req.into_parts();
+        let req = v2::ClearPeersRequest { layer: LAYER.into() };
+        self.inner.clear(Request::from_parts(metadata, extensions, req)).await
     }
 }
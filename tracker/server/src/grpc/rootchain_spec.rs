@@ -1,16 +1,16 @@
-use kallax_primitives::ChainSpec;
-use kallax_tracker_proto as proto;
+use kallax_tracker_proto::{self as proto, v2, v2::ChainSpecService};
 use tonic::{Request, Response, Status};
 
-use crate::chain_spec_list::ChainSpecList;
+const LAYER: v2::BlockchainLayer = v2::BlockchainLayer::Rootchain;
 
+/// Serves the rootchain spec API of v1 by [`super::chain_spec::Service`].
 pub struct Service {
-    chain_spec_list: ChainSpecList,
+    inner: super::chain_spec::Service,
 }
 
 impl Service {
-    pub const fn new(chain_spec_list: ChainSpecList) -> Self {
-        Self { chain_spec_list }
+    pub const fn new(inner: super::chain_spec::Service) -> Self {
+        Self { inner }
     }
 }
 
@@ -20,16 +20,12 @@ impl proto::RootchainSpecService for Service {
         &self,
         req: Request<proto::InsertRootchainSpecRequest>,
     ) -> Result<Response<proto::InsertRootchainSpecResponse>, Status> {
-        let proto::InsertRootchainSpecRequest { chain_id, spec } = req.into_inner();
-
-        let spec = ChainSpec::try_from(spec.as_ref())
-            .map_err(|e| Status::invalid_argument(e.to_string()))?;
-        if self.chain_spec_list.insert(&chain_id, spec).await {
-            tracing::warn!("Rootchain spec `{chain_id}` is replaced by a new one");
-        } else {
-            tracing::info!("Rootchain spec `{chain_id}` is added");
-        }
+        let (metadata, extensions, proto::InsertRootchainSpecRequest { chain_id, spec }) =
+            req.into_parts();
+        let req = v2::InsertChainSpecRequest { layer: LAYER.into(), chain_id, spec };
 
+        let v2::InsertChainSpecResponse { chain_id } =
+            self.inner.insert(Request::from_parts(metadata, extensions, req)).await?.into_inner();
         Ok(Response::new(proto::InsertRootchainSpecResponse { chain_id }))
     }
 
@@ -37,16 +33,11 @@ impl proto::RootchainSpecService for Service {
         &self,
         req: Request<proto::GetRootchainSpecRequest>,
     ) -> Result<Response<proto::GetRootchainSpecResponse>, Status> {
-        let chain_id = req.into_inner().chain_id;
+        let (metadata, extensions, proto::GetRootchainSpecRequest { chain_id }) = req.into_parts();
+        let req = v2::GetChainSpecRequest { layer: LAYER.into(), chain_id };
 
-        if let Some(spec) = self.chain_spec_list.get(&chain_id).await {
-            Ok(Response::new(proto::GetRootchainSpecResponse {
-                chain_id,
-                spec: spec.as_ref().to_vec(),
-            }))
-        } else {
-            let message = format!("chain spec `{chain_id}` not found");
-            Err(Status::not_found(message))
-        }
+        let v2::GetChainSpecResponse { chain_id, spec } =
+            self.inner.get(Request::from_parts(metadata, extensions, req)).await?.into_inner();
+        Ok(Response::new(proto::GetRootchainSpecResponse { chain_id, spec }))
     }
 }
@@ -146,35 +146,59 @@
     )
 )]
 
+mod address_filter;
+mod alerting;
+mod audit_log;
+mod by_layer;
+mod chain_health;
 mod chain_spec_list;
+mod component_registry;
+mod drain_list;
 mod error;
 mod grpc;
 mod peer_address_book;
+mod peer_policy;
+mod peer_verifier;
+mod rate_limiter;
+mod topology;
 mod web;
 
-use std::{net::SocketAddr, time::Duration};
+use std::{net::SocketAddr, path::PathBuf, time::Duration};
 
-use axum::http::StatusCode;
-use kallax_primitives::{BlockchainLayer, ChainSpec};
+use kallax_primitives::{AddressPolicy, BlockchainLayer, ChainSpec};
 use kallax_tracker_proto::{
-    LeafchainPeerServiceServer, LeafchainSpecServiceServer, RootchainPeerServiceServer,
+    v2, LeafchainPeerServiceServer, LeafchainSpecServiceServer, RootchainPeerServiceServer,
     RootchainSpecServiceServer,
 };
 use snafu::ResultExt;
+use tonic_health::ServingStatus;
 
 use self::web::extension::LeafchainSpecList;
 pub use self::{
     error::{Error, Result},
+    peer_verifier::VerificationMode,
     web::controller::{
-        leafchain::InsertLeafchainPeerAddressRequest, rootchain::InsertRootchainPeerAddressRequest,
+        chain::{InsertPeerAddressRequest, InsertPeerAddressResponse},
+        leafchain::InsertLeafchainPeerAddressRequest,
+        rootchain::InsertRootchainPeerAddressRequest,
     },
+    web::problem::{Problem, ProblemCode},
 };
 use crate::{
+    address_filter::AddressFilter,
+    alerting::{AlertEvaluator, AlertingConfig},
+    audit_log::{AuditAction, AuditEvent, AuditLog, AuditLogConfig},
+    by_layer::ByLayer,
     chain_spec_list::ChainSpecList,
-    peer_address_book::PeerAddressBook,
+    component_registry::ComponentRegistry,
+    drain_list::DrainList,
+    peer_address_book::{Capacity, PeerAddressBook},
+    peer_policy::PeerPolicy,
+    peer_verifier::PeerVerifier,
+    rate_limiter::RateLimiter,
     web::extension::{
-        LeafchainPeerAddressBook, RootchainPeerAddressBook, RootchainSpecList, TrackerConfig,
-        TrackerStartTime,
+        AdminToken, LeafchainPeerAddressBook, RootchainPeerAddressBook, RootchainSpecList,
+        TrackerConfig, TrackerStartTime,
     },
 };
 
@@ -184,9 +208,76 @@ pub struct Config {
 
     pub grpc_listen_address: SocketAddr,
 
-    pub allow_peer_in_loopback_network: bool,
+    /// Scopes of the addresses which are accepted on registration, other
+    /// addresses are skipped.
+    pub accepted_address_scopes: AddressPolicy,
+
+    /// Whether addresses of a local network are only handed out to callers
+    /// which are not on the public internet.
+    pub scope_addresses_by_caller: bool,
+
+    /// Whether private addresses are also handed out rewritten to the source
+    /// IP of their registration.
+    pub record_observed_addresses: bool,
 
     pub peer_time_to_live: Duration,
+
+    /// Number of the freshest peers of a chain which are never evicted.
+    pub min_surviving_peers_per_chain: usize,
+
+    /// How long peers which were evicted or cleared are reported as
+    /// tombstones.
+    pub tombstone_time_to_live: Duration,
+
+    /// Requests per second allowed from a single source IP, `0` disables rate
+    /// limiting.
+    pub rate_limit_per_second: u32,
+
+    pub rate_limit_burst: u32,
+
+    pub max_chains_per_layer: usize,
+
+    pub max_peers_per_chain: usize,
+
+    pub max_addresses_per_peer: usize,
+
+    /// Maximum number of components registered, e.g. bridge relayers and
+    /// offline workers.
+    pub max_components: usize,
+
+    /// File the peer policy is loaded from and saved to, the policy is kept
+    /// in memory only if `None`.
+    pub peer_policy_file: Option<PathBuf>,
+
+    /// Bearer token required by the admin API, the admin API is disabled if
+    /// `None`.
+    pub admin_token: Option<String>,
+
+    /// Whether newly advertised addresses are dialed back, and how the
+    /// result affects the addresses handed out.
+    pub peer_verification_mode: VerificationMode,
+
+    pub peer_verification_timeout: Duration,
+
+    pub peer_verification_concurrency: usize,
+
+    /// Nodes whose best block does not change within this duration are
+    /// reported as stuck.
+    pub chain_stall_threshold: Duration,
+
+    /// File the alert rules and webhooks are loaded from, alerting is
+    /// disabled if `None`.
+    pub alert_rules_file: Option<PathBuf>,
+
+    /// Directory the audit log of peer and chain spec mutations is written
+    /// to, auditing is disabled if `None`.
+    pub audit_log_directory: Option<PathBuf>,
+
+    pub audit_log_max_file_size: u64,
+
+    pub audit_log_max_file_age: Duration,
+
+    pub audit_log_max_files: usize,
 }
 
 /// # Errors
@@ -198,8 +289,29 @@ pub async fn serve<R, L>(
     Config {
         api_listen_address,
         grpc_listen_address,
-        allow_peer_in_loopback_network,
+        accepted_address_scopes,
+        scope_addresses_by_caller,
+        record_observed_addresses,
         peer_time_to_live,
+        min_surviving_peers_per_chain,
+        tombstone_time_to_live,
+        rate_limit_per_second,
+        rate_limit_burst,
+        max_chains_per_layer,
+        max_peers_per_chain,
+        max_addresses_per_peer,
+        max_components,
+        peer_policy_file,
+        admin_token,
+        peer_verification_mode,
+        peer_verification_timeout,
+        peer_verification_concurrency,
+        chain_stall_threshold,
+        alert_rules_file,
+        audit_log_directory,
+        audit_log_max_file_size,
+        audit_log_max_file_age,
+        audit_log_max_files,
     }: Config,
     rootchain_spec_files: R,
     leafchain_spec_files: L,
@@ -210,22 +322,113 @@ where
 {
     let lifecycle_manager = sigfinn::LifecycleManager::new();
 
-    let rootchain_peer_address_book = PeerAddressBook::with_ttl(peer_time_to_live);
-    let leafchain_peer_address_book = PeerAddressBook::with_ttl(peer_time_to_live);
+    let capacity =
+        Capacity { max_chains: max_chains_per_layer, max_peers_per_chain, max_addresses_per_peer };
+    let rate_limiter = RateLimiter::new(rate_limit_per_second, rate_limit_burst);
+    let peer_policy =
+        PeerPolicy::load(peer_policy_file).await.context(error::LoadPeerPolicySnafu)?;
+    let admin_token = AdminToken(admin_token.map(Into::into));
+    let drain_list = DrainList::default();
+    let mut alert_evaluator = match alert_rules_file {
+        Some(path) => Some(AlertEvaluator::new(
+            AlertingConfig::load(path).await.context(error::LoadAlertRulesSnafu)?,
+        )),
+        None => None,
+    };
+    let audit_log = match audit_log_directory {
+        Some(directory) => AuditLog::open(AuditLogConfig {
+            directory,
+            max_file_size: audit_log_max_file_size,
+            max_file_age: audit_log_max_file_age,
+            max_files: audit_log_max_files,
+        })
+        .await
+        .context(error::OpenAuditLogSnafu)?,
+        None => AuditLog::default(),
+    };
+
+    let (rootchain_verification_queue, rootchain_peer_verifier) =
+        PeerVerifier::new(peer_verification_timeout, peer_verification_concurrency);
+    let (leafchain_verification_queue, leafchain_peer_verifier) =
+        PeerVerifier::new(peer_verification_timeout, peer_verification_concurrency);
+
+    let mut rootchain_peer_address_book = PeerAddressBook::with_ttl(peer_time_to_live)
+        .with_tombstone_ttl(tombstone_time_to_live)
+        .with_min_survivors(min_surviving_peers_per_chain)
+        .with_capacity(capacity)
+        .with_stall_threshold(chain_stall_threshold)
+        .with_policy(peer_policy.clone())
+        .with_drain_list(drain_list.clone())
+        .with_observed_addresses(record_observed_addresses);
+    let mut leafchain_peer_address_book = PeerAddressBook::with_ttl(peer_time_to_live)
+        .with_tombstone_ttl(tombstone_time_to_live)
+        .with_min_survivors(min_surviving_peers_per_chain)
+        .with_capacity(capacity)
+        .with_stall_threshold(chain_stall_threshold)
+        .with_policy(peer_policy.clone())
+        .with_drain_list(drain_list.clone())
+        .with_observed_addresses(record_observed_addresses);
+    if peer_verification_mode.is_enabled() {
+        rootchain_peer_address_book = rootchain_peer_address_book
+            .with_verification(peer_verification_mode, rootchain_verification_queue);
+        leafchain_peer_address_book = leafchain_peer_address_book
+            .with_verification(peer_verification_mode, leafchain_verification_queue);
+
+        let _handle = lifecycle_manager
+            .spawn("Rootchain peer verifier", {
+                let book = rootchain_peer_address_book.clone();
+                move |shutdown| async move {
+                    rootchain_peer_verifier.run(book, shutdown).await;
+                    sigfinn::ExitStatus::Success
+                }
+            })
+            .spawn("Leafchain peer verifier", {
+                let book = leafchain_peer_address_book.clone();
+                move |shutdown| async move {
+                    leafchain_peer_verifier.run(book, shutdown).await;
+                    sigfinn::ExitStatus::Success
+                }
+            });
+    }
+    let component_registry =
+        ComponentRegistry::with_ttl(peer_time_to_live).with_max_components(max_components);
     let rootchain_spec_list = ChainSpecList::new(BlockchainLayer::Rootchain, rootchain_spec_files);
     let leafchain_spec_list = ChainSpecList::new(BlockchainLayer::Leafchain, leafchain_spec_files);
 
-    let tracker_config = TrackerConfig { peer_time_to_live_seconds: peer_time_to_live.as_secs() };
+    let tracker_config = TrackerConfig {
+        peer_time_to_live_seconds: peer_time_to_live.as_secs(),
+        tombstone_time_to_live_seconds: tombstone_time_to_live.as_secs(),
+        min_surviving_peers_per_chain,
+        rate_limit_per_second,
+        rate_limit_burst,
+        capacity,
+        peer_verification_mode,
+        accepted_address_scopes: accepted_address_scopes.clone(),
+        scope_addresses_by_caller,
+        record_observed_addresses,
+        chain_stall_threshold_seconds: chain_stall_threshold.as_secs(),
+    };
     let tracker_start_time = TrackerStartTime(std::time::Instant::now());
+    let address_filter = AddressFilter::new(accepted_address_scopes, scope_addresses_by_caller);
 
     let _handle = lifecycle_manager
         .spawn("API", {
+            let peer_address_books = ByLayer::new(
+                rootchain_peer_address_book.clone(),
+                leafchain_peer_address_book.clone(),
+            );
+            let chain_spec_lists =
+                ByLayer::new(rootchain_spec_list.clone(), leafchain_spec_list.clone());
             let rootchain_peer_address_book =
                 RootchainPeerAddressBook(rootchain_peer_address_book.clone());
             let rootchain_spec_list = RootchainSpecList(rootchain_spec_list.clone());
             let leafchain_peer_address_book =
                 LeafchainPeerAddressBook(leafchain_peer_address_book.clone());
             let leafchain_spec_list = LeafchainSpecList(leafchain_spec_list.clone());
+            let rate_limiter = rate_limiter.clone();
+            let audit_log = audit_log.clone();
+            let address_filter = address_filter.clone();
+            let component_registry = component_registry.clone();
 
             move |shutdown| async move {
                 let middleware_stack = tower::ServiceBuilder::new()
@@ -233,12 +436,25 @@ where
                     .layer(tower_http::compression::CompressionLayer::new());
 
                 let router = self::web::controller::api_v1_router()
+                    .layer(axum::middleware::from_fn_with_state(
+                        rate_limiter.clone(),
+                        self::web::middleware::rate_limit,
+                    ))
                     .layer(axum::Extension(tracker_config))
+                    .layer(axum::Extension(rate_limiter))
+                    .layer(axum::Extension(peer_policy))
+                    .layer(axum::Extension(drain_list))
+                    .layer(axum::Extension(audit_log))
+                    .layer(axum::Extension(address_filter))
+                    .layer(axum::Extension(admin_token))
                     .layer(axum::Extension(tracker_start_time))
                     .layer(axum::Extension(rootchain_spec_list))
                     .layer(axum::Extension(rootchain_peer_address_book))
                     .layer(axum::Extension(leafchain_spec_list))
                     .layer(axum::Extension(leafchain_peer_address_book))
+                    .layer(axum::Extension(peer_address_books))
+                    .layer(axum::Extension(chain_spec_lists))
+                    .layer(axum::Extension(component_registry))
                     .layer(middleware_stack)
                     .fallback(api_fallback)
                     .into_make_service_with_connect_info::<SocketAddr>();
@@ -265,29 +481,76 @@ where
             }
         })
         .spawn("gRPC", {
-            let rootchain_peer_address_book = rootchain_peer_address_book.clone();
-            let leafchain_peer_address_book = leafchain_peer_address_book.clone();
+            let peer_service = grpc::peer::Service::new(
+                address_filter,
+                ByLayer::new(
+                    rootchain_peer_address_book.clone(),
+                    leafchain_peer_address_book.clone(),
+                ),
+                ByLayer::new(rootchain_spec_list.clone(), leafchain_spec_list.clone()),
+                audit_log.clone(),
+            );
+            let chain_spec_service = grpc::chain_spec::Service::new(
+                ByLayer::new(rootchain_spec_list, leafchain_spec_list),
+                audit_log.clone(),
+            );
+            let component_service = grpc::component::Service::new(component_registry.clone());
+            let rate_limiter = rate_limiter.clone();
 
             move |shutdown| async move {
+                let reflection_service = match grpc::health::reflection_service()
+                    .context(error::BuildReflectionServiceSnafu)
+                {
+                    Ok(service) => service,
+                    Err(err) => return sigfinn::ExitStatus::Failure(err),
+                };
+                let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
+                grpc::health::set_serving_status(&mut health_reporter, ServingStatus::Serving)
+                    .await;
+                let shutdown = async move {
+                    shutdown.await;
+                    tracing::info!(
+                        "Report gRPC services as not serving, stop in {:?}",
+                        grpc::health::SHUTDOWN_DRAIN_PERIOD
+                    );
+                    grpc::health::report_shutdown(
+                        &mut health_reporter,
+                        grpc::health::SHUTDOWN_DRAIN_PERIOD,
+                    )
+                    .await;
+                };
+
                 tracing::info!("Listen gRPC service on {grpc_listen_address}");
                 let server = tonic::transport::Server::builder()
-                    .add_service(RootchainSpecServiceServer::new(
-                        grpc::rootchain_spec::Service::new(rootchain_spec_list),
+                    .add_service(health_service)
+                    .add_service(reflection_service)
+                    .add_service(RootchainSpecServiceServer::with_interceptor(
+                        grpc::rootchain_spec::Service::new(chain_spec_service.clone()),
+                        rate_limiter.clone(),
                     ))
-                    .add_service(RootchainPeerServiceServer::new(
-                        grpc::rootchain_peer::Service::new(
-                            allow_peer_in_loopback_network,
-                            rootchain_peer_address_book,
-                        ),
+                    .add_service(RootchainPeerServiceServer::with_interceptor(
+                        grpc::rootchain_peer::Service::new(peer_service.clone()),
+                        rate_limiter.clone(),
                     ))
-                    .add_service(LeafchainSpecServiceServer::new(
-                        grpc::leafchain_spec::Service::new(leafchain_spec_list),
+                    .add_service(LeafchainSpecServiceServer::with_interceptor(
+                        grpc::leafchain_spec::Service::new(chain_spec_service.clone()),
+                        rate_limiter.clone(),
                     ))
-                    .add_service(LeafchainPeerServiceServer::new(
-                        grpc::leafchain_peer::Service::new(
-                            allow_peer_in_loopback_network,
-                            leafchain_peer_address_book,
-                        ),
+                    .add_service(LeafchainPeerServiceServer::with_interceptor(
+                        grpc::leafchain_peer::Service::new(peer_service.clone()),
+                        rate_limiter.clone(),
+                    ))
+                    .add_service(v2::ChainSpecServiceServer::with_interceptor(
+                        chain_spec_service,
+                        rate_limiter.clone(),
+                    ))
+                    .add_service(v2::PeerServiceServer::with_interceptor(
+                        peer_service,
+                        rate_limiter.clone(),
+                    ))
+                    .add_service(v2::ComponentServiceServer::with_interceptor(
+                        component_service,
+                        rate_limiter,
                     ))
                     .serve_with_shutdown(grpc_listen_address, shutdown);
 
@@ -305,8 +568,27 @@ where
                 tokio::select! {
                   () = &mut shutdown => break,
                   _ = interval.tick() => {
-                    rootchain_peer_address_book.flush().await;
-                    leafchain_peer_address_book.flush().await;
+                    for (layer, book) in [
+                        (BlockchainLayer::Rootchain, &rootchain_peer_address_book),
+                        (BlockchainLayer::Leafchain, &leafchain_peer_address_book),
+                    ] {
+                        for (chain_id, address) in book.flush().await {
+                            audit_log
+                                .record(
+                                    AuditEvent::new(AuditAction::EvictPeer, layer, None)
+                                        .with_chain_id(chain_id)
+                                        .with_peer(&address),
+                                )
+                                .await;
+                        }
+                    }
+                    let _expired = component_registry.flush().await;
+                    if let Some(evaluator) = &mut alert_evaluator {
+                        evaluator
+                            .evaluate(&rootchain_peer_address_book, &leafchain_peer_address_book)
+                            .await;
+                    }
+                    rate_limiter.flush();
                   }
                 }
             }
@@ -322,6 +604,6 @@ where
 }
 
 #[allow(clippy::unused_async)]
-async fn api_fallback(_uri: axum::http::Uri) -> StatusCode {
-    StatusCode::NOT_FOUND
+async fn api_fallback(uri: axum::http::Uri) -> Problem {
+    Problem::new(ProblemCode::NotFound, format!("no route matches `{}`", uri.path()))
 }
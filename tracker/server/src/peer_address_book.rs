@@ -1,36 +1,446 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet},
     fmt,
-    sync::Arc,
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
 
-use kallax_primitives::ExternalEndpoint;
+use kallax_primitives::{ExternalEndpoint, NodeRole, PeerMetadata};
 use serde::Serialize;
 use time::Duration;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock, RwLockReadGuard};
+use utoipa::ToSchema;
+
+use crate::{
+    chain_health::{ChainHealth, NodeReport},
+    drain_list::DrainList,
+    error::{CapacityError, InsertPeerError, LeaseError},
+    peer_policy::{PeerPolicy, PolicySnapshot},
+    peer_verifier::{
+        AddressVerification, Verification, VerificationMode, VerificationQueue, VerificationRequest,
+    },
+    topology::Topology,
+};
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 struct PeerAddress {
     address: kallax_primitives::PeerAddress,
 
-    external: Option<ExternalEndpoint>,
+    /// Sorted and without duplicates, so that the same endpoints registered
+    /// in another order identify the same address.
+    external: Vec<ExternalEndpoint>,
+}
+
+impl PeerAddress {
+    fn new(address: kallax_primitives::PeerAddress, mut external: Vec<ExternalEndpoint>) -> Self {
+        external.sort_unstable();
+        external.dedup();
+        Self { address, external }
+    }
 }
 
-#[derive(Clone, Debug, Serialize)]
+/// State of a tracked address, see [`PeerAddressBook::diagnostic_snapshot`].
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct DiagnosticPeer {
     pub address: String,
-    pub external_endpoint: Option<ExternalEndpoint>,
-    pub last_seen: Option<String>,
+    pub external_endpoints: Vec<ExternalEndpoint>,
+    pub exposed_addresses: Vec<String>,
+    pub observed_address: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_seen: Option<time::OffsetDateTime>,
     pub is_reserved: bool,
+    pub is_stale: bool,
+    pub is_draining: bool,
+    #[schema(value_type = Option<String>)]
+    pub source: Option<IpAddr>,
+    pub verification: Verification,
+    pub address_verifications: Vec<AddressVerification>,
+    pub metadata: PeerMetadata,
+}
+
+/// Addresses of a single peer, see [`PeerAddressBook::fetch_peer_groups`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, ToSchema)]
+pub struct PeerAddressGroup {
+    pub peer_id: String,
+
+    /// Internal, exposed and observed addresses, sorted and without
+    /// duplicates.
+    pub addresses: Vec<kallax_primitives::PeerAddress>,
+
+    /// Latest registration of any of the addresses, `None` for reserved
+    /// peers and boot nodes.
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_seen: Option<time::OffsetDateTime>,
+}
+
+impl PeerAddressGroup {
+    /// Groups addresses which are not tracked by the book, such as boot
+    /// nodes, by peer ID.
+    pub fn from_addresses<I>(addresses: I) -> Vec<Self>
+    where
+        I: IntoIterator<Item = kallax_primitives::PeerAddress>,
+    {
+        let mut groups = BTreeMap::<String, BTreeSet<_>>::new();
+        for address in addresses {
+            groups.entry(address.id()).or_default().insert(address);
+        }
+        groups
+            .into_iter()
+            .map(|(peer_id, addresses)| Self {
+                peer_id,
+                addresses: addresses.into_iter().collect(),
+                last_seen: None,
+            })
+            .collect()
+    }
+}
+
+/// Result of [`PeerAddressBook::insert`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InsertOutcome {
+    /// The address was not tracked before.
+    pub is_new: bool,
+
+    /// Addresses the peer registered before with other external endpoints,
+    /// which were replaced by this registration.
+    pub replaced: Vec<kallax_primitives::PeerAddress>,
+
+    /// Other source IPs the peer ID is registered from, a non-empty list
+    /// means the node key is likely shared by several nodes.
+    pub conflicting_sources: Vec<IpAddr>,
+}
+
+impl InsertOutcome {
+    /// Warning returned to the inserting client if the peer ID is in conflict.
+    pub fn warning(&self, peer_id: &str) -> Option<String> {
+        if self.conflicting_sources.is_empty() {
+            return None;
+        }
+        let sources =
+            self.conflicting_sources.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+        Some(format!(
+            "Peer ID `{peer_id}` is also registered from {sources}, several nodes may share the \
+             same node key"
+        ))
+    }
+}
+
+/// Result of [`PeerAddressBook::register`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Registration {
+    pub lease_id: String,
+
+    /// The lease expires unless it is kept alive within this time.
+    pub ttl: std::time::Duration,
+
+    /// Addresses of the previous registration which are not registered
+    /// again.
+    pub replaced: Vec<kallax_primitives::PeerAddress>,
+
+    /// Outcome of each registered address.
+    pub outcomes: Vec<(kallax_primitives::PeerAddress, InsertOutcome)>,
+}
+
+impl Registration {
+    /// Warnings returned to the registering client, without duplicates.
+    pub fn warnings(&self) -> Vec<String> {
+        self.outcomes
+            .iter()
+            .filter_map(|(address, outcome)| outcome.warning(&address.id()))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Peer ID which is registered from several source IPs at the same time.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct KeyConflict {
+    pub peer_id: String,
+
+    pub sources: Vec<IpAddr>,
+
+    pub addresses: Vec<String>,
+}
+
+/// Upper bounds of a [`PeerAddressBook`], checked whenever a new entry is
+/// inserted. Refreshing an existing entry is always allowed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct Capacity {
+    pub max_chains: usize,
+
+    pub max_peers_per_chain: usize,
+
+    pub max_addresses_per_peer: usize,
+}
+
+impl Default for Capacity {
+    fn default() -> Self {
+        Self {
+            max_chains: usize::MAX,
+            max_peers_per_chain: usize::MAX,
+            max_addresses_per_peer: usize::MAX,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct CapacityRejections {
+    pub too_many_chains: u64,
+    pub too_many_peers: u64,
+    pub too_many_addresses: u64,
+}
+
+#[derive(Debug, Default)]
+struct RejectionCounters {
+    too_many_chains: AtomicU64,
+    too_many_peers: AtomicU64,
+    too_many_addresses: AtomicU64,
+}
+
+impl RejectionCounters {
+    fn record(&self, err: &CapacityError) {
+        let counter = match err {
+            CapacityError::TooManyChains { .. } => &self.too_many_chains,
+            CapacityError::TooManyPeers { .. } => &self.too_many_peers,
+            CapacityError::TooManyAddresses { .. } => &self.too_many_addresses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct PeerState {
+    /// `None` for reserved peers, which never expire.
+    last_seen: Option<time::OffsetDateTime>,
+
+    verification: Verification,
+
+    /// Outcome of dialing each internal and exposed address on the last
+    /// dial-back verification.
+    address_verifications: Vec<AddressVerification>,
+
+    metadata: PeerMetadata,
+
+    /// When the reported best block number last changed, `None` if the peer
+    /// never reported one.
+    best_block_changed_at: Option<time::OffsetDateTime>,
+
+    /// Expired, but kept by [`PeerAddressBook::flush`] as one of the
+    /// freshest peers of the chain.
+    is_stale: bool,
+
+    /// IP address of the client which registered the address last time.
+    source: Option<IpAddr>,
+
+    /// Lease the address was registered with, the address expires with the
+    /// lease instead of by its own `last_seen`.
+    lease_id: Option<String>,
+
+    /// Address rewritten for each external endpoint of the peer, computed
+    /// once on insertion as they are handed out on every fetch.
+    exposed: Vec<kallax_primitives::PeerAddress>,
+
+    /// Address rewritten to the source IP of the last registration, see
+    /// [`PeerAddressBook::with_observed_addresses`].
+    observed: Option<kallax_primitives::PeerAddress>,
+}
+
+impl PeerState {
+    fn refresh(&mut self, metadata: Option<PeerMetadata>, now: time::OffsetDateTime) {
+        if let Some(metadata) = metadata {
+            if metadata.best_block_number.is_none() {
+                self.best_block_changed_at = None;
+            } else if self.best_block_changed_at.is_none()
+                || self.metadata.best_block_number != metadata.best_block_number
+            {
+                self.best_block_changed_at = Some(now);
+            }
+            self.metadata = metadata;
+        }
+        self.last_seen = Some(now);
+        self.is_stale = false;
+    }
+}
+
+/// Addresses registered together by [`PeerAddressBook::register`].
+#[derive(Clone, Debug)]
+struct Lease {
+    chain_id: String,
+
+    expires_at: time::OffsetDateTime,
+}
+
+type PeerAddresses = HashMap<PeerAddress, PeerState>;
+
+/// Peer addresses of a chain, with the addresses of every peer ID and of
+/// every lease indexed so that an insertion or a keep-alive only touches the
+/// addresses of one peer or lease.
+#[derive(Debug, Default)]
+struct ChainBook {
+    /// Addresses are only added and removed by the methods below, and the
+    /// `lease_id` of a state is only changed by [`Self::set_lease`], which
+    /// keep the indexes in sync.
+    addresses: PeerAddresses,
+
+    /// Addresses of each peer ID.
+    peers: HashMap<String, HashSet<PeerAddress>>,
+
+    /// Addresses registered under each lease.
+    leases: HashMap<String, HashSet<PeerAddress>>,
+}
+
+impl ChainBook {
+    /// Inserts an address, or replaces its state.
+    fn insert(&mut self, peer: PeerAddress, state: PeerState) {
+        self.remove(&peer);
+        if let Some(lease_id) = &state.lease_id {
+            self.leases.entry(lease_id.clone()).or_default().insert(peer.clone());
+        }
+        self.peers.entry(peer.address.id()).or_default().insert(peer.clone());
+        self.addresses.insert(peer, state);
+    }
+
+    /// Returns whether the address is new, and its state, inserted without
+    /// lease by `default` if the address is new.
+    fn get_or_insert_with(
+        &mut self,
+        peer: &PeerAddress,
+        default: impl FnOnce() -> PeerState,
+    ) -> (bool, &mut PeerState) {
+        match self.addresses.entry(peer.clone()) {
+            Entry::Occupied(entry) => (false, entry.into_mut()),
+            Entry::Vacant(entry) => {
+                self.peers.entry(peer.address.id()).or_default().insert(peer.clone());
+                (true, entry.insert(default()))
+            }
+        }
+    }
+
+    fn remove(&mut self, peer: &PeerAddress) -> Option<PeerState> {
+        let state = self.addresses.remove(peer)?;
+        unindex(&mut self.peers, peer, Some(&peer.address.id()));
+        unindex(&mut self.leases, peer, state.lease_id.as_deref());
+        Some(state)
+    }
+
+    /// Keeps the addresses for which `f` returns `true`, `f` must not change
+    /// the lease of an address.
+    fn retain(&mut self, mut f: impl FnMut(&PeerAddress, &mut PeerState) -> bool) {
+        let Self { addresses, peers, leases } = self;
+        addresses.retain(|peer, state| {
+            let is_kept = f(peer, state);
+            if !is_kept {
+                unindex(peers, peer, Some(&peer.address.id()));
+                unindex(leases, peer, state.lease_id.as_deref());
+            }
+            is_kept
+        });
+    }
+
+    /// Returns the addresses of a peer ID.
+    fn peer_addresses<'a>(
+        &'a self,
+        peer_id: &str,
+    ) -> impl Iterator<Item = (&'a PeerAddress, &'a PeerState)> {
+        self.peers
+            .get(peer_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|peer| self.addresses.get_key_value(peer))
+    }
+
+    /// Moves a tracked address to another lease, or to none.
+    fn set_lease(&mut self, peer: &PeerAddress, lease_id: Option<&str>) {
+        let Some(state) = self.addresses.get_mut(peer) else {
+            return;
+        };
+        if state.lease_id.as_deref() == lease_id {
+            return;
+        }
+        unindex(&mut self.leases, peer, state.lease_id.as_deref());
+        state.lease_id = lease_id.map(ToString::to_string);
+        if let Some(lease_id) = lease_id {
+            self.leases.entry(lease_id.to_string()).or_default().insert(peer.clone());
+        }
+    }
+
+    /// Refreshes the addresses of a lease, returns `false` if none is
+    /// tracked.
+    fn refresh_lease(
+        &mut self,
+        lease_id: &str,
+        metadata: Option<PeerMetadata>,
+        now: time::OffsetDateTime,
+    ) -> bool {
+        let Some(peers) = self.leases.get(lease_id) else {
+            return false;
+        };
+        for peer in peers {
+            if let Some(state) = self.addresses.get_mut(peer) {
+                state.refresh(metadata.clone(), now);
+            }
+        }
+        true
+    }
+}
+
+/// Removes an address from the entry `key` of an index of [`ChainBook`].
+fn unindex(
+    index: &mut HashMap<String, HashSet<PeerAddress>>,
+    peer: &PeerAddress,
+    key: Option<&str>,
+) {
+    let Some(key) = key else {
+        return;
+    };
+    if let Some(peers) = index.get_mut(key) {
+        peers.remove(peer);
+        if peers.is_empty() {
+            index.remove(key);
+        }
+    }
 }
 
-type PeerAddresses = HashMap<PeerAddress, Option<time::OffsetDateTime>>;
+/// Peer addresses of every chain. Chains are locked independently, the map
+/// itself is only locked for writing when a chain is added or cleared.
+type ChainBooks = HashMap<String, RwLock<ChainBook>>;
+
+/// Peer IDs of a chain which were removed, and when.
+type Tombstones = HashMap<String, time::OffsetDateTime>;
 
 #[derive(Clone, Debug)]
 pub struct PeerAddressBook {
     ttl: Duration,
 
-    books: Arc<Mutex<HashMap<String, PeerAddresses>>>,
+    stall_threshold: Duration,
+
+    tombstone_ttl: Duration,
+
+    min_survivors: usize,
+
+    capacity: Capacity,
+
+    policy: PeerPolicy,
+
+    drain_list: DrainList,
+
+    verification_mode: VerificationMode,
+
+    verification_queue: Option<VerificationQueue>,
+
+    observe_addresses: bool,
+
+    books: Arc<RwLock<ChainBooks>>,
+
+    tombstones: Arc<Mutex<HashMap<String, Tombstones>>>,
+
+    leases: Arc<Mutex<HashMap<String, Lease>>>,
+
+    rejections: Arc<RejectionCounters>,
 }
 
 impl Default for PeerAddressBook {
@@ -46,7 +456,87 @@ impl PeerAddressBook {
 
     pub fn with_ttl(ttl: std::time::Duration) -> Self {
         let ttl = Duration::new(i64::try_from(ttl.as_secs()).unwrap_or_default(), 0);
-        Self { ttl, books: Arc::default() }
+        Self {
+            ttl,
+            stall_threshold: Duration::minutes(1),
+            tombstone_ttl: Duration::minutes(5),
+            min_survivors: 0,
+            capacity: Capacity::default(),
+            policy: PeerPolicy::default(),
+            drain_list: DrainList::default(),
+            verification_mode: VerificationMode::default(),
+            verification_queue: None,
+            observe_addresses: false,
+            books: Arc::default(),
+            tombstones: Arc::default(),
+            leases: Arc::default(),
+            rejections: Arc::default(),
+        }
+    }
+
+    #[must_use]
+    pub const fn with_capacity(mut self, capacity: Capacity) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Nodes whose best block does not change within `stall_threshold` are
+    /// reported as stuck by [`Self::chain_health`].
+    #[must_use]
+    pub fn with_stall_threshold(mut self, stall_threshold: std::time::Duration) -> Self {
+        self.stall_threshold =
+            Duration::new(i64::try_from(stall_threshold.as_secs()).unwrap_or(i64::MAX), 0);
+        self
+    }
+
+    /// [`Self::flush`] never evicts the `min_survivors` most recently
+    /// refreshed peers of a chain, they are kept and marked as stale instead.
+    #[must_use]
+    pub const fn with_min_survivors(mut self, min_survivors: usize) -> Self {
+        self.min_survivors = min_survivors;
+        self
+    }
+
+    /// Peers which were removed are reported by [`Self::tombstones`] for
+    /// `tombstone_ttl`.
+    #[must_use]
+    pub fn with_tombstone_ttl(mut self, tombstone_ttl: std::time::Duration) -> Self {
+        self.tombstone_ttl =
+            Duration::new(i64::try_from(tombstone_ttl.as_secs()).unwrap_or(i64::MAX), 0);
+        self
+    }
+
+    #[must_use]
+    pub fn with_policy(mut self, policy: PeerPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Peers in `drain_list` are not returned by `fetch_*`.
+    #[must_use]
+    pub fn with_drain_list(mut self, drain_list: DrainList) -> Self {
+        self.drain_list = drain_list;
+        self
+    }
+
+    /// Queues new addresses to `queue` for dial-back verification and filters
+    /// `fetch_*` results according to `mode`.
+    #[must_use]
+    pub fn with_verification(mut self, mode: VerificationMode, queue: VerificationQueue) -> Self {
+        self.verification_mode = mode;
+        self.verification_queue = Some(queue);
+        self
+    }
+
+    /// Records the address observed for every registration, with a private
+    /// listen IP rewritten to the public source IP of the registration, see
+    /// [`kallax_primitives::PeerAddress::observed`], and hands it
+    /// out next to the registered and exposed addresses. Lets peers behind a
+    /// simple NAT be reached without an external endpoint.
+    #[must_use]
+    pub const fn with_observed_addresses(mut self, observe_addresses: bool) -> Self {
+        self.observe_addresses = observe_addresses;
+        self
     }
 }
 
@@ -60,16 +550,26 @@ impl PeerAddressBook {
         ChainId: fmt::Display,
     {
         let chain_id = chain_id.to_string();
-        self.books.lock().await.get(&chain_id).map_or_else(Vec::new, |addresses| {
-            addresses.iter().map(|(addr, _)| addr.address.clone()).collect()
+        let policy = self.policy.snapshot().await;
+        let draining = self.drain_list.peer_ids().await;
+        self.read_chain(&chain_id, |addresses| {
+            self.servable_peers(&chain_id, addresses, &policy, &draining, None)
+                .into_iter()
+                .filter(|(peer, state)| self.serves_address(state, &peer.address))
+                .map(|(peer, _)| peer.address.clone())
+                .collect()
         })
+        .await
+        .unwrap_or_default()
     }
 
     /// Fetches peer addresses with external endpoint rewriting for cross-cluster
-    /// connectivity. Peers with an external endpoint get their address rewritten
-    /// via `exposed()`. Peers without an external endpoint (or where `exposed()`
-    /// returns `None`) fall back to their original internal address, ensuring they
-    /// are never silently dropped from the peer list.
+    /// connectivity. Peers with external endpoints get the address rewritten
+    /// via `exposed()` for each endpoint at insert time. Peers without an
+    /// external endpoint (or where `exposed()` returned `None` for every
+    /// endpoint) fall back to their observed address if any, and
+    /// to their original internal address otherwise, ensuring they are never
+    /// silently dropped from the peer list.
     #[allow(dead_code)]
     pub async fn fetch_exposed_peers<ChainId>(
         &self,
@@ -79,24 +579,19 @@ impl PeerAddressBook {
         ChainId: fmt::Display,
     {
         let chain_id = chain_id.to_string();
-        self.books.lock().await.get(&chain_id).map_or_else(Vec::new, |addresses| {
-            let mut addresses = addresses
-                .iter()
-                .map(|(PeerAddress { address, external }, _)| {
-                    external
-                        .as_ref()
-                        .and_then(|external_endpoint| {
-                            let exposed = address.exposed(external_endpoint);
-                            if exposed.is_none() {
-                                tracing::warn!(
-                                    %address, ?external_endpoint,
-                                    "Peer has external endpoint but exposed() returned None; \
-                                     falling back to original address"
-                                );
-                            }
-                            exposed
-                        })
-                        .unwrap_or_else(|| address.clone())
+        let policy = self.policy.snapshot().await;
+        let draining = self.drain_list.peer_ids().await;
+        self.read_chain(&chain_id, |addresses| {
+            let mut addresses = self
+                .servable_peers(&chain_id, addresses, &policy, &draining, None)
+                .into_iter()
+                .flat_map(|(peer, state)| {
+                    let addresses = if state.exposed.is_empty() {
+                        vec![state.observed.clone().unwrap_or_else(|| peer.address.clone())]
+                    } else {
+                        state.exposed.clone()
+                    };
+                    addresses.into_iter().filter(|address| self.serves_address(state, address))
                 })
                 .collect::<HashSet<_>>()
                 .into_iter()
@@ -104,36 +599,82 @@ impl PeerAddressBook {
             addresses.sort_unstable();
             addresses
         })
+        .await
+        .unwrap_or_default()
     }
 
     /// Fetches both internal and exposed addresses for every peer, enabling
     /// topology-agnostic peer discovery. Peers with an external endpoint will
     /// have both their original (internal) address and the exposed (external)
-    /// address included. This lets libp2p connect via whichever route works.
+    /// address included, as well as the observed address if recorded. This
+    /// lets libp2p connect via whichever route works.
+    ///
+    /// Only peers which reported `role` are returned, or peers of any role if
+    /// `role` is `None`.
     pub async fn fetch_all_peers<ChainId>(
         &self,
         chain_id: ChainId,
+        role: Option<NodeRole>,
     ) -> Vec<kallax_primitives::PeerAddress>
     where
         ChainId: fmt::Display,
     {
         let chain_id = chain_id.to_string();
-        self.books.lock().await.get(&chain_id).map_or_else(Vec::new, |addresses| {
-            let mut result = HashSet::new();
-            for PeerAddress { address, external } in addresses.keys() {
-                result.insert(address.clone()); // always include internal
-                if let Some(ep) = external.as_ref() {
-                    if let Some(exposed) = address.exposed(ep) {
-                        result.insert(exposed);
-                    } else {
-                        tracing::warn!(%address, external_endpoint = ?ep, "exposed() returned None");
-                    }
-                }
-            }
-            let mut result: Vec<_> = result.into_iter().collect();
+        let policy = self.policy.snapshot().await;
+        let draining = self.drain_list.peer_ids().await;
+        self.read_chain(&chain_id, |addresses| {
+            let mut result = self
+                .servable_peers(&chain_id, addresses, &policy, &draining, role)
+                .into_iter()
+                .flat_map(|(peer, state)| {
+                    served_addresses(peer, state)
+                        .filter(|address| self.serves_address(state, address))
+                })
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect::<Vec<_>>();
             result.sort_unstable();
             result
         })
+        .await
+        .unwrap_or_default()
+    }
+
+    /// Same as [`Self::fetch_all_peers`], but the addresses are grouped by
+    /// peer ID. Groups are sorted by peer ID.
+    pub async fn fetch_peer_groups<ChainId>(
+        &self,
+        chain_id: ChainId,
+        role: Option<NodeRole>,
+    ) -> Vec<PeerAddressGroup>
+    where
+        ChainId: fmt::Display,
+    {
+        let chain_id = chain_id.to_string();
+        let policy = self.policy.snapshot().await;
+        let draining = self.drain_list.peer_ids().await;
+        self.read_chain(&chain_id, |addresses| {
+            let mut groups = BTreeMap::<String, (BTreeSet<_>, Option<time::OffsetDateTime>)>::new();
+            for (peer, state) in self.servable_peers(&chain_id, addresses, &policy, &draining, role)
+            {
+                let (group, last_seen) = groups.entry(peer.address.id()).or_default();
+                group.extend(
+                    served_addresses(peer, state)
+                        .filter(|address| self.serves_address(state, address)),
+                );
+                *last_seen = (*last_seen).max(state.last_seen);
+            }
+            groups
+                .into_iter()
+                .map(|(peer_id, (addresses, last_seen))| PeerAddressGroup {
+                    peer_id,
+                    addresses: addresses.into_iter().collect(),
+                    last_seen,
+                })
+                .collect()
+        })
+        .await
+        .unwrap_or_default()
     }
 
     #[allow(dead_code)]
@@ -141,124 +682,1123 @@ impl PeerAddressBook {
         &self,
         chain_id: ChainId,
         peer_address: kallax_primitives::PeerAddress,
-        external_endpoint: Option<ExternalEndpoint>,
+        external_endpoints: Vec<ExternalEndpoint>,
     ) where
         ChainId: fmt::Display,
     {
-        let chain_id = chain_id.to_string();
+        let peer = PeerAddress::new(peer_address, external_endpoints);
+        let state = PeerState { exposed: exposed_addresses(&peer), ..PeerState::default() };
         self.books
-            .lock()
+            .write()
             .await
-            .entry(chain_id)
-            .or_insert_with(HashMap::new)
-            .insert(PeerAddress { address: peer_address, external: external_endpoint }, None);
+            .entry(chain_id.to_string())
+            .or_default()
+            .get_mut()
+            .insert(peer, state);
     }
 
+    /// Inserts or refreshes a peer address registered from `source`,
+    /// replacing the metadata reported last time. Addresses the peer
+    /// registered from the same source with other external endpoints are
+    /// replaced. Registrations of the same peer ID from different sources are
+    /// reported as conflicts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the peer is rejected by the [`PeerPolicy`], or if
+    /// the address is new and accepting it would exceed the [`Capacity`] of
+    /// the book.
     pub async fn insert<ChainId>(
         &self,
         chain_id: ChainId,
         peer_address: kallax_primitives::PeerAddress,
-        external_endpoint: Option<ExternalEndpoint>,
-    ) where
+        external_endpoints: Vec<ExternalEndpoint>,
+        metadata: PeerMetadata,
+        source: Option<IpAddr>,
+    ) -> Result<InsertOutcome, InsertPeerError>
+    where
         ChainId: fmt::Display,
     {
         let chain_id = chain_id.to_string();
-        self.books.lock().await.entry(chain_id).or_insert_with(HashMap::new).insert(
-            PeerAddress { address: peer_address, external: external_endpoint },
-            Some(time::OffsetDateTime::now_utc()),
-        );
-    }
+        let policy = self.policy.snapshot().await;
+        let peer = permitted_peer(&policy, &chain_id, peer_address, external_endpoints)?;
 
-    pub async fn flush(&self) {
-        tracing::info!("Start to flush stalled peer addresses");
+        let books = self.books_with_chain(&chain_id).await?;
+        let outcome = self.insert_locked(
+            &mut *books[&chain_id].write().await,
+            &chain_id,
+            &peer,
+            metadata,
+            source,
+            None,
+        );
+        drop(books);
+        let outcome = outcome.inspect_err(|err| self.rejections.record(err))?;
 
-        let now = time::OffsetDateTime::now_utc();
+        self.on_inserted(&chain_id, peer, &outcome, source).await;
+        Ok(outcome)
+    }
 
-        let mut books = self.books.lock().await;
+    /// Registers the addresses of a peer under a new lease. The addresses
+    /// expire together unless the lease is kept alive within the TTL, see
+    /// [`Self::keep_alive`]. Addresses the peer registered before from the
+    /// same source which are not part of `peer_addresses` are replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an address is rejected by the [`PeerPolicy`], or
+    /// if accepting a new address would exceed the [`Capacity`] of the book.
+    pub async fn register<ChainId>(
+        &self,
+        chain_id: ChainId,
+        peer_addresses: Vec<kallax_primitives::PeerAddress>,
+        external_endpoints: Vec<ExternalEndpoint>,
+        metadata: PeerMetadata,
+        source: Option<IpAddr>,
+    ) -> Result<Registration, InsertPeerError>
+    where
+        ChainId: fmt::Display,
+    {
+        let chain_id = chain_id.to_string();
+        let policy = self.policy.snapshot().await;
+        let peers = peer_addresses
+            .into_iter()
+            .map(|address| permitted_peer(&policy, &chain_id, address, external_endpoints.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let peer_ids = peers.iter().map(|peer| peer.address.id()).collect::<HashSet<_>>();
+        let lease_id = format!("{:032x}", rand::random::<u128>());
 
-        for (_, book) in &mut books.iter_mut() {
-            book.retain(|PeerAddress { address, .. }, last_update_time| {
-                last_update_time.map_or(true, |last_update_time| {
-                    if (now - last_update_time) < self.ttl {
-                        true
-                    } else {
-                        tracing::info!("`{address}` is stalled, removing it");
-                        false
+        let books = self.books_with_chain(&chain_id).await?;
+        let mut book = books[&chain_id].write().await;
+        // a rejected registration must not damage the previous one, the
+        // previous state of every address it touches is kept to restore it
+        let mut previous_states = Vec::new();
+        let mut replaced = Vec::new();
+        let mut previous_leases = HashSet::new();
+        let own_addresses = peer_ids
+            .iter()
+            .flat_map(|peer_id| book.peer_addresses(peer_id))
+            .filter(|(_, state)| state.last_seen.is_some() && state.source == source)
+            .map(|(other, state)| (other.clone(), state.lease_id.clone()))
+            .collect::<Vec<_>>();
+        for (other, previous_lease) in own_addresses {
+            previous_leases.extend(previous_lease);
+            if !peers.contains(&other) {
+                replaced.push(other.address.clone());
+                let state = book.remove(&other);
+                previous_states.push((other, state));
+            }
+        }
+        let mut outcomes = Vec::with_capacity(peers.len());
+        for peer in &peers {
+            // the other addresses of the peer from `source` were removed
+            // above, so none is replaced by `insert_locked`
+            previous_states.push((peer.clone(), book.addresses.get(peer).cloned()));
+            let outcome = self.insert_locked(
+                &mut book,
+                &chain_id,
+                peer,
+                metadata.clone(),
+                source,
+                Some(&lease_id),
+            );
+            match outcome {
+                Ok(outcome) => outcomes.push(outcome),
+                Err(err) => {
+                    for (peer, state) in previous_states.into_iter().rev() {
+                        match state {
+                            Some(state) => book.insert(peer, state),
+                            None => drop(book.remove(&peer)),
+                        }
                     }
-                })
-            });
+                    self.rejections.record(&err);
+                    return Err(err.into());
+                }
+            }
         }
+        drop(book);
         drop(books);
-        tracing::info!("Flushing stalled peer addresses completed");
-    }
 
-    pub async fn clear(&self) {
-        let mut books = self.books.lock().await;
-        books.clear();
+        let expires_at = time::OffsetDateTime::now_utc() + self.ttl;
+        let mut leases = self.leases.lock().await;
+        for previous_lease in &previous_leases {
+            leases.remove(previous_lease);
+        }
+        leases.insert(lease_id.clone(), Lease { chain_id: chain_id.clone(), expires_at });
+        drop(leases);
+
+        if !replaced.is_empty() {
+            tracing::info!(
+                "Peers {peer_ids:?} of chain `{chain_id}` registered again, replacing {replaced:?}"
+            );
+        }
+        let mut addresses = Vec::with_capacity(peers.len());
+        for (peer, outcome) in peers.into_iter().zip(&outcomes) {
+            addresses.push(peer.address.clone());
+            self.on_inserted(&chain_id, peer, outcome, source).await;
+        }
+
+        Ok(Registration {
+            lease_id,
+            ttl: self.ttl.unsigned_abs(),
+            replaced,
+            outcomes: addresses.into_iter().zip(outcomes).collect(),
+        })
     }
 
-    pub async fn peer_counts(&self) -> HashMap<String, usize> {
-        let books = self.books.lock().await;
-        books.iter().map(|(chain_id, addresses)| (chain_id.clone(), addresses.len())).collect()
+    /// Extends a lease created by [`Self::register`] and refreshes its
+    /// addresses, replacing their metadata if `metadata` is reported.
+    /// Returns the TTL of the lease.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lease is unknown or expired, the peer should
+    /// register again.
+    pub async fn keep_alive(
+        &self,
+        lease_id: &str,
+        metadata: Option<PeerMetadata>,
+    ) -> Result<std::time::Duration, LeaseError> {
+        let now = time::OffsetDateTime::now_utc();
+        let chain_id = match self.leases.lock().await.get_mut(lease_id) {
+            Some(lease) if lease.expires_at > now => {
+                lease.expires_at = now + self.ttl;
+                lease.chain_id.clone()
+            }
+            _ => return Err(LeaseError::LeaseNotFound { lease_id: lease_id.to_string() }),
+        };
+
+        let books = self.books.read().await;
+        let is_found = match books.get(&chain_id) {
+            Some(book) => book.write().await.refresh_lease(lease_id, metadata, now),
+            None => false,
+        };
+        drop(books);
+
+        if !is_found {
+            // every address of the lease was removed, e.g. by `clear`
+            self.leases.lock().await.remove(lease_id);
+            return Err(LeaseError::LeaseNotFound { lease_id: lease_id.to_string() });
+        }
+        Ok(self.ttl.unsigned_abs())
     }
 
-    #[allow(dead_code)]
-    pub async fn diagnostic_snapshot(&self) -> HashMap<String, Vec<DiagnosticPeer>> {
-        let books = self.books.lock().await;
-        books
-            .iter()
-            .map(|(chain_id, addresses)| {
-                let peers = addresses
-                    .iter()
-                    .map(|(peer, last_seen)| DiagnosticPeer {
-                        address: peer.address.to_string(),
-                        external_endpoint: peer.external.clone(),
-                        last_seen: last_seen.map(|t| t.to_string()),
-                        is_reserved: last_seen.is_none(),
-                    })
-                    .collect();
-                (chain_id.clone(), peers)
+    /// Inserts or refreshes an address while the book of the chain is
+    /// locked, see [`Self::insert`].
+    fn insert_locked(
+        &self,
+        book: &mut ChainBook,
+        chain_id: &str,
+        peer: &PeerAddress,
+        metadata: PeerMetadata,
+        source: Option<IpAddr>,
+        lease_id: Option<&str>,
+    ) -> Result<InsertOutcome, CapacityError> {
+        let peer_id = peer.address.id();
+        let replaced = book
+            .peer_addresses(&peer_id)
+            .filter(|(other, state)| {
+                other.external != peer.external
+                    && state.last_seen.is_some()
+                    && state.source == source
             })
-            .collect()
+            .map(|(other, _)| other.clone())
+            .collect::<Vec<_>>();
+        for other in &replaced {
+            book.remove(other);
+        }
+        let replaced = replaced.into_iter().map(|other| other.address).collect();
+        self.check_capacity(book, chain_id, peer, &peer_id)?;
+
+        let conflicting_sources = source
+            .map(|source| {
+                book.peer_addresses(&peer_id)
+                    .filter(|(_, state)| state.last_seen.is_some() && !state.is_stale)
+                    .filter_map(|(_, state)| state.source)
+                    .filter(|other_source| *other_source != source)
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let (is_new, state) = book.get_or_insert_with(peer, || PeerState {
+            exposed: exposed_addresses(peer),
+            ..PeerState::default()
+        });
+        state.refresh(Some(metadata), time::OffsetDateTime::now_utc());
+        state.source = source;
+        state.observed = source
+            .filter(|_| self.observe_addresses)
+            .and_then(|source| peer.address.observed(source));
+        book.set_lease(peer, lease_id);
+
+        Ok(InsertOutcome { is_new, replaced, conflicting_sources })
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::str::FromStr;
+    /// Logs the outcome of an insertion and hands new addresses over to the
+    /// verifier.
+    async fn on_inserted(
+        &self,
+        chain_id: &str,
+        peer: PeerAddress,
+        outcome: &InsertOutcome,
+        source: Option<IpAddr>,
+    ) {
+        let peer_id = peer.address.id();
+        let InsertOutcome { is_new, replaced, conflicting_sources } = outcome;
 
-    use kallax_primitives::{ExternalEndpoint, PeerAddress as PrimitivePeerAddress};
+        if *is_new {
+            if let Some(tombstones) = self.tombstones.lock().await.get_mut(chain_id) {
+                tombstones.remove(&peer_id);
+            }
+        }
 
-    use super::*;
+        if !replaced.is_empty() {
+            tracing::info!(
+                "Peer `{peer_id}` of chain `{chain_id}` changed its external endpoints, replacing \
+                 {replaced:?}"
+            );
+        }
 
-    const PEER_ADDR_WITH_IP: &str =
-        "/ip4/10.0.0.1/tcp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo";
-    const PEER_ADDR_WITH_DNS: &str =
-        "/dns/node.example.com/tcp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo";
+        if !conflicting_sources.is_empty() {
+            tracing::warn!(
+                "Peer `{peer_id}` of chain `{chain_id}` is registered from {source:?} and \
+                 {conflicting_sources:?}, several nodes may share the same node key"
+            );
+        }
 
-    #[tokio::test]
-    async fn fetch_exposed_peers_includes_peers_without_external_endpoint() {
-        let book = PeerAddressBook::new();
-        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
-        book.insert("chain-1", addr.clone(), None).await;
+        if let (true, Some(queue)) = (*is_new, &self.verification_queue) {
+            let PeerAddress { address, external } = peer;
+            queue.push(VerificationRequest {
+                chain_id: chain_id.to_string(),
+                address,
+                external_endpoints: external,
+            });
+        }
+    }
 
-        let peers = book.fetch_exposed_peers("chain-1").await;
-        assert_eq!(peers.len(), 1);
-        assert_eq!(peers[0].to_string(), PEER_ADDR_WITH_IP);
+    /// Records the result of a dial-back verification, addresses which are
+    /// no longer tracked are ignored.
+    pub async fn set_verification(
+        &self,
+        chain_id: &str,
+        address: kallax_primitives::PeerAddress,
+        external_endpoints: Vec<ExternalEndpoint>,
+        verification: Verification,
+        address_verifications: Vec<AddressVerification>,
+    ) {
+        let peer = PeerAddress::new(address, external_endpoints);
+        let books = self.books.read().await;
+        if let Some(book) = books.get(chain_id) {
+            if let Some(state) = book.write().await.addresses.get_mut(&peer) {
+                state.verification = verification;
+                state.address_verifications = address_verifications;
+            }
+        }
     }
 
-    #[tokio::test]
-    async fn fetch_exposed_peers_rewrites_when_external_endpoint_present() {
-        let book = PeerAddressBook::new();
-        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
-        let external = ExternalEndpoint { host: "node.example.com".to_string(), port: 54321 };
-        book.insert("chain-1", addr, Some(external)).await;
+    /// Returns the addresses of a chain which may be handed out, according
+    /// to the peer policy, the drain list, the verification mode and the
+    /// requested role.
+    /// Reserved peers are configured by the operator and are exempt from
+    /// verification.
+    fn servable_peers<'a>(
+        &self,
+        chain_id: &str,
+        addresses: &'a PeerAddresses,
+        policy: &PolicySnapshot,
+        draining: &HashSet<String>,
+        role: Option<NodeRole>,
+    ) -> Vec<(&'a PeerAddress, &'a PeerState)> {
+        let is_trusted = |state: &PeerState| {
+            state.last_seen.is_none() || state.verification == Verification::Verified
+        };
 
-        let peers = book.fetch_exposed_peers("chain-1").await;
-        assert_eq!(peers.len(), 1);
-        assert!(
-            peers[0].to_string().contains("/dns/node.example.com/tcp/54321/"),
-            "Expected rewritten DNS address, got: {}",
+        let permitted = addresses
+            .iter()
+            .filter(|(PeerAddress { address, external }, state)| {
+                role.is_none_or(|role| state.metadata.role == Some(role))
+                    && !draining.contains(&address.id())
+                    && policy.permits(chain_id, address, external)
+            })
+            .collect::<Vec<_>>();
+
+        match self.verification_mode {
+            VerificationMode::Disabled | VerificationMode::Observe => permitted,
+            VerificationMode::Require => {
+                permitted.into_iter().filter(|(_, state)| is_trusted(state)).collect()
+            }
+            VerificationMode::Prefer => {
+                let trusted_peer_ids = permitted
+                    .iter()
+                    .filter(|(_, state)| is_trusted(state))
+                    .map(|(peer, _)| peer.address.id())
+                    .collect::<HashSet<_>>();
+                permitted
+                    .into_iter()
+                    .filter(|(peer, state)| {
+                        is_trusted(state) || !trusted_peer_ids.contains(&peer.address.id())
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Returns whether `address` of a peer returned by [`Self::servable_peers`]
+    /// may be handed out. Once a peer is verified, `Prefer` and `Require` only
+    /// hand out the addresses which identified as the peer.
+    fn serves_address(&self, state: &PeerState, address: &kallax_primitives::PeerAddress) -> bool {
+        let is_verified = state.last_seen.is_some() && state.verification == Verification::Verified;
+        match self.verification_mode {
+            VerificationMode::Disabled | VerificationMode::Observe => true,
+            VerificationMode::Prefer | VerificationMode::Require => {
+                !is_verified
+                    || state
+                        .address_verifications
+                        .iter()
+                        .any(|v| v.verification == Verification::Verified && v.address == *address)
+            }
+        }
+    }
+
+    /// Locks the books for reading with the book of `chain_id` in place,
+    /// adding it if the chain is new and the [`Capacity`] permits.
+    async fn books_with_chain(
+        &self,
+        chain_id: &str,
+    ) -> Result<RwLockReadGuard<'_, ChainBooks>, CapacityError> {
+        let books = self.books.read().await;
+        if books.contains_key(chain_id) {
+            return Ok(books);
+        }
+        drop(books);
+
+        let mut books = self.books.write().await;
+        if !books.contains_key(chain_id) {
+            let max_chains = self.capacity.max_chains;
+            if books.len() >= max_chains {
+                let err = CapacityError::TooManyChains { limit: max_chains };
+                self.rejections.record(&err);
+                return Err(err);
+            }
+            books.insert(chain_id.to_string(), RwLock::default());
+        }
+        Ok(books.downgrade())
+    }
+
+    /// Applies `f` to the book of a chain, `None` if the chain is not
+    /// tracked. Other chains are not locked meanwhile.
+    async fn read_chain<T>(
+        &self,
+        chain_id: &str,
+        f: impl FnOnce(&PeerAddresses) -> T,
+    ) -> Option<T> {
+        let books = self.books.read().await;
+        let book = books.get(chain_id)?.read().await;
+        Some(f(&book.addresses))
+    }
+
+    /// Applies `f` to the book of every chain, locking one chain at a time.
+    async fn read_chains<T>(
+        &self,
+        mut f: impl FnMut(&str, &PeerAddresses) -> T,
+    ) -> HashMap<String, T> {
+        let books = self.books.read().await;
+        let mut result = HashMap::with_capacity(books.len());
+        for (chain_id, book) in books.iter() {
+            result.insert(chain_id.clone(), f(chain_id, &book.read().await.addresses));
+        }
+        result
+    }
+
+    fn check_capacity(
+        &self,
+        book: &ChainBook,
+        chain_id: &str,
+        peer: &PeerAddress,
+        peer_id: &str,
+    ) -> Result<(), CapacityError> {
+        let Capacity { max_peers_per_chain, max_addresses_per_peer, .. } = self.capacity;
+
+        if book.addresses.contains_key(peer) {
+            return Ok(());
+        }
+
+        let address_count = book.peers.get(peer_id).map_or(0, HashSet::len);
+        if address_count == 0 {
+            if book.peers.len() >= max_peers_per_chain {
+                return Err(CapacityError::TooManyPeers {
+                    chain_id: chain_id.to_string(),
+                    limit: max_peers_per_chain,
+                });
+            }
+        } else if address_count >= max_addresses_per_peer {
+            return Err(CapacityError::TooManyAddresses {
+                peer_id: peer_id.to_string(),
+                limit: max_addresses_per_peer,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Removes the addresses which were not refreshed within the TTL, or
+    /// whose lease expired, and returns them along with their chain IDs.
+    /// Addresses of the freshest peers are kept as stale, see
+    /// [`Self::with_min_survivors`]. Peers left without any address are
+    /// tombstoned, and expired tombstones are dropped.
+    pub async fn flush(&self) -> Vec<(String, kallax_primitives::PeerAddress)> {
+        tracing::info!("Start to flush stalled peer addresses");
+
+        let now = time::OffsetDateTime::now_utc();
+
+        let live_leases = {
+            let mut leases = self.leases.lock().await;
+            leases.retain(|_, lease| lease.expires_at > now);
+            leases.keys().cloned().collect::<HashSet<_>>()
+        };
+
+        let books = self.books.read().await;
+        let mut evicted = Vec::new();
+        let mut departed = Vec::new();
+
+        for (chain_id, book) in books.iter() {
+            let mut book = book.write().await;
+            let survivors = freshest_peer_ids(&book.addresses, self.min_survivors);
+            let mut chain_evicted = Vec::new();
+            book.retain(|PeerAddress { address, .. }, state| {
+                let PeerState { last_seen, is_stale, lease_id, .. } = state;
+                last_seen.map_or(true, |last_update_time| {
+                    let is_alive = lease_id.as_ref().map_or_else(
+                        || (now - last_update_time) < self.ttl,
+                        |lease_id| live_leases.contains(lease_id),
+                    );
+                    if is_alive {
+                        true
+                    } else if survivors.contains(&address.id()) {
+                        if !*is_stale {
+                            tracing::warn!(
+                                "`{address}` is stalled, keeping it as one of the last {} peers \
+                                 of chain `{chain_id}`",
+                                self.min_survivors
+                            );
+                            *is_stale = true;
+                        }
+                        true
+                    } else {
+                        tracing::info!("`{address}` is stalled, removing it");
+                        chain_evicted.push(address.clone());
+                        false
+                    }
+                })
+            });
+            departed.extend(
+                chain_evicted
+                    .iter()
+                    .map(kallax_primitives::PeerAddress::id)
+                    .filter(|peer_id| !book.peers.contains_key(peer_id))
+                    .map(|peer_id| (chain_id.clone(), peer_id)),
+            );
+            evicted.extend(chain_evicted.into_iter().map(|address| (chain_id.clone(), address)));
+        }
+        drop(books);
+
+        let mut tombstones = self.tombstones.lock().await;
+        for (chain_id, peer_id) in departed {
+            tombstones.entry(chain_id).or_default().insert(peer_id, now);
+        }
+        for chain_tombstones in tombstones.values_mut() {
+            chain_tombstones.retain(|_, removed_at| (now - *removed_at) < self.tombstone_ttl);
+        }
+        tombstones.retain(|_, chain_tombstones| !chain_tombstones.is_empty());
+        drop(tombstones);
+        tracing::info!("Flushing stalled peer addresses completed");
+        evicted
+    }
+
+    /// Removes every peer address, the removed peers are tombstoned.
+    pub async fn clear(&self) {
+        let now = time::OffsetDateTime::now_utc();
+        let removed = std::mem::take(&mut *self.books.write().await);
+        self.leases.lock().await.clear();
+        let mut tombstones = self.tombstones.lock().await;
+        for (chain_id, addresses) in removed {
+            tombstones.entry(chain_id).or_default().extend(
+                addresses.into_inner().addresses.keys().map(|peer| (peer.address.id(), now)),
+            );
+        }
+    }
+
+    /// Returns the sorted IDs of the tracked peers of a chain which are
+    /// draining.
+    pub async fn draining_peers(&self, chain_id: &str) -> Vec<String> {
+        let draining = self.drain_list.peer_ids().await;
+        let mut peer_ids = self
+            .read_chain(chain_id, |addresses| {
+                addresses
+                    .keys()
+                    .map(|peer| peer.address.id())
+                    .filter(|peer_id| draining.contains(peer_id))
+                    .collect::<HashSet<_>>()
+            })
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<Vec<_>>();
+        peer_ids.sort_unstable();
+        peer_ids
+    }
+
+    /// Returns the sorted IDs of the peers of a chain which were removed
+    /// recently, either evicted by [`Self::flush`] or cleared.
+    pub async fn tombstones(&self, chain_id: &str) -> Vec<String> {
+        let mut peer_ids = self
+            .tombstones
+            .lock()
+            .await
+            .get(chain_id)
+            .map(|tombstones| tombstones.keys().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        peer_ids.sort_unstable();
+        peer_ids
+    }
+
+    /// Returns the distinct IDs of the live peers of every chain. Reserved
+    /// peers and stale survivors of [`Self::flush`] are skipped.
+    pub async fn live_peer_ids(&self) -> HashMap<String, HashSet<String>> {
+        self.read_chains(|_, addresses| {
+            addresses
+                .iter()
+                .filter(|(_, state)| state.last_seen.is_some() && !state.is_stale)
+                .map(|(peer, _)| peer.address.id())
+                .collect()
+        })
+        .await
+    }
+
+    /// Returns the peer IDs of every chain which are registered from several
+    /// source IPs, sorted by peer ID. Chains without conflicts map to an
+    /// empty list.
+    pub async fn key_conflicts(&self) -> HashMap<String, Vec<KeyConflict>> {
+        self.read_chains(|_, addresses| {
+            let mut peers = HashMap::<String, (BTreeSet<IpAddr>, BTreeSet<String>)>::new();
+            for (peer, state) in addresses {
+                if let (Some(source), Some(_), false) =
+                    (state.source, state.last_seen, state.is_stale)
+                {
+                    let (sources, peer_addresses) = peers.entry(peer.address.id()).or_default();
+                    sources.insert(source);
+                    peer_addresses.insert(peer.address.to_string());
+                }
+            }
+            let mut conflicts = peers
+                .into_iter()
+                .filter(|(_, (sources, _))| sources.len() > 1)
+                .map(|(peer_id, (sources, addresses))| KeyConflict {
+                    peer_id,
+                    sources: sources.into_iter().collect(),
+                    addresses: addresses.into_iter().collect(),
+                })
+                .collect::<Vec<_>>();
+            conflicts.sort_unstable_by(|a, b| a.peer_id.cmp(&b.peer_id));
+            conflicts
+        })
+        .await
+    }
+
+    pub async fn peer_counts(&self) -> HashMap<String, usize> {
+        self.read_chains(|_, addresses| addresses.len()).await
+    }
+
+    pub fn capacity_rejections(&self) -> CapacityRejections {
+        let RejectionCounters { too_many_chains, too_many_peers, too_many_addresses } =
+            self.rejections.as_ref();
+        CapacityRejections {
+            too_many_chains: too_many_chains.load(Ordering::Relaxed),
+            too_many_peers: too_many_peers.load(Ordering::Relaxed),
+            too_many_addresses: too_many_addresses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Aggregates the latest report of every peer, addresses of the same peer
+    /// ID are counted once.
+    pub async fn chain_health(&self) -> Vec<ChainHealth> {
+        let now = time::OffsetDateTime::now_utc();
+        let mut health = self
+            .read_chains(|chain_id, addresses| {
+                let reports = latest_state_per_peer(addresses)
+                    .into_values()
+                    .filter_map(|state| {
+                        let best_block_changed_at = state.best_block_changed_at?;
+                        Some(NodeReport { metadata: state.metadata.clone(), best_block_changed_at })
+                    })
+                    .collect::<Vec<_>>();
+                ChainHealth::aggregate(chain_id, &reports, now, self.stall_threshold)
+            })
+            .await
+            .into_values()
+            .collect::<Vec<_>>();
+        health.sort_unstable_by(|a, b| a.chain_id.cmp(&b.chain_id));
+        health
+    }
+
+    /// Builds the connectivity graph of a chain from the latest report of
+    /// every peer, `None` if the chain is not tracked.
+    pub async fn topology(&self, chain_id: &str) -> Option<Topology> {
+        self.read_chain(chain_id, |addresses| {
+            let reports = latest_state_per_peer(addresses);
+            Topology::build(
+                chain_id,
+                reports.into_iter().map(|(peer_id, state)| (peer_id, &state.metadata)),
+            )
+        })
+        .await
+    }
+
+    /// Returns every tracked address with its state and metadata, per chain
+    /// and ordered by address.
+    pub async fn diagnostic_snapshot(&self) -> HashMap<String, Vec<DiagnosticPeer>> {
+        let draining = self.drain_list.peer_ids().await;
+        self.read_chains(|_, addresses| {
+            let mut peers = addresses
+                .iter()
+                .map(
+                    |(
+                        peer,
+                        PeerState {
+                            last_seen,
+                            verification,
+                            address_verifications,
+                            metadata,
+                            is_stale,
+                            source,
+                            exposed,
+                            observed,
+                            ..
+                        },
+                    )| {
+                        DiagnosticPeer {
+                            address: peer.address.to_string(),
+                            external_endpoints: peer.external.clone(),
+                            exposed_addresses: exposed.iter().map(ToString::to_string).collect(),
+                            observed_address: observed.as_ref().map(ToString::to_string),
+                            last_seen: *last_seen,
+                            is_reserved: last_seen.is_none(),
+                            is_stale: *is_stale,
+                            is_draining: draining.contains(&peer.address.id()),
+                            source: *source,
+                            verification: *verification,
+                            address_verifications: address_verifications.clone(),
+                            metadata: metadata.clone(),
+                        }
+                    },
+                )
+                .collect::<Vec<_>>();
+            peers.sort_unstable_by(|a, b| a.address.cmp(&b.address));
+            peers
+        })
+        .await
+    }
+}
+
+/// Pairs an address with its external endpoints if the peer policy permits
+/// it on `chain_id`.
+fn permitted_peer(
+    policy: &PolicySnapshot,
+    chain_id: &str,
+    address: kallax_primitives::PeerAddress,
+    external: Vec<ExternalEndpoint>,
+) -> Result<PeerAddress, InsertPeerError> {
+    if policy.permits(chain_id, &address, &external) {
+        Ok(PeerAddress::new(address, external))
+    } else {
+        Err(InsertPeerError::RejectedByPolicy { peer_address: address.to_string() })
+    }
+}
+
+/// Rewrites the address of a peer for each of its external endpoints,
+/// endpoints the address cannot be rewritten for are skipped.
+fn exposed_addresses(
+    PeerAddress { address, external }: &PeerAddress,
+) -> Vec<kallax_primitives::PeerAddress> {
+    let mut exposed = external
+        .iter()
+        .filter_map(|external_endpoint| {
+            let exposed = address.exposed(external_endpoint);
+            if exposed.is_none() {
+                tracing::warn!(
+                    %address, ?external_endpoint,
+                    "Peer has external endpoint but exposed() returned None; \
+                     falling back to original address"
+                );
+            }
+            exposed
+        })
+        .collect::<Vec<_>>();
+    exposed.sort_unstable();
+    exposed.dedup();
+    exposed
+}
+
+/// Returns the internal address, the exposed addresses of the external
+/// endpoints of the peer, and the observed address if recorded.
+fn served_addresses(
+    peer: &PeerAddress,
+    state: &PeerState,
+) -> impl Iterator<Item = kallax_primitives::PeerAddress> {
+    std::iter::once(peer.address.clone()).chain(state.exposed.clone()).chain(state.observed.clone())
+}
+
+/// Returns the most recently refreshed state of every peer ID, reserved peers
+/// are skipped as they never report anything.
+fn latest_state_per_peer(addresses: &PeerAddresses) -> HashMap<String, &PeerState> {
+    let mut latest = HashMap::<String, &PeerState>::new();
+    for (PeerAddress { address, .. }, state) in addresses {
+        if state.last_seen.is_none() {
+            continue;
+        }
+        match latest.entry(address.id()) {
+            Entry::Occupied(mut entry) if entry.get().last_seen < state.last_seen => {
+                entry.insert(state);
+            }
+            Entry::Occupied(_) => {}
+            Entry::Vacant(entry) => {
+                entry.insert(state);
+            }
+        }
+    }
+    latest
+}
+
+/// Returns the IDs of the `count` most recently refreshed peers.
+fn freshest_peer_ids(addresses: &PeerAddresses, count: usize) -> HashSet<String> {
+    if count == 0 {
+        return HashSet::new();
+    }
+    let mut peers = latest_state_per_peer(addresses)
+        .into_iter()
+        .map(|(peer_id, state)| (state.last_seen, peer_id))
+        .collect::<Vec<_>>();
+    peers.sort_unstable_by(|a, b| b.cmp(a));
+    peers.into_iter().take(count).map(|(_, peer_id)| peer_id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use kallax_primitives::{ExternalEndpoint, PeerAddress as PrimitivePeerAddress};
+
+    use super::*;
+    use crate::{
+        drain_list::DrainRequest,
+        peer_policy::{PeerMatcher, PolicyAction, PolicyRule},
+        peer_verifier::PeerVerifier,
+    };
+
+    const PEER_ADDR_WITH_IP: &str =
+        "/ip4/10.0.0.1/tcp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo";
+    const PEER_ADDR_WITH_DNS: &str =
+        "/dns/node.example.com/tcp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo";
+    const PEER_ADDR_WITHOUT_TRANSPORT: &str =
+        "/ip4/10.0.0.1/udp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo";
+
+    #[tokio::test]
+    async fn departed_peers_are_tombstoned_until_they_return() {
+        let book = PeerAddressBook::with_ttl(std::time::Duration::ZERO);
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        book.insert("chain-1", addr.clone(), Vec::new(), PeerMetadata::default(), None)
+            .await
+            .unwrap();
+        assert!(book.tombstones("chain-1").await.is_empty());
+
+        assert_eq!(book.flush().await.len(), 1);
+        assert_eq!(book.tombstones("chain-1").await, vec![addr.id()]);
+
+        book.insert("chain-1", addr, Vec::new(), PeerMetadata::default(), None).await.unwrap();
+        assert!(book.tombstones("chain-1").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn flush_keeps_freshest_peers_as_stale() {
+        let book = PeerAddressBook::with_ttl(std::time::Duration::ZERO).with_min_survivors(1);
+        let old = PrimitivePeerAddress::from_str(
+            "/ip4/10.0.0.2/tcp/30333/p2p/12D3KooWHdiAxVd8uMQR1hGWXccidmfCwLqcMpGwR6QcTP6QRMuD",
+        )
+        .unwrap();
+        let fresh = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        book.insert("chain-1", old.clone(), Vec::new(), PeerMetadata::default(), None)
+            .await
+            .unwrap();
+        book.insert("chain-1", fresh.clone(), Vec::new(), PeerMetadata::default(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(book.flush().await, vec![("chain-1".to_string(), old)]);
+        assert_eq!(book.fetch_all_peers("chain-1", None).await, vec![fresh]);
+        let snapshot = book.diagnostic_snapshot().await;
+        assert!(snapshot["chain-1"][0].is_stale);
+    }
+
+    #[tokio::test]
+    async fn live_peer_ids_skip_reserved_and_stale_peers() {
+        let book = PeerAddressBook::with_ttl(std::time::Duration::ZERO).with_min_survivors(1);
+        let reserved = PrimitivePeerAddress::from_str(
+            "/ip4/10.0.0.2/tcp/30333/p2p/12D3KooWHdiAxVd8uMQR1hGWXccidmfCwLqcMpGwR6QcTP6QRMuD",
+        )
+        .unwrap();
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        book.insert_reserved("chain-1", reserved, Vec::new()).await;
+        book.insert("chain-1", addr.clone(), Vec::new(), PeerMetadata::default(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(book.live_peer_ids().await["chain-1"], HashSet::from([addr.id()]));
+
+        assert!(book.flush().await.is_empty());
+        assert!(book.live_peer_ids().await["chain-1"].is_empty());
+    }
+
+    #[tokio::test]
+    async fn draining_peers_are_not_handed_out() {
+        let drain_list = DrainList::default();
+        let book = PeerAddressBook::new().with_drain_list(drain_list.clone());
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        book.insert("chain-1", addr.clone(), Vec::new(), PeerMetadata::default(), None)
+            .await
+            .unwrap();
+
+        drain_list.drain(addr.id(), DrainRequest::default()).await;
+        assert!(book.fetch_all_peers("chain-1", None).await.is_empty());
+        assert_eq!(book.draining_peers("chain-1").await, vec![addr.id()]);
+
+        drain_list.undrain(&addr.id()).await;
+        assert_eq!(book.fetch_all_peers("chain-1", None).await, vec![addr]);
+    }
+
+    #[tokio::test]
+    async fn same_peer_id_from_different_sources_is_a_conflict() {
+        let book = PeerAddressBook::new();
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        let other = PrimitivePeerAddress::from_str(
+            "/ip4/10.0.0.9/tcp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo",
+        )
+        .unwrap();
+        let source = IpAddr::from([10, 0, 0, 1]);
+        let other_source = IpAddr::from([10, 0, 0, 9]);
+
+        let outcome = book
+            .insert("chain-1", addr.clone(), Vec::new(), PeerMetadata::default(), Some(source))
+            .await
+            .unwrap();
+        assert!(outcome.warning(&addr.id()).is_none());
+        let outcome = book
+            .insert("chain-1", other, Vec::new(), PeerMetadata::default(), Some(other_source))
+            .await
+            .unwrap();
+        assert_eq!(outcome.conflicting_sources, vec![source]);
+        assert!(outcome.warning(&addr.id()).is_some());
+
+        let conflicts = book.key_conflicts().await;
+        assert_eq!(conflicts["chain-1"][0].sources, vec![source, other_source]);
+    }
+
+    #[tokio::test]
+    async fn peer_groups_replace_addresses_of_previous_external_endpoint() {
+        let book = PeerAddressBook::new();
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        let old = ExternalEndpoint::new("old.example.com".to_string(), 30333);
+        let new = ExternalEndpoint::new("new.example.com".to_string(), 30333);
+        let source = Some(IpAddr::from([10, 0, 0, 1]));
+
+        book.insert("chain-1", addr.clone(), vec![old], PeerMetadata::default(), source)
+            .await
+            .unwrap();
+        let outcome = book
+            .insert("chain-1", addr.clone(), vec![new], PeerMetadata::default(), source)
+            .await
+            .unwrap();
+        assert_eq!(outcome.replaced, vec![addr.clone()]);
+
+        let groups = book.fetch_peer_groups("chain-1", None).await;
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].peer_id, addr.id());
+        assert!(groups[0].last_seen.is_some());
+        let addresses = groups[0].addresses.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(addresses.len(), 2);
+        assert!(addresses.iter().any(|a| a.contains("/dns/new.example.com/")));
+        assert!(!addresses.iter().any(|a| a.contains("/dns/old.example.com/")));
+    }
+
+    #[tokio::test]
+    async fn registered_addresses_expire_with_their_lease() {
+        let book = PeerAddressBook::new();
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        let other = PrimitivePeerAddress::from_str(
+            "/ip4/10.0.0.9/tcp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo",
+        )
+        .unwrap();
+        let source = Some(IpAddr::from([10, 0, 0, 1]));
+
+        let registration = book
+            .register(
+                "chain-1",
+                vec![addr.clone(), other.clone()],
+                Vec::new(),
+                PeerMetadata::default(),
+                source,
+            )
+            .await
+            .unwrap();
+        assert_eq!(registration.outcomes.len(), 2);
+        assert!(book.keep_alive(&registration.lease_id, None).await.is_ok());
+        assert!(book.keep_alive("unknown", None).await.is_err());
+
+        // registering again without `other` replaces it and the previous lease
+        let again = book
+            .register("chain-1", vec![addr.clone()], Vec::new(), PeerMetadata::default(), source)
+            .await
+            .unwrap();
+        assert_eq!(again.replaced, vec![other]);
+        assert!(book.keep_alive(&registration.lease_id, None).await.is_err());
+
+        book.leases.lock().await.get_mut(&again.lease_id).unwrap().expires_at =
+            time::OffsetDateTime::now_utc();
+        assert_eq!(book.flush().await, vec![("chain-1".to_string(), addr)]);
+        assert!(book.keep_alive(&again.lease_id, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn keep_alive_refreshes_the_addresses_of_its_lease_only() {
+        let book = PeerAddressBook::new();
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        let other = PrimitivePeerAddress::from_str(
+            "/ip4/10.0.0.2/tcp/30333/p2p/12D3KooWHdiAxVd8uMQR1hGWXccidmfCwLqcMpGwR6QcTP6QRMuD",
+        )
+        .unwrap();
+        let registration = book
+            .register("chain-1", vec![addr], Vec::new(), PeerMetadata::default(), None)
+            .await
+            .unwrap();
+        let _ = book
+            .register("chain-1", vec![other], Vec::new(), PeerMetadata::default(), None)
+            .await
+            .unwrap();
+
+        let metadata = PeerMetadata { best_block_number: Some(7), ..PeerMetadata::default() };
+        book.keep_alive(&registration.lease_id, Some(metadata)).await.unwrap();
+
+        let best_block_numbers = book.diagnostic_snapshot().await["chain-1"]
+            .iter()
+            .map(|peer| peer.metadata.best_block_number)
+            .collect::<Vec<_>>();
+        assert_eq!(best_block_numbers, vec![Some(7), None]);
+
+        book.clear().await;
+        assert!(book.keep_alive(&registration.lease_id, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejected_registration_keeps_previous_lease() {
+        let book = PeerAddressBook::new()
+            .with_capacity(Capacity { max_addresses_per_peer: 2, ..Capacity::default() });
+        let addresses = (1..=3)
+            .map(|host| {
+                PrimitivePeerAddress::from_str(&format!(
+                    "/ip4/10.0.0.{host}/tcp/30333/p2p/\
+                     12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo"
+                ))
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let source = Some(IpAddr::from([10, 0, 0, 1]));
+
+        let registration = book
+            .register(
+                "chain-1",
+                addresses[..1].to_vec(),
+                Vec::new(),
+                PeerMetadata::default(),
+                source,
+            )
+            .await
+            .unwrap();
+        let rejected = book
+            .register("chain-1", addresses.clone(), Vec::new(), PeerMetadata::default(), source)
+            .await;
+        assert!(matches!(
+            rejected,
+            Err(InsertPeerError::Capacity { source: CapacityError::TooManyAddresses { .. } })
+        ));
+
+        assert!(book.flush().await.is_empty());
+        assert!(book.keep_alive(&registration.lease_id, None).await.is_ok());
+        assert_eq!(book.fetch_all_peers("chain-1", None).await, addresses[..1].to_vec());
+    }
+
+    #[tokio::test]
+    async fn rejected_registration_restores_replaced_addresses() {
+        let book = PeerAddressBook::new()
+            .with_capacity(Capacity { max_peers_per_chain: 1, ..Capacity::default() });
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        let moved = PrimitivePeerAddress::from_str(
+            "/ip4/10.0.0.9/tcp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo",
+        )
+        .unwrap();
+        let other_peer = PrimitivePeerAddress::from_str(
+            "/ip4/10.0.0.2/tcp/30333/p2p/12D3KooWHdiAxVd8uMQR1hGWXccidmfCwLqcMpGwR6QcTP6QRMuD",
+        )
+        .unwrap();
+        let source = Some(IpAddr::from([10, 0, 0, 1]));
+
+        let registration = book
+            .register("chain-1", vec![addr.clone()], Vec::new(), PeerMetadata::default(), source)
+            .await
+            .unwrap();
+        // `addr` is replaced by `moved` before `other_peer` exceeds the capacity
+        let rejected = book
+            .register(
+                "chain-1",
+                vec![moved, other_peer],
+                Vec::new(),
+                PeerMetadata::default(),
+                source,
+            )
+            .await;
+        assert!(matches!(
+            rejected,
+            Err(InsertPeerError::Capacity { source: CapacityError::TooManyPeers { .. } })
+        ));
+
+        assert_eq!(book.fetch_all_peers("chain-1", None).await, vec![addr]);
+        assert!(book.keep_alive(&registration.lease_id, None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn fetch_exposed_peers_includes_peers_without_external_endpoint() {
+        let book = PeerAddressBook::new();
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        book.insert("chain-1", addr.clone(), Vec::new(), PeerMetadata::default(), None)
+            .await
+            .unwrap();
+
+        let peers = book.fetch_exposed_peers("chain-1").await;
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers[0].to_string(), PEER_ADDR_WITH_IP);
+    }
+
+    #[tokio::test]
+    async fn fetch_exposed_peers_rewrites_when_external_endpoint_present() {
+        let book = PeerAddressBook::new();
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        let external = ExternalEndpoint::new("node.example.com".to_string(), 54321);
+        book.insert("chain-1", addr, vec![external], PeerMetadata::default(), None).await.unwrap();
+
+        let peers = book.fetch_exposed_peers("chain-1").await;
+        assert_eq!(peers.len(), 1);
+        assert!(
+            peers[0].to_string().contains("/dns/node.example.com/tcp/54321/"),
+            "Expected rewritten DNS address, got: {}",
             peers[0]
         );
     }
@@ -266,25 +1806,27 @@ mod tests {
     #[tokio::test]
     async fn fetch_exposed_peers_falls_back_when_exposed_returns_none() {
         let book = PeerAddressBook::new();
-        // A /dns/ base address won't match the Ip4/Ip6 check in exposed(), returning None
-        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_DNS).unwrap();
-        let external = ExternalEndpoint { host: "other.example.com".to_string(), port: 9999 };
-        book.insert("chain-1", addr.clone(), Some(external)).await;
+        // UDP without QUIC is not a known transport, exposed() returns None
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITHOUT_TRANSPORT).unwrap();
+        let external = ExternalEndpoint::new("other.example.com".to_string(), 9999);
+        book.insert("chain-1", addr.clone(), vec![external], PeerMetadata::default(), None)
+            .await
+            .unwrap();
 
         let peers = book.fetch_exposed_peers("chain-1").await;
         assert_eq!(peers.len(), 1);
-        // Should fall back to original /dns/ address, not be dropped
-        assert_eq!(peers[0].to_string(), PEER_ADDR_WITH_DNS);
+        // Should fall back to original address, not be dropped
+        assert_eq!(peers[0].to_string(), PEER_ADDR_WITHOUT_TRANSPORT);
     }
 
     #[tokio::test]
     async fn fetch_all_peers_returns_both_when_external_present() {
         let book = PeerAddressBook::new();
         let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
-        let external = ExternalEndpoint { host: "node.example.com".to_string(), port: 54321 };
-        book.insert("chain-1", addr, Some(external)).await;
+        let external = ExternalEndpoint::new("node.example.com".to_string(), 54321);
+        book.insert("chain-1", addr, vec![external], PeerMetadata::default(), None).await.unwrap();
 
-        let peers = book.fetch_all_peers("chain-1").await;
+        let peers = book.fetch_all_peers("chain-1", None).await;
         // Should contain both the internal /ip4/ address and the exposed /dns/ address
         assert_eq!(peers.len(), 2);
         let addrs: Vec<String> = peers.iter().map(ToString::to_string).collect();
@@ -295,13 +1837,53 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn every_external_endpoint_is_stored_and_served() {
+        let book = PeerAddressBook::new();
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        let ipv4 = ExternalEndpoint::new("203.0.113.7".to_string(), 30333);
+        let ipv6 = ExternalEndpoint::new("2001:db8::7".to_string(), 30333);
+        let dns = ExternalEndpoint::new("node.example.com".to_string(), 30333);
+        let endpoints = vec![ipv4.clone(), ipv6.clone(), dns.clone()];
+
+        let outcome = book
+            .insert("chain-1", addr.clone(), endpoints, PeerMetadata::default(), None)
+            .await
+            .unwrap();
+        assert!(outcome.is_new);
+
+        // the same endpoints in another order refresh the same address
+        let outcome = book
+            .insert("chain-1", addr, vec![dns, ipv6, ipv4], PeerMetadata::default(), None)
+            .await
+            .unwrap();
+        assert!(!outcome.is_new);
+
+        let exposed = [
+            "/dns/node.example.com/tcp/30333",
+            "/ip4/203.0.113.7/tcp/30333",
+            "/ip6/2001:db8::7/tcp/30333",
+        ]
+        .map(|host| format!("{host}/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo"));
+        let peers = book.fetch_exposed_peers("chain-1").await;
+        let mut addrs = peers.iter().map(ToString::to_string).collect::<Vec<_>>();
+        addrs.sort_unstable();
+        assert_eq!(addrs, exposed);
+
+        let peers = book.fetch_all_peers("chain-1", None).await;
+        assert_eq!(peers.len(), 4);
+
+        let snapshot = book.diagnostic_snapshot().await;
+        assert_eq!(snapshot["chain-1"][0].external_endpoints.len(), 3);
+    }
+
     #[tokio::test]
     async fn fetch_all_peers_returns_only_internal_when_no_external() {
         let book = PeerAddressBook::new();
         let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
-        book.insert("chain-1", addr, None).await;
+        book.insert("chain-1", addr, Vec::new(), PeerMetadata::default(), None).await.unwrap();
 
-        let peers = book.fetch_all_peers("chain-1").await;
+        let peers = book.fetch_all_peers("chain-1", None).await;
         assert_eq!(peers.len(), 1);
         assert!(peers[0].to_string().contains("/ip4/10.0.0.1/"));
     }
@@ -309,15 +1891,17 @@ mod tests {
     #[tokio::test]
     async fn fetch_all_peers_keeps_internal_when_exposed_returns_none() {
         let book = PeerAddressBook::new();
-        // A /dns/ base address won't match the Ip4/Ip6 check in exposed(), returning None
-        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_DNS).unwrap();
-        let external = ExternalEndpoint { host: "other.example.com".to_string(), port: 9999 };
-        book.insert("chain-1", addr.clone(), Some(external)).await;
+        // UDP without QUIC is not a known transport, exposed() returns None
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITHOUT_TRANSPORT).unwrap();
+        let external = ExternalEndpoint::new("other.example.com".to_string(), 9999);
+        book.insert("chain-1", addr.clone(), vec![external], PeerMetadata::default(), None)
+            .await
+            .unwrap();
 
-        let peers = book.fetch_all_peers("chain-1").await;
-        // exposed() returns None for /dns/ base → only internal address kept
+        let peers = book.fetch_all_peers("chain-1", None).await;
+        // exposed() returns None → only internal address kept
         assert_eq!(peers.len(), 1);
-        assert_eq!(peers[0].to_string(), PEER_ADDR_WITH_DNS);
+        assert_eq!(peers[0].to_string(), PEER_ADDR_WITHOUT_TRANSPORT);
     }
 
     #[tokio::test]
@@ -328,16 +1912,527 @@ mod tests {
             "/ip4/10.0.0.2/tcp/30333/p2p/12D3KooWHdiAxVd8uMQR1hGWXccidmfCwLqcMpGwR6QcTP6QRMuD",
         )
         .unwrap();
-        let external = ExternalEndpoint { host: "node.example.com".to_string(), port: 54321 };
+        let external = ExternalEndpoint::new("node.example.com".to_string(), 54321);
 
-        book.insert("chain-1", addr1, Some(external.clone())).await;
-        book.insert("chain-1", addr2, Some(external)).await;
+        book.insert("chain-1", addr1, vec![external.clone()], PeerMetadata::default(), None)
+            .await
+            .unwrap();
+        book.insert("chain-1", addr2, vec![external], PeerMetadata::default(), None).await.unwrap();
 
-        let peers = book.fetch_all_peers("chain-1").await;
+        let peers = book.fetch_all_peers("chain-1", None).await;
         // 2 internal + 2 exposed = 4 unique addresses (different peer IDs)
         assert_eq!(peers.len(), 4);
         // Verify no duplicates
         let unique: HashSet<String> = peers.iter().map(ToString::to_string).collect();
         assert_eq!(unique.len(), peers.len());
     }
+
+    #[tokio::test]
+    async fn insert_rejects_new_chain_over_capacity() {
+        let book =
+            PeerAddressBook::new().with_capacity(Capacity { max_chains: 1, ..Capacity::default() });
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+
+        book.insert("chain-1", addr.clone(), Vec::new(), PeerMetadata::default(), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            book.insert("chain-2", addr, Vec::new(), PeerMetadata::default(), None).await,
+            Err(CapacityError::TooManyChains { limit: 1 }.into())
+        );
+        assert_eq!(book.capacity_rejections().too_many_chains, 1);
+    }
+
+    #[tokio::test]
+    async fn insert_rejects_new_peer_over_capacity() {
+        let book = PeerAddressBook::new()
+            .with_capacity(Capacity { max_peers_per_chain: 1, ..Capacity::default() });
+        let addr1 = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        let addr2 = PrimitivePeerAddress::from_str(
+            "/ip4/10.0.0.2/tcp/30333/p2p/12D3KooWHdiAxVd8uMQR1hGWXccidmfCwLqcMpGwR6QcTP6QRMuD",
+        )
+        .unwrap();
+
+        book.insert("chain-1", addr1.clone(), Vec::new(), PeerMetadata::default(), None)
+            .await
+            .unwrap();
+        // refreshing a known peer is always allowed
+        book.insert("chain-1", addr1, Vec::new(), PeerMetadata::default(), None).await.unwrap();
+        assert!(matches!(
+            book.insert("chain-1", addr2, Vec::new(), PeerMetadata::default(), None).await,
+            Err(InsertPeerError::Capacity { source: CapacityError::TooManyPeers { limit: 1, .. } })
+        ));
+    }
+
+    #[tokio::test]
+    async fn flushed_peers_free_their_capacity() {
+        let book = PeerAddressBook::with_ttl(std::time::Duration::ZERO)
+            .with_capacity(Capacity { max_peers_per_chain: 1, ..Capacity::default() });
+        let addr1 = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        let addr2 = PrimitivePeerAddress::from_str(
+            "/ip4/10.0.0.2/tcp/30333/p2p/12D3KooWHdiAxVd8uMQR1hGWXccidmfCwLqcMpGwR6QcTP6QRMuD",
+        )
+        .unwrap();
+
+        book.insert("chain-1", addr1, Vec::new(), PeerMetadata::default(), None).await.unwrap();
+        assert_eq!(book.flush().await.len(), 1);
+        book.insert("chain-1", addr2, Vec::new(), PeerMetadata::default(), None).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn insert_rejects_new_address_over_capacity() {
+        let book = PeerAddressBook::new()
+            .with_capacity(Capacity { max_addresses_per_peer: 1, ..Capacity::default() });
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        let other = PrimitivePeerAddress::from_str(
+            "/ip4/10.0.0.9/tcp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo",
+        )
+        .unwrap();
+
+        book.insert("chain-1", addr, Vec::new(), PeerMetadata::default(), None).await.unwrap();
+        assert!(matches!(
+            book.insert("chain-1", other, Vec::new(), PeerMetadata::default(), None).await,
+            Err(InsertPeerError::Capacity {
+                source: CapacityError::TooManyAddresses { limit: 1, .. }
+            })
+        ));
+        assert_eq!(book.peer_counts().await.get("chain-1"), Some(&1));
+    }
+
+    #[tokio::test]
+    async fn policy_is_enforced_on_insert_and_fetch() {
+        let policy = PeerPolicy::default();
+        let book = PeerAddressBook::new().with_policy(policy.clone());
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        book.insert("chain-1", addr.clone(), Vec::new(), PeerMetadata::default(), None)
+            .await
+            .unwrap();
+
+        let rule = policy
+            .add_rule(PolicyRule {
+                action: PolicyAction::Block,
+                matcher: PeerMatcher::Cidr("10.0.0.0/24".parse().unwrap()),
+                chain_id: None,
+            })
+            .await
+            .unwrap();
+        // peers inserted before the rule was added are hidden as well
+        assert!(book.fetch_all_peers("chain-1", None).await.is_empty());
+        assert!(matches!(
+            book.insert("chain-1", addr, Vec::new(), PeerMetadata::default(), None).await,
+            Err(InsertPeerError::RejectedByPolicy { .. })
+        ));
+
+        policy.remove_rule(rule.id).await.unwrap();
+        assert_eq!(book.fetch_all_peers("chain-1", None).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fetch_all_peers_filters_on_reported_role() {
+        let book = PeerAddressBook::new();
+        let archive = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        let validator = PrimitivePeerAddress::from_str(
+            "/ip4/10.0.0.2/tcp/30333/p2p/12D3KooWHdiAxVd8uMQR1hGWXccidmfCwLqcMpGwR6QcTP6QRMuD",
+        )
+        .unwrap();
+        let metadata = |role| PeerMetadata {
+            role: Some(role),
+            best_block_number: Some(42),
+            ..Default::default()
+        };
+
+        book.insert("chain-1", archive, Vec::new(), metadata(NodeRole::Archive), None)
+            .await
+            .unwrap();
+        book.insert("chain-1", validator, Vec::new(), metadata(NodeRole::Validator), None)
+            .await
+            .unwrap();
+
+        let peers = book.fetch_all_peers("chain-1", Some(NodeRole::Archive)).await;
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers[0].to_string(), PEER_ADDR_WITH_IP);
+        assert!(book.fetch_all_peers("chain-1", Some(NodeRole::Full)).await.is_empty());
+        assert_eq!(book.fetch_all_peers("chain-1", None).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn chain_health_counts_each_peer_once() {
+        let book = PeerAddressBook::new();
+        let metadata = PeerMetadata { best_block_number: Some(7), ..Default::default() };
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        let external = ExternalEndpoint::new("node.example.com".to_string(), 30333);
+
+        book.insert("chain-1", addr.clone(), Vec::new(), metadata.clone(), None).await.unwrap();
+        book.insert("chain-1", addr, vec![external], metadata, None).await.unwrap();
+
+        let health = book.chain_health().await;
+        assert_eq!(health.len(), 1);
+        assert_eq!(health[0].chain_id, "chain-1");
+        assert_eq!(health[0].reporting_nodes, 1);
+        assert_eq!(health[0].highest_best_block, Some(7));
+        assert!(!health[0].is_stalled);
+    }
+
+    async fn book_with_one_verified_address(mode: VerificationMode) -> PeerAddressBook {
+        let (queue, _verifier) = PeerVerifier::new(std::time::Duration::from_secs(1), 1);
+        let book = PeerAddressBook::new().with_verification(mode, queue);
+        let verified = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        let unverified = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_DNS).unwrap();
+        let other_peer = PrimitivePeerAddress::from_str(
+            "/ip4/10.0.0.2/tcp/30333/p2p/12D3KooWHdiAxVd8uMQR1hGWXccidmfCwLqcMpGwR6QcTP6QRMuD",
+        )
+        .unwrap();
+
+        for addr in [&verified, &unverified, &other_peer] {
+            book.insert("chain-1", addr.clone(), Vec::new(), PeerMetadata::default(), None)
+                .await
+                .unwrap();
+        }
+        book.set_verification(
+            "chain-1",
+            verified.clone(),
+            Vec::new(),
+            Verification::Verified,
+            vec![AddressVerification { address: verified, verification: Verification::Verified }],
+        )
+        .await;
+        book.set_verification(
+            "chain-1",
+            other_peer.clone(),
+            Vec::new(),
+            Verification::Unreachable,
+            vec![AddressVerification {
+                address: other_peer,
+                verification: Verification::Unreachable,
+            }],
+        )
+        .await;
+        book
+    }
+
+    #[tokio::test]
+    async fn observe_mode_returns_every_address() {
+        let book = book_with_one_verified_address(VerificationMode::Observe).await;
+        assert_eq!(book.fetch_all_peers("chain-1", None).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn prefer_mode_drops_unverified_addresses_of_verified_peers() {
+        let book = book_with_one_verified_address(VerificationMode::Prefer).await;
+
+        let addrs: Vec<String> =
+            book.fetch_all_peers("chain-1", None).await.iter().map(ToString::to_string).collect();
+        assert_eq!(addrs.len(), 2);
+        assert!(addrs.contains(&PEER_ADDR_WITH_IP.to_string()));
+        // the other peer has no verified address, it is kept as is
+        assert!(addrs.iter().any(|a| a.contains("/ip4/10.0.0.2/")));
+    }
+
+    #[tokio::test]
+    async fn require_mode_returns_verified_addresses_only() {
+        let book = book_with_one_verified_address(VerificationMode::Require).await;
+
+        let peers = book.fetch_all_peers("chain-1", None).await;
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers[0].to_string(), PEER_ADDR_WITH_IP);
+    }
+
+    #[tokio::test]
+    async fn verified_peers_are_served_with_verified_addresses_only() {
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        let external = ExternalEndpoint::new("203.0.113.7".to_string(), 30333);
+        let exposed = addr.exposed(&external).unwrap();
+
+        for mode in [VerificationMode::Prefer, VerificationMode::Require] {
+            let (queue, _verifier) = PeerVerifier::new(std::time::Duration::from_secs(1), 1);
+            let book = PeerAddressBook::new().with_verification(mode, queue);
+            book.insert(
+                "chain-1",
+                addr.clone(),
+                vec![external.clone()],
+                PeerMetadata::default(),
+                None,
+            )
+            .await
+            .unwrap();
+            book.set_verification(
+                "chain-1",
+                addr.clone(),
+                vec![external.clone()],
+                Verification::Verified,
+                vec![
+                    AddressVerification {
+                        address: exposed.clone(),
+                        verification: Verification::Verified,
+                    },
+                    AddressVerification {
+                        address: addr.clone(),
+                        verification: Verification::Unverified,
+                    },
+                ],
+            )
+            .await;
+
+            // the internal address did not authenticate as the peer
+            assert_eq!(book.fetch_all_peers("chain-1", None).await, vec![exposed.clone()]);
+            assert_eq!(book.fetch_exposed_peers("chain-1").await, vec![exposed.clone()]);
+            assert!(book.fetch_peers("chain-1").await.is_empty());
+            let groups = book.fetch_peer_groups("chain-1", None).await;
+            assert_eq!(groups[0].addresses, vec![exposed.clone()]);
+        }
+    }
+
+    #[tokio::test]
+    async fn observed_addresses_are_served_when_enabled() {
+        let source = Some(IpAddr::from([203, 0, 113, 7]));
+        let addr = PrimitivePeerAddress::from_str(PEER_ADDR_WITH_IP).unwrap();
+        let observed = PrimitivePeerAddress::from_str(
+            "/ip4/203.0.113.7/tcp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo",
+        )
+        .unwrap();
+
+        let book = PeerAddressBook::new();
+        book.insert("chain-1", addr.clone(), Vec::new(), PeerMetadata::default(), source)
+            .await
+            .unwrap();
+        assert_eq!(book.fetch_all_peers("chain-1", None).await, vec![addr.clone()]);
+
+        let book = PeerAddressBook::new().with_observed_addresses(true);
+        book.insert("chain-1", addr.clone(), Vec::new(), PeerMetadata::default(), source)
+            .await
+            .unwrap();
+        assert_eq!(book.fetch_all_peers("chain-1", None).await, vec![addr, observed.clone()]);
+        assert_eq!(book.fetch_exposed_peers("chain-1").await, vec![observed]);
+    }
+
+    /// The book as it was before being sharded per chain: one lock around
+    /// every chain, and exposed addresses computed on every fetch. Baseline of
+    /// [`poll_benchmark`].
+    #[derive(Clone, Default)]
+    struct SingleLockBook {
+        books: Arc<Mutex<HashMap<String, PeerAddresses>>>,
+
+        leases: Arc<Mutex<HashMap<String, Lease>>>,
+    }
+
+    impl SingleLockBook {
+        async fn register(
+            &self,
+            chain_id: &str,
+            addresses: Vec<PrimitivePeerAddress>,
+            external_endpoints: Vec<ExternalEndpoint>,
+        ) -> String {
+            let lease_id = format!("{:032x}", rand::random::<u128>());
+            let now = time::OffsetDateTime::now_utc();
+            let mut books = self.books.lock().await;
+            let book = books.entry(chain_id.to_string()).or_default();
+            for address in addresses {
+                let state = PeerState {
+                    last_seen: Some(now),
+                    lease_id: Some(lease_id.clone()),
+                    ..PeerState::default()
+                };
+                book.insert(PeerAddress::new(address, external_endpoints.clone()), state);
+            }
+            drop(books);
+            let lease =
+                Lease { chain_id: chain_id.to_string(), expires_at: now + Duration::MINUTE };
+            self.leases.lock().await.insert(lease_id.clone(), lease);
+            lease_id
+        }
+
+        async fn keep_alive(&self, lease_id: &str, metadata: Option<PeerMetadata>) {
+            let now = time::OffsetDateTime::now_utc();
+            let chain_id = {
+                let mut leases = self.leases.lock().await;
+                let lease = leases.get_mut(lease_id).unwrap();
+                lease.expires_at = now + Duration::MINUTE;
+                lease.chain_id.clone()
+            };
+            let mut books = self.books.lock().await;
+            for state in books
+                .get_mut(&chain_id)
+                .into_iter()
+                .flat_map(HashMap::values_mut)
+                .filter(|state| state.lease_id.as_deref() == Some(lease_id))
+            {
+                state.refresh(metadata.clone(), now);
+            }
+        }
+
+        async fn fetch_all_peers(&self, chain_id: &str) -> Vec<PrimitivePeerAddress> {
+            self.books.lock().await.get(chain_id).map_or_else(Vec::new, |addresses| {
+                let mut result = addresses
+                    .keys()
+                    .flat_map(|PeerAddress { address, external }| {
+                        std::iter::once(address.clone())
+                            .chain(external.iter().filter_map(|external| address.exposed(external)))
+                    })
+                    .collect::<HashSet<_>>()
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                result.sort_unstable();
+                result
+            })
+        }
+
+        async fn flush(&self) {
+            let now = time::OffsetDateTime::now_utc();
+            let live_leases = {
+                let mut leases = self.leases.lock().await;
+                leases.retain(|_, lease| lease.expires_at > now);
+                leases.keys().cloned().collect::<HashSet<_>>()
+            };
+            for book in self.books.lock().await.values_mut() {
+                book.retain(|_, state| {
+                    state.lease_id.as_ref().is_none_or(|lease_id| live_leases.contains(lease_id))
+                });
+            }
+        }
+    }
+
+    #[derive(Clone)]
+    enum BenchmarkBook {
+        Sharded(PeerAddressBook),
+
+        SingleLock(SingleLockBook),
+    }
+
+    impl BenchmarkBook {
+        async fn register(
+            &self,
+            chain_id: &str,
+            addresses: Vec<PrimitivePeerAddress>,
+            external_endpoints: Vec<ExternalEndpoint>,
+        ) -> String {
+            match self {
+                Self::Sharded(book) => {
+                    book.register(
+                        chain_id,
+                        addresses,
+                        external_endpoints,
+                        PeerMetadata::default(),
+                        None,
+                    )
+                    .await
+                    .unwrap()
+                    .lease_id
+                }
+                Self::SingleLock(book) => {
+                    book.register(chain_id, addresses, external_endpoints).await
+                }
+            }
+        }
+
+        async fn poll(&self, chain_id: &str, lease_id: &str) {
+            let peers = match self {
+                Self::Sharded(book) => {
+                    book.keep_alive(lease_id, Some(PeerMetadata::default())).await.unwrap();
+                    book.fetch_all_peers(chain_id, None).await
+                }
+                Self::SingleLock(book) => {
+                    book.keep_alive(lease_id, Some(PeerMetadata::default())).await;
+                    book.fetch_all_peers(chain_id).await
+                }
+            };
+            assert!(!peers.is_empty());
+        }
+
+        async fn flush(&self) {
+            match self {
+                Self::Sharded(book) => drop(book.flush().await),
+                Self::SingleLock(book) => book.flush().await,
+            }
+        }
+    }
+
+    const BENCHMARK_CHAINS: usize = 10;
+    const BENCHMARK_SIDECARS_PER_CHAIN: usize = 200;
+    const BENCHMARK_DURATION: std::time::Duration = std::time::Duration::from_secs(5);
+
+    /// Registers the sidecars, then lets each of them keep its lease alive
+    /// and fetch the peers of its chain at 1 Hz while the tracker flushes in
+    /// the background. Returns the latency of every poll, sorted.
+    async fn run_poll_workload(book: BenchmarkBook) -> Vec<std::time::Duration> {
+        let sidecar_count = BENCHMARK_CHAINS * BENCHMARK_SIDECARS_PER_CHAIN;
+        let mut leases = Vec::new();
+        for sidecar in 0..sidecar_count {
+            let peer_id = libp2p::identity::Keypair::generate_ed25519().public().to_peer_id();
+            let [a, b] = u16::try_from(sidecar).unwrap().to_be_bytes();
+            let addresses = [format!("/ip4/10.0.{a}.{b}"), format!("/ip4/10.1.{a}.{b}")]
+                .into_iter()
+                .map(|ip| PrimitivePeerAddress::from_str(&format!("{ip}/tcp/30333/p2p/{peer_id}")))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let external = ExternalEndpoint::new(format!("node-{sidecar}.example.com"), 30333);
+            let chain_id = format!("chain-{}", sidecar % BENCHMARK_CHAINS);
+            let lease_id = book.register(&chain_id, addresses, vec![external]).await;
+            leases.push((chain_id, lease_id));
+        }
+
+        let started_at = std::time::Instant::now();
+        let flusher = {
+            let book = book.clone();
+            tokio::spawn(async move {
+                while started_at.elapsed() < BENCHMARK_DURATION {
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    book.flush().await;
+                }
+            })
+        };
+        let sidecars = leases.into_iter().enumerate().map(|(sidecar, (chain_id, lease_id))| {
+            let book = book.clone();
+            tokio::spawn(async move {
+                // spread the polls of the sidecars over the second
+                let offset = std::time::Duration::from_secs(1) * u32::try_from(sidecar).unwrap()
+                    / u32::try_from(sidecar_count).unwrap();
+                tokio::time::sleep(offset).await;
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
+                let mut latencies = Vec::new();
+                while started_at.elapsed() < BENCHMARK_DURATION {
+                    interval.tick().await;
+                    let poll_started_at = std::time::Instant::now();
+                    book.poll(&chain_id, &lease_id).await;
+                    latencies.push(poll_started_at.elapsed());
+                }
+                latencies
+            })
+        });
+        let mut latencies = Vec::new();
+        for sidecar in futures::future::join_all(sidecars).await {
+            latencies.extend(sidecar.unwrap());
+        }
+        flusher.await.unwrap();
+
+        latencies.sort_unstable();
+        latencies
+    }
+
+    /// Simulates sidecars of several chains polling the tracker at 1 Hz,
+    /// once against the book sharded per chain and once against the single
+    /// lock baseline, and prints the poll latencies side by side. Run with
+    /// `cargo test --release -p kallax-tracker-server -- --ignored --nocapture poll_benchmark`.
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "benchmark"]
+    async fn poll_benchmark() {
+        let baseline =
+            run_poll_workload(BenchmarkBook::SingleLock(SingleLockBook::default())).await;
+        let sharded = run_poll_workload(BenchmarkBook::Sharded(PeerAddressBook::new())).await;
+
+        println!(
+            "{} sidecars on {BENCHMARK_CHAINS} chains polling at 1 Hz for {BENCHMARK_DURATION:?}",
+            BENCHMARK_CHAINS * BENCHMARK_SIDECARS_PER_CHAIN
+        );
+        println!("{:<18} {:>8} {:>12} {:>12} {:>12}", "book", "polls", "p50", "p99", "max");
+        for (name, latencies) in [("single lock", baseline), ("sharded per chain", sharded)] {
+            let percentile = |p: usize| latencies[(latencies.len() - 1) * p / 100];
+            println!(
+                "{name:<18} {:>8} {:>12?} {:>12?} {:>12?}",
+                latencies.len(),
+                percentile(50),
+                percentile(99),
+                latencies[latencies.len() - 1]
+            );
+        }
+    }
 }
@@ -0,0 +1,313 @@
+use std::{
+    net::IpAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use ipnet::IpNet;
+use kallax_primitives::{ExternalEndpoint, PeerAddress};
+use sc_network::multiaddr::Protocol;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use tokio::sync::RwLock;
+use utoipa::ToSchema;
+
+use crate::error::{self, PeerPolicyError};
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyAction {
+    Allow,
+    Block,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum PeerMatcher {
+    PeerId(String),
+    #[schema(value_type = String)]
+    Cidr(IpNet),
+    DnsSuffix(String),
+}
+
+impl PeerMatcher {
+    fn matches(&self, address: &PeerAddress, external_endpoints: &[ExternalEndpoint]) -> bool {
+        match self {
+            Self::PeerId(peer_id) => address.id() == *peer_id,
+            Self::Cidr(net) => {
+                let external_ips =
+                    external_endpoints.iter().filter_map(|ep| ep.host.parse::<IpAddr>().ok());
+                ip_of(address).into_iter().chain(external_ips).any(|ip| net.contains(&ip))
+            }
+            Self::DnsSuffix(suffix) => {
+                let suffix = suffix.trim_start_matches('.').to_ascii_lowercase();
+                let external_hosts = external_endpoints.iter().map(|ep| ep.host.clone());
+                dns_of(address).into_iter().chain(external_hosts).any(|host| {
+                    let host = host.trim_end_matches('.').to_ascii_lowercase();
+                    host == suffix || host.ends_with(&format!(".{suffix}"))
+                })
+            }
+        }
+    }
+}
+
+fn ip_of(address: &PeerAddress) -> Option<IpAddr> {
+    match address.0.iter().next()? {
+        Protocol::Ip4(ip) => Some(IpAddr::V4(ip)),
+        Protocol::Ip6(ip) => Some(IpAddr::V6(ip)),
+        _ => None,
+    }
+}
+
+fn dns_of(address: &PeerAddress) -> Option<String> {
+    match address.0.iter().next()? {
+        Protocol::Dns(host)
+        | Protocol::Dns4(host)
+        | Protocol::Dns6(host)
+        | Protocol::Dnsaddr(host) => Some(host.to_string()),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+pub struct PolicyRule {
+    pub action: PolicyAction,
+
+    pub matcher: PeerMatcher,
+
+    /// Chain the rule applies to, `None` applies it to every chain.
+    #[serde(default)]
+    pub chain_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+pub struct PolicyEntry {
+    pub id: u64,
+
+    #[serde(flatten)]
+    pub rule: PolicyRule,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct PolicySnapshot {
+    next_id: u64,
+
+    rules: Vec<PolicyEntry>,
+}
+
+impl PolicySnapshot {
+    pub fn rules(&self) -> &[PolicyEntry] {
+        &self.rules
+    }
+
+    /// Returns `true` if the peer may be tracked in the chain.
+    ///
+    /// A rule matches if the address or any of the external endpoints
+    /// matches. A matching block rule always wins. If there is any allow rule for the
+    /// chain, the peer must match one of them, otherwise the peer is allowed.
+    pub fn permits(
+        &self,
+        chain_id: &str,
+        address: &PeerAddress,
+        external_endpoints: &[ExternalEndpoint],
+    ) -> bool {
+        let mut has_allow_rule = false;
+        let mut is_allowed = false;
+
+        for PolicyEntry { rule: PolicyRule { action, matcher, chain_id: scope }, .. } in &self.rules
+        {
+            if scope.as_ref().is_some_and(|scope| scope != chain_id) {
+                continue;
+            }
+
+            match action {
+                PolicyAction::Block => {
+                    if matcher.matches(address, external_endpoints) {
+                        return false;
+                    }
+                }
+                PolicyAction::Allow => {
+                    has_allow_rule = true;
+                    is_allowed = is_allowed || matcher.matches(address, external_endpoints);
+                }
+            }
+        }
+
+        !has_allow_rule || is_allowed
+    }
+}
+
+/// Operator managed rules deciding which peers the tracker may hand out.
+///
+/// Rules are written back to `file_path` on every change so that they survive
+/// restarts.
+#[derive(Clone, Debug, Default)]
+pub struct PeerPolicy {
+    file_path: Option<PathBuf>,
+
+    snapshot: Arc<RwLock<PolicySnapshot>>,
+}
+
+impl PeerPolicy {
+    /// Loads rules from `file_path`, a missing file is treated as an empty
+    /// policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but could not be read or parsed.
+    pub async fn load(file_path: Option<PathBuf>) -> Result<Self, PeerPolicyError> {
+        let snapshot = match &file_path {
+            Some(path) if path.exists() => {
+                let content = tokio::fs::read(path)
+                    .await
+                    .with_context(|_| error::ReadFileSnafu { path: path.clone() })?;
+                let snapshot: PolicySnapshot = serde_json::from_slice(&content)
+                    .with_context(|_| error::ParseFileSnafu { path: path.clone() })?;
+                tracing::info!(
+                    "Loaded {} peer policy rules from `{}`",
+                    snapshot.rules.len(),
+                    path.display()
+                );
+                snapshot
+            }
+            _ => PolicySnapshot::default(),
+        };
+
+        Ok(Self { file_path, snapshot: Arc::new(RwLock::new(snapshot)) })
+    }
+
+    pub async fn snapshot(&self) -> PolicySnapshot {
+        self.snapshot.read().await.clone()
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the updated policy could not be persisted.
+    pub async fn add_rule(&self, rule: PolicyRule) -> Result<PolicyEntry, PeerPolicyError> {
+        let mut snapshot = self.snapshot.write().await;
+        let mut updated = snapshot.clone();
+        let entry = PolicyEntry { id: updated.next_id, rule };
+        updated.next_id += 1;
+        updated.rules.push(entry.clone());
+        self.persist(&updated).await?;
+        *snapshot = updated;
+        drop(snapshot);
+
+        tracing::info!("Added peer policy rule {entry:?}");
+        Ok(entry)
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the updated policy could not be persisted.
+    pub async fn remove_rule(&self, id: u64) -> Result<Option<PolicyEntry>, PeerPolicyError> {
+        let mut snapshot = self.snapshot.write().await;
+        let Some(index) = snapshot.rules.iter().position(|entry| entry.id == id) else {
+            return Ok(None);
+        };
+        let mut updated = snapshot.clone();
+        let entry = updated.rules.remove(index);
+        self.persist(&updated).await?;
+        *snapshot = updated;
+        drop(snapshot);
+
+        tracing::info!("Removed peer policy rule {entry:?}");
+        Ok(Some(entry))
+    }
+
+    /// Writes the policy to disk before it is applied in memory, so a failed
+    /// write leaves the policy unchanged.
+    async fn persist(&self, snapshot: &PolicySnapshot) -> Result<(), PeerPolicyError> {
+        let Some(path) = &self.file_path else {
+            return Ok(());
+        };
+
+        let content = serde_json::to_vec_pretty(snapshot).expect("policy is serializable");
+        write_atomically(path, &content)
+            .await
+            .with_context(|_| error::PersistSnafu { path: path.clone() })
+    }
+}
+
+async fn write_atomically(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    tokio::fs::write(&tmp_path, content).await?;
+    tokio::fs::rename(&tmp_path, path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    const PEER_ID: &str = "12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo";
+
+    fn address(host: &str) -> PeerAddress {
+        PeerAddress::from_str(&format!("{host}/tcp/30333/p2p/{PEER_ID}")).unwrap()
+    }
+
+    fn snapshot(rules: Vec<PolicyRule>) -> PolicySnapshot {
+        let rules = (0..).zip(rules).map(|(id, rule)| PolicyEntry { id, rule }).collect();
+        PolicySnapshot { next_id: 0, rules }
+    }
+
+    #[test]
+    fn empty_policy_permits_everything() {
+        assert!(PolicySnapshot::default().permits("chain-1", &address("/ip4/10.0.0.1"), &[]));
+    }
+
+    #[test]
+    fn block_rules_match_peer_id_cidr_and_dns_suffix() {
+        let block = |matcher| PolicyRule { action: PolicyAction::Block, matcher, chain_id: None };
+
+        let by_id = snapshot(vec![block(PeerMatcher::PeerId(PEER_ID.to_string()))]);
+        assert!(!by_id.permits("chain-1", &address("/ip4/10.0.0.1"), &[]));
+
+        let by_cidr = snapshot(vec![block(PeerMatcher::Cidr("10.0.0.0/24".parse().unwrap()))]);
+        assert!(!by_cidr.permits("chain-1", &address("/ip4/10.0.0.1"), &[]));
+        assert!(by_cidr.permits("chain-1", &address("/ip4/10.0.1.1"), &[]));
+
+        let by_dns = snapshot(vec![block(PeerMatcher::DnsSuffix("bad.example.com".to_string()))]);
+        assert!(!by_dns.permits("chain-1", &address("/dns/node.bad.example.com"), &[]));
+        assert!(by_dns.permits("chain-1", &address("/dns/node.notbad.example.com"), &[]));
+        let good = ExternalEndpoint::new("203.0.113.7".to_string(), 1);
+        let bad = ExternalEndpoint::new("node.bad.example.com".to_string(), 1);
+        assert!(by_dns.permits("chain-1", &address("/ip4/10.0.0.1"), std::slice::from_ref(&good)));
+        assert!(!by_dns.permits("chain-1", &address("/ip4/10.0.0.1"), &[good, bad]));
+    }
+
+    #[test]
+    fn allow_rules_are_scoped_per_chain() {
+        let policy = snapshot(vec![PolicyRule {
+            action: PolicyAction::Allow,
+            matcher: PeerMatcher::Cidr("10.0.0.0/8".parse().unwrap()),
+            chain_id: Some("chain-1".to_string()),
+        }]);
+
+        assert!(policy.permits("chain-1", &address("/ip4/10.1.2.3"), &[]));
+        assert!(!policy.permits("chain-1", &address("/ip4/192.168.0.1"), &[]));
+        // other chains are not restricted by the allow rule
+        assert!(policy.permits("chain-2", &address("/ip4/192.168.0.1"), &[]));
+    }
+
+    #[tokio::test]
+    async fn rules_survive_reload() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("peer-policy.json");
+
+        let policy = PeerPolicy::load(Some(path.clone())).await.unwrap();
+        let rule = PolicyRule {
+            action: PolicyAction::Block,
+            matcher: PeerMatcher::PeerId(PEER_ID.to_string()),
+            chain_id: None,
+        };
+        let entry = policy.add_rule(rule.clone()).await.unwrap();
+        let other = policy.add_rule(rule).await.unwrap();
+        assert_eq!(policy.remove_rule(other.id).await.unwrap(), Some(other));
+
+        let reloaded = PeerPolicy::load(Some(path)).await.unwrap().snapshot().await;
+        assert_eq!(reloaded.rules(), &[entry]);
+        assert_eq!(reloaded.next_id, 2);
+    }
+}
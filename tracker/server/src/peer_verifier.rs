@@ -0,0 +1,503 @@
+use std::{collections::HashMap, future::Future, io, time::Duration};
+
+use futures::StreamExt;
+use kallax_primitives::{ExternalEndpoint, PeerAddress};
+use libp2p::{
+    core::{muxing::StreamMuxerBox, transport::Boxed, upgrade::Version, ConnectedPoint},
+    dns::TokioDnsConfig,
+    identity::Keypair,
+    multiaddr::Protocol,
+    noise,
+    swarm::{
+        dial_opts::{DialOpts, PeerCondition},
+        dummy, DialError, SwarmEvent,
+    },
+    tcp,
+    websocket::WsConfig,
+    yamux, Multiaddr, PeerId, Swarm, Transport,
+};
+use serde::Serialize;
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinSet,
+};
+use utoipa::ToSchema;
+
+use crate::peer_address_book::PeerAddressBook;
+
+const QUEUE_SIZE: usize = 4096;
+
+/// Result of dialing back an advertised address.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Verification {
+    /// Not dialed yet, not dialable over TCP, or the remote authenticated
+    /// itself with a different peer ID.
+    #[default]
+    Unverified,
+
+    /// The remote authenticated itself with the advertised peer ID.
+    Verified,
+
+    /// No connection could be established.
+    Unreachable,
+}
+
+/// Result of dialing back one of the internal and exposed addresses of a
+/// registration.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, ToSchema)]
+pub struct AddressVerification {
+    pub address: PeerAddress,
+
+    pub verification: Verification,
+}
+
+/// How `fetch_*` calls of a [`PeerAddressBook`] treat the verification state.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationMode {
+    /// Addresses are never dialed back.
+    #[default]
+    Disabled,
+
+    /// Addresses are dialed back, but every address is handed out.
+    Observe,
+
+    /// Peers with a verified address are handed out with the addresses which
+    /// identified as the peer only, other peers are handed out unchanged.
+    Prefer,
+
+    /// Only addresses which identified as the peer are handed out.
+    Require,
+}
+
+impl VerificationMode {
+    pub const fn is_enabled(self) -> bool {
+        !matches!(self, Self::Disabled)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct VerificationRequest {
+    pub chain_id: String,
+
+    pub address: PeerAddress,
+
+    pub external_endpoints: Vec<ExternalEndpoint>,
+}
+
+#[derive(Clone, Debug)]
+pub struct VerificationQueue(mpsc::Sender<VerificationRequest>);
+
+impl VerificationQueue {
+    /// Queues an address for verification, the request is dropped if the
+    /// queue is full and the address stays unverified until it is inserted
+    /// again.
+    pub fn push(&self, request: VerificationRequest) {
+        if let Err(err) = self.0.try_send(request) {
+            tracing::warn!("Skip verifying peer, error: {err}");
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PeerVerifier {
+    requests: mpsc::Receiver<VerificationRequest>,
+
+    dial_timeout: Duration,
+
+    concurrency: usize,
+}
+
+impl PeerVerifier {
+    pub fn new(dial_timeout: Duration, concurrency: usize) -> (VerificationQueue, Self) {
+        let (sender, requests) = mpsc::channel(QUEUE_SIZE);
+        (
+            VerificationQueue(sender),
+            Self { requests, dial_timeout, concurrency: concurrency.max(1) },
+        )
+    }
+
+    /// Dials back queued addresses and records the results in `book` until
+    /// `shutdown` resolves.
+    pub async fn run<S>(mut self, book: PeerAddressBook, shutdown: S)
+    where
+        S: Future<Output = ()>,
+    {
+        let (dialer, driver) = match Dialer::new(self.dial_timeout) {
+            Ok(dialer) => dialer,
+            Err(err) => {
+                tracing::error!("Failed to build libp2p swarm, error: {err}");
+                return;
+            }
+        };
+        let driver = driver.run();
+        tokio::pin!(driver);
+        tokio::pin!(shutdown);
+
+        let mut verifications = JoinSet::new();
+        loop {
+            tokio::select! {
+                () = &mut shutdown => break,
+                () = &mut driver => break,
+                Some(result) = verifications.join_next() => {
+                    if let Err(err) = result {
+                        tracing::warn!("Failed to verify peer, error: {err}");
+                    }
+                }
+                request = self.requests.recv(), if verifications.len() < self.concurrency => {
+                    let Some(request) = request else {
+                        break;
+                    };
+                    let book = book.clone();
+                    let dialer = dialer.clone();
+                    let _ = verifications.spawn(async move {
+                        let VerificationRequest { chain_id, address, external_endpoints } =
+                            request;
+                        let (verification, address_verifications) =
+                            verify(&dialer, &address, &external_endpoints).await;
+                        tracing::info!(
+                            "Peer `{address}` of chain `{chain_id}` is {verification:?}"
+                        );
+                        book.set_verification(
+                            &chain_id,
+                            address,
+                            external_endpoints,
+                            verification,
+                            address_verifications,
+                        )
+                        .await;
+                    });
+                }
+            }
+        }
+
+        verifications.shutdown().await;
+    }
+}
+
+/// Dials the exposed addresses and the advertised one, and checks that the
+/// remote authenticates itself with the peer ID in the address. Returns the
+/// outcome of every candidate address. The verifier dials over TCP, plain or
+/// WebSocket, other addresses stay unverified.
+async fn verify(
+    dialer: &Dialer,
+    address: &PeerAddress,
+    external_endpoints: &[ExternalEndpoint],
+) -> (Verification, Vec<AddressVerification>) {
+    let Some(peer_id) = peer_id(address) else {
+        return (Verification::Unverified, Vec::new());
+    };
+
+    let candidates: Vec<_> = external_endpoints
+        .iter()
+        .filter_map(|external_endpoint| address.exposed(external_endpoint))
+        .chain(Some(address.clone()))
+        .collect();
+
+    let address_verifications =
+        futures::future::join_all(candidates.into_iter().map(|candidate| async move {
+            let verification = match candidate.transport() {
+                Some(
+                    kallax_primitives::Transport::Tcp
+                    | kallax_primitives::Transport::Ws
+                    | kallax_primitives::Transport::Wss,
+                ) => dialer.dial(peer_id, candidate.0.clone()).await,
+                Some(kallax_primitives::Transport::Quic) | None => Verification::Unverified,
+            };
+            AddressVerification { address: candidate, verification }
+        }))
+        .await;
+
+    // the peer is as verified as its best address
+    let verification = [Verification::Verified, Verification::Unverified]
+        .into_iter()
+        .find(|verification| address_verifications.iter().any(|v| v.verification == *verification))
+        .unwrap_or(Verification::Unreachable);
+    (verification, address_verifications)
+}
+
+fn peer_id(address: &PeerAddress) -> Option<PeerId> {
+    address.0.iter().find_map(|protocol| match protocol {
+        Protocol::P2p(multihash) => PeerId::from_multihash(multihash).ok(),
+        _ => None,
+    })
+}
+
+/// Strips the trailing `/p2p/<peer ID>`, which the swarm appends to dialed
+/// addresses.
+fn without_peer_id(mut address: Multiaddr) -> Multiaddr {
+    if matches!(address.iter().last(), Some(Protocol::P2p(_))) {
+        let _ = address.pop();
+    }
+    address
+}
+
+struct DialRequest {
+    peer_id: PeerId,
+
+    address: Multiaddr,
+
+    reply: oneshot::Sender<Verification>,
+}
+
+/// Hands dials to the [`SwarmDriver`] shared by all verifications.
+#[derive(Clone, Debug)]
+struct Dialer {
+    requests: mpsc::Sender<DialRequest>,
+
+    dial_timeout: Duration,
+}
+
+impl Dialer {
+    fn new(dial_timeout: Duration) -> io::Result<(Self, SwarmDriver)> {
+        let keypair = Keypair::generate_ed25519();
+        let local_peer_id = keypair.public().to_peer_id();
+        let transport = build_transport(&keypair, dial_timeout)?;
+        let swarm = Swarm::with_tokio_executor(transport, dummy::Behaviour, local_peer_id);
+
+        let (requests, receiver) = mpsc::channel(QUEUE_SIZE);
+        Ok((
+            Self { requests, dial_timeout },
+            SwarmDriver { swarm, requests: receiver, pending: HashMap::new() },
+        ))
+    }
+
+    async fn dial(&self, peer_id: PeerId, address: Multiaddr) -> Verification {
+        let (reply, verification) = oneshot::channel();
+        let request = DialRequest { peer_id, address, reply };
+        if self.requests.send(request).await.is_err() {
+            return Verification::Unreachable;
+        }
+
+        match tokio::time::timeout(self.dial_timeout, verification).await {
+            Ok(Ok(verification)) => verification,
+            Ok(Err(_)) | Err(_) => Verification::Unreachable,
+        }
+    }
+}
+
+fn build_transport(
+    keypair: &Keypair,
+    dial_timeout: Duration,
+) -> io::Result<Boxed<(PeerId, StreamMuxerBox)>> {
+    let tcp = || tcp::tokio::Transport::new(tcp::Config::new().nodelay(true));
+    let dns_tcp = TokioDnsConfig::system(tcp())?;
+    let ws_dns_tcp = WsConfig::new(TokioDnsConfig::system(tcp())?);
+    let noise = noise::NoiseAuthenticated::xx(keypair).map_err(io::Error::other)?;
+
+    Ok(dns_tcp
+        .or_transport(ws_dns_tcp)
+        .upgrade(Version::V1)
+        .authenticate(noise)
+        .multiplex(yamux::YamuxConfig::default())
+        .timeout(dial_timeout)
+        .boxed())
+}
+
+/// Drives the swarm, the noise handshake of a dial proves the peer ID of the
+/// remote, and the connection is closed right after as no protocol is run on
+/// it.
+struct SwarmDriver {
+    swarm: Swarm<dummy::Behaviour>,
+
+    requests: mpsc::Receiver<DialRequest>,
+
+    pending: HashMap<(PeerId, Multiaddr), Vec<oneshot::Sender<Verification>>>,
+}
+
+impl SwarmDriver {
+    async fn run(mut self) {
+        loop {
+            tokio::select! {
+                request = self.requests.recv() => match request {
+                    Some(request) => self.dial(request),
+                    None => break,
+                },
+                event = self.swarm.select_next_some() => self.on_event(event),
+            }
+        }
+    }
+
+    fn dial(&mut self, DialRequest { peer_id, address, reply }: DialRequest) {
+        let opts = DialOpts::peer_id(peer_id)
+            .addresses(vec![address.clone()])
+            .condition(PeerCondition::Always)
+            .build();
+        if let Err(err) = self.swarm.dial(opts) {
+            tracing::debug!("Failed to dial `{address}`, error: {err}");
+            let _ = reply.send(Verification::Unreachable);
+            return;
+        }
+
+        self.pending.entry((peer_id, without_peer_id(address))).or_default().push(reply);
+    }
+
+    fn on_event<E, H>(&mut self, event: SwarmEvent<E, H>) {
+        match event {
+            SwarmEvent::ConnectionEstablished {
+                peer_id,
+                endpoint: ConnectedPoint::Dialer { address, .. },
+                ..
+            } => self.resolve(peer_id, address, Verification::Verified),
+            SwarmEvent::OutgoingConnectionError { peer_id: Some(peer_id), error } => {
+                match error {
+                    DialError::WrongPeerId { obtained, endpoint } => {
+                        let address = endpoint.get_remote_address().clone();
+                        tracing::warn!(
+                            "Peer `{address}` authenticated itself as `{obtained}`, expected \
+                             `{peer_id}`"
+                        );
+                        self.resolve(peer_id, address, Verification::Unverified);
+                    }
+                    DialError::Transport(errors) => {
+                        for (address, err) in errors {
+                            tracing::debug!("Failed to connect `{address}`, error: {err}");
+                            self.resolve(peer_id, address, Verification::Unreachable);
+                        }
+                    }
+                    error => {
+                        // the failed address is unknown, every pending dial of
+                        // the peer is given up
+                        tracing::debug!("Failed to connect `{peer_id}`, error: {error}");
+                        self.pending.retain(|(pending_peer_id, _), replies| {
+                            if *pending_peer_id != peer_id {
+                                return true;
+                            }
+                            for reply in replies.drain(..) {
+                                let _ = reply.send(Verification::Unreachable);
+                            }
+                            false
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn resolve(&mut self, peer_id: PeerId, address: Multiaddr, verification: Verification) {
+        if let Some(replies) = self.pending.remove(&(peer_id, without_peer_id(address))) {
+            for reply in replies {
+                let _ = reply.send(verification);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    const DIAL_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Accepts connections on a local TCP port with a new identity.
+    async fn listener() -> (Multiaddr, PeerId) {
+        let keypair = Keypair::generate_ed25519();
+        let peer_id = keypair.public().to_peer_id();
+        let transport = build_transport(&keypair, DIAL_TIMEOUT).unwrap();
+        let mut swarm = Swarm::with_tokio_executor(transport, dummy::Behaviour, peer_id);
+        let _ = swarm.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap()).unwrap();
+
+        let address = loop {
+            if let SwarmEvent::NewListenAddr { address, .. } = swarm.select_next_some().await {
+                break address;
+            }
+        };
+        drop(tokio::spawn(async move {
+            loop {
+                let _ = swarm.select_next_some().await;
+            }
+        }));
+        (address, peer_id)
+    }
+
+    fn dialer() -> Dialer {
+        let (dialer, driver) = Dialer::new(DIAL_TIMEOUT).unwrap();
+        drop(tokio::spawn(driver.run()));
+        dialer
+    }
+
+    fn closed_port() -> u16 {
+        std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+    }
+
+    fn peer_address(address: &str) -> PeerAddress {
+        PeerAddress::from_str(address).unwrap()
+    }
+
+    #[tokio::test]
+    async fn peer_with_the_advertised_id_is_verified() {
+        let (address, peer_id) = listener().await;
+        let address = peer_address(&format!("{address}/p2p/{peer_id}"));
+
+        let (verification, address_verifications) = verify(&dialer(), &address, &[]).await;
+        assert_eq!(verification, Verification::Verified);
+        assert_eq!(
+            address_verifications,
+            vec![AddressVerification { address, verification: Verification::Verified }]
+        );
+    }
+
+    #[tokio::test]
+    async fn peer_with_another_id_is_unverified() {
+        let (address, _) = listener().await;
+        let other_peer_id = Keypair::generate_ed25519().public().to_peer_id();
+        let address = peer_address(&format!("{address}/p2p/{other_peer_id}"));
+
+        let (verification, _) = verify(&dialer(), &address, &[]).await;
+        assert_eq!(verification, Verification::Unverified);
+    }
+
+    #[tokio::test]
+    async fn each_address_is_verified_and_the_best_one_wins() {
+        let (address, peer_id) = listener().await;
+        let Some(Protocol::Tcp(port)) = address.iter().nth(1) else {
+            panic!("listener is not on TCP");
+        };
+        let advertised =
+            peer_address(&format!("/ip4/127.0.0.1/tcp/{}/p2p/{peer_id}", closed_port()));
+        let external_endpoint = ExternalEndpoint::new("127.0.0.1".to_string(), port);
+
+        let (verification, address_verifications) =
+            verify(&dialer(), &advertised, &[external_endpoint]).await;
+        assert_eq!(verification, Verification::Verified);
+        assert_eq!(
+            address_verifications,
+            vec![
+                AddressVerification {
+                    address: peer_address(&format!("{address}/p2p/{peer_id}")),
+                    verification: Verification::Verified,
+                },
+                AddressVerification {
+                    address: advertised,
+                    verification: Verification::Unreachable
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn closed_port_is_unreachable() {
+        let peer_id = Keypair::generate_ed25519().public().to_peer_id();
+        let address = peer_address(&format!("/ip4/127.0.0.1/tcp/{}/p2p/{peer_id}", closed_port()));
+
+        let (verification, _) = verify(&dialer(), &address, &[]).await;
+        assert_eq!(verification, Verification::Unreachable);
+    }
+
+    #[tokio::test]
+    async fn quic_address_is_left_unverified() {
+        let peer_id = Keypair::generate_ed25519().public().to_peer_id();
+        let address = peer_address(&format!("/ip4/127.0.0.1/udp/30333/quic/p2p/{peer_id}"));
+
+        let (verification, address_verifications) = verify(&dialer(), &address, &[]).await;
+        assert_eq!(verification, Verification::Unverified);
+        assert_eq!(
+            address_verifications,
+            vec![AddressVerification { address, verification: Verification::Unverified }]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use tonic::{service::Interceptor, Code, Request, Status};
+use tonic_types::{ErrorDetails, StatusExt};
+
+#[derive(Clone, Copy, Debug)]
+struct Bucket {
+    tokens: f64,
+
+    last_refill: Instant,
+}
+
+/// Token bucket rate limiter keyed by the source IP of a request.
+///
+/// Each IP may burst up to `burst` requests, then gets `requests_per_second`
+/// new requests every second. A limiter with `requests_per_second == 0` lets
+/// every request through.
+#[derive(Clone, Debug, Default)]
+pub struct RateLimiter {
+    requests_per_second: u32,
+
+    burst: u32,
+
+    buckets: Arc<Mutex<HashMap<IpAddr, Bucket>>>,
+
+    rejected_requests: Arc<AtomicU64>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: u32, burst: u32) -> Self {
+        Self {
+            requests_per_second,
+            burst: burst.max(1),
+            buckets: Arc::default(),
+            rejected_requests: Arc::default(),
+        }
+    }
+
+    pub const fn is_enabled(&self) -> bool {
+        self.requests_per_second != 0
+    }
+
+    /// Takes one token from the bucket of `ip`, returns `false` if the bucket
+    /// is empty.
+    pub fn check(&self, ip: IpAddr) -> bool {
+        if !self.is_enabled() {
+            return true;
+        }
+
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().expect("rate limiter lock is poisoned");
+        let bucket = buckets
+            .entry(ip)
+            .or_insert_with(|| Bucket { tokens: f64::from(self.burst), last_refill: now });
+        self.refill(bucket, now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            drop(buckets);
+            self.rejected_requests.fetch_add(1, Ordering::Relaxed);
+            false
+        }
+    }
+
+    /// Drops the buckets that are full again, so that the limiter does not
+    /// grow with the number of IPs it has ever seen.
+    pub fn flush(&self) {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().expect("rate limiter lock is poisoned");
+        buckets.retain(|_, bucket| {
+            self.refill(bucket, now);
+            bucket.tokens < f64::from(self.burst)
+        });
+    }
+
+    pub const fn requests_per_second(&self) -> u32 {
+        self.requests_per_second
+    }
+
+    /// Delay after which a rejected client gets a new token.
+    pub fn retry_delay(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / f64::from(self.requests_per_second.max(1)))
+    }
+
+    pub fn rejected_requests(&self) -> u64 {
+        self.rejected_requests.load(Ordering::Relaxed)
+    }
+
+    fn refill(&self, bucket: &mut Bucket, now: Instant) {
+        let elapsed = now.saturating_duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = elapsed
+            .mul_add(f64::from(self.requests_per_second), bucket.tokens)
+            .min(f64::from(self.burst));
+        bucket.last_refill = now;
+    }
+}
+
+impl Interceptor for RateLimiter {
+    fn call(&mut self, req: Request<()>) -> Result<Request<()>, Status> {
+        match req.remote_addr() {
+            Some(remote_addr) if !self.check(remote_addr.ip()) => {
+                tracing::debug!("Reject request from `{remote_addr}`, rate limit exceeded");
+                Err(Status::with_error_details(
+                    Code::ResourceExhausted,
+                    format!(
+                        "rate limit of {} requests per second exceeded",
+                        self.requests_per_second
+                    ),
+                    ErrorDetails::with_retry_info(Some(self.retry_delay())),
+                ))
+            }
+            _ => Ok(req),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+    use tonic::transport::server::TcpConnectInfo;
+
+    use super::*;
+
+    const IP_A: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+    const IP_B: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+    #[test]
+    fn rejects_requests_over_burst_per_ip() {
+        let limiter = RateLimiter::new(1, 3);
+
+        assert!((0..3).all(|_| limiter.check(IP_A)));
+        assert!(!limiter.check(IP_A));
+        // other IPs have their own bucket
+        assert!(limiter.check(IP_B));
+        assert_eq!(limiter.rejected_requests(), 1);
+    }
+
+    #[test]
+    fn disabled_limiter_accepts_everything() {
+        let limiter = RateLimiter::new(0, 1);
+
+        assert!((0..100).all(|_| limiter.check(IP_A)));
+        assert_eq!(limiter.rejected_requests(), 0);
+    }
+
+    #[test]
+    fn rejected_requests_carry_retry_info() {
+        let mut limiter = RateLimiter::new(2, 1);
+        let request = || {
+            let mut request = Request::new(());
+            let _ = request.extensions_mut().insert(TcpConnectInfo {
+                local_addr: None,
+                remote_addr: Some(SocketAddr::new(IP_A, 30333)),
+            });
+            request
+        };
+
+        assert!(limiter.call(request()).is_ok());
+        let status = limiter.call(request()).unwrap_err();
+        assert_eq!(status.code(), Code::ResourceExhausted);
+        let retry_info = status.get_details_retry_info().unwrap();
+        assert_eq!(retry_info.retry_delay, Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn flush_keeps_only_drained_buckets() {
+        let limiter = RateLimiter::new(1, 2);
+        limiter.check(IP_A);
+        limiter.check(IP_A);
+
+        limiter.flush();
+        assert_eq!(limiter.buckets.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use kallax_primitives::{NodeRole, PeerMetadata};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct TopologyNode {
+    pub peer_id: String,
+
+    pub node_name: Option<String>,
+
+    pub role: Option<NodeRole>,
+
+    /// `false` for peers which are only known from the reports of others.
+    pub is_tracked: bool,
+
+    /// Tracked node without any connection.
+    pub is_isolated: bool,
+
+    /// Index of the connected component the node belongs to.
+    pub partition: usize,
+}
+
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize, ToSchema)]
+pub struct TopologyEdge {
+    pub from: String,
+
+    pub to: String,
+}
+
+/// Connectivity graph of a chain, built from the connected and reserved peers
+/// reported by each node.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct Topology {
+    pub chain_id: String,
+
+    pub nodes: Vec<TopologyNode>,
+
+    /// Undirected connections, `from` is always the lower peer ID.
+    pub edges: Vec<TopologyEdge>,
+
+    /// Reserved peers the reporting node is not connected to.
+    pub unconnected_reserved_peers: Vec<TopologyEdge>,
+
+    /// Number of connected components among the tracked nodes, more than one
+    /// means the network is partitioned.
+    pub partitions: usize,
+}
+
+impl Topology {
+    pub fn build<'a, I>(chain_id: &str, reports: I) -> Self
+    where
+        I: IntoIterator<Item = (String, &'a PeerMetadata)>,
+    {
+        let reports = reports.into_iter().collect::<BTreeMap<_, _>>();
+
+        let mut edges = BTreeSet::new();
+        let mut unconnected_reserved_peers = BTreeSet::new();
+        for (peer_id, metadata) in &reports {
+            for other in &metadata.connected_peers {
+                if other != peer_id {
+                    let (from, to) =
+                        if peer_id < other { (peer_id, other) } else { (other, peer_id) };
+                    edges.insert(TopologyEdge { from: from.clone(), to: to.clone() });
+                }
+            }
+            for reserved in &metadata.reserved_peers {
+                if !metadata.connected_peers.contains(reserved) {
+                    unconnected_reserved_peers
+                        .insert(TopologyEdge { from: peer_id.clone(), to: reserved.clone() });
+                }
+            }
+        }
+
+        let mut peer_ids = reports.keys().cloned().collect::<BTreeSet<_>>();
+        peer_ids.extend(edges.iter().flat_map(|edge| [edge.from.clone(), edge.to.clone()]));
+
+        let partition_of = partition(&peer_ids, &edges);
+        let partitions = reports
+            .keys()
+            .filter_map(|peer_id| partition_of.get(peer_id))
+            .collect::<BTreeSet<_>>()
+            .len();
+
+        let nodes = peer_ids
+            .into_iter()
+            .map(|peer_id| {
+                let metadata = reports.get(&peer_id);
+                let is_tracked = metadata.is_some();
+                let is_isolated = is_tracked
+                    && !edges.iter().any(|edge| edge.from == peer_id || edge.to == peer_id);
+                TopologyNode {
+                    partition: partition_of[&peer_id],
+                    node_name: metadata.and_then(|metadata| metadata.node_name.clone()),
+                    role: metadata.and_then(|metadata| metadata.role),
+                    is_tracked,
+                    is_isolated,
+                    peer_id,
+                }
+            })
+            .collect();
+
+        Self {
+            chain_id: chain_id.to_string(),
+            nodes,
+            edges: edges.into_iter().collect(),
+            unconnected_reserved_peers: unconnected_reserved_peers.into_iter().collect(),
+            partitions,
+        }
+    }
+
+    /// Renders the graph in the Graphviz DOT language. Isolated nodes are
+    /// drawn in red, untracked peers dashed, and reserved peers which are not
+    /// connected as dashed red arrows.
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec![format!("graph {} {{", quote(&self.chain_id))];
+        for node in &self.nodes {
+            // the line break is appended after escaping, node names are
+            // reported by the nodes and must not inject escape sequences
+            let label = node.node_name.as_ref().map_or_else(
+                || quote(&node.peer_id),
+                |name| format!("\"{}\\n{}\"", escape(name), escape(&node.peer_id)),
+            );
+            let mut attributes = vec![format!("label={label}")];
+            if node.is_isolated {
+                attributes.push("color=red".to_string());
+            }
+            if !node.is_tracked {
+                attributes.push("style=dashed".to_string());
+            }
+            lines.push(format!("  {} [{}];", quote(&node.peer_id), attributes.join(", ")));
+        }
+        for TopologyEdge { from, to } in &self.edges {
+            lines.push(format!("  {} -- {};", quote(from), quote(to)));
+        }
+        for TopologyEdge { from, to } in &self.unconnected_reserved_peers {
+            lines.push(format!(
+                "  {} -- {} [dir=forward, style=dashed, color=red];",
+                quote(from),
+                quote(to)
+            ));
+        }
+        lines.push("}\n".to_string());
+        lines.join("\n")
+    }
+}
+
+/// Assigns every peer the index of its connected component.
+fn partition(
+    peer_ids: &BTreeSet<String>,
+    edges: &BTreeSet<TopologyEdge>,
+) -> BTreeMap<String, usize> {
+    let mut partition_of = BTreeMap::new();
+    let mut next_partition = 0;
+    for peer_id in peer_ids {
+        if partition_of.contains_key(peer_id) {
+            continue;
+        }
+        let mut pending = vec![peer_id.clone()];
+        while let Some(current) = pending.pop() {
+            if partition_of.insert(current.clone(), next_partition).is_some() {
+                continue;
+            }
+            for TopologyEdge { from, to } in edges {
+                if *from == current && !partition_of.contains_key(to) {
+                    pending.push(to.clone());
+                } else if *to == current && !partition_of.contains_key(from) {
+                    pending.push(from.clone());
+                }
+            }
+        }
+        next_partition += 1;
+    }
+    partition_of
+}
+
+/// Quotes a DOT identifier.
+fn quote(id: &str) -> String {
+    format!("\"{}\"", escape(id))
+}
+
+/// Escapes backslashes and double quotes of a quoted DOT identifier, control
+/// characters are stripped.
+fn escape(id: &str) -> String {
+    id.chars().filter(|c| !c.is_control()).fold(
+        String::with_capacity(id.len()),
+        |mut escaped, c| {
+            if matches!(c, '\\' | '"') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+            escaped
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(connected: &[&str], reserved: &[&str]) -> PeerMetadata {
+        PeerMetadata {
+            connected_peers: connected.iter().map(ToString::to_string).collect(),
+            reserved_peers: reserved.iter().map(ToString::to_string).collect(),
+            ..PeerMetadata::default()
+        }
+    }
+
+    #[test]
+    fn detects_partitions_isolated_nodes_and_unconnected_reserved_peers() {
+        let a = metadata(&["b"], &["b", "c"]);
+        let b = metadata(&["a"], &[]);
+        let c = metadata(&["d"], &[]);
+        let e = metadata(&[], &[]);
+        let topology = Topology::build(
+            "chain-1",
+            [
+                ("a".to_string(), &a),
+                ("b".to_string(), &b),
+                ("c".to_string(), &c),
+                ("e".to_string(), &e),
+            ],
+        );
+
+        assert_eq!(topology.edges.len(), 2);
+        assert_eq!(topology.partitions, 3);
+        assert_eq!(
+            topology.unconnected_reserved_peers,
+            vec![TopologyEdge { from: "a".to_string(), to: "c".to_string() }]
+        );
+        let node = |id: &str| topology.nodes.iter().find(|node| node.peer_id == id).unwrap();
+        assert!(node("e").is_isolated);
+        assert!(!node("d").is_tracked);
+        assert_eq!(node("a").partition, node("b").partition);
+        assert_ne!(node("a").partition, node("c").partition);
+
+        let dot = topology.to_dot();
+        assert!(dot.starts_with("graph \"chain-1\" {"));
+        assert!(dot.contains("\"a\" -- \"b\";"));
+        assert!(dot.contains("\"e\" [label=\"e\", color=red];"));
+    }
+
+    #[test]
+    fn dot_escapes_node_names() {
+        let a = PeerMetadata {
+            node_name: Some("a\\\" [color=blue\n".to_string()),
+            ..metadata(&[], &[])
+        };
+        let topology = Topology::build("chain-1", [("a".to_string(), &a)]);
+
+        let dot = topology.to_dot();
+        assert!(dot.contains(r#"  "a" [label="a\\\" [color=blue\na", color=red];"#), "{dot}");
+    }
+}
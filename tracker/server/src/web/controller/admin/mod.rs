@@ -0,0 +1,76 @@
+mod v1;
+
+use axum::{http::Method, middleware};
+use utoipa::{
+    openapi::security::{Http, HttpAuthScheme, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use crate::{
+    audit_log::{AuditAction, AuditEvent},
+    drain_list::{DrainEntry, DrainRequest},
+    peer_policy::{PeerMatcher, PolicyAction, PolicyEntry, PolicyRule},
+    web::{
+        controller::{nest, Route},
+        middleware::require_admin_token,
+    },
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        self::v1::get_peer_policy,
+        self::v1::add_peer_policy_rule,
+        self::v1::remove_peer_policy_rule,
+        self::v1::get_audit_log,
+        self::v1::get_draining_peers,
+        self::v1::drain_peer,
+        self::v1::undrain_peer
+    ),
+    components(schemas(
+        AuditAction,
+        AuditEvent,
+        DrainEntry,
+        DrainRequest,
+        PeerMatcher,
+        PolicyAction,
+        PolicyEntry,
+        PolicyRule
+    )),
+    modifiers(&AdminTokenScheme)
+)]
+pub struct V1ApiDoc;
+
+struct AdminTokenScheme;
+
+impl Modify for AdminTokenScheme {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        openapi.components.get_or_insert_with(Default::default).add_security_scheme(
+            "admin_token",
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+        );
+    }
+}
+
+/// Routes of the admin API, every route requires the admin token.
+pub fn v1() -> Vec<Route> {
+    nest(
+        "/v1/admin",
+        vec![
+            Route::new(Method::GET, "/peer-policy", self::v1::get_peer_policy),
+            Route::new(Method::POST, "/peer-policy", self::v1::add_peer_policy_rule),
+            Route::new(Method::DELETE, "/peer-policy/:rule_id", self::v1::remove_peer_policy_rule),
+            Route::new(Method::GET, "/audit-log", self::v1::get_audit_log),
+            Route::new(Method::GET, "/drain", self::v1::get_draining_peers),
+            Route::new(Method::PUT, "/drain/:peer_id", self::v1::drain_peer),
+            Route::new(Method::DELETE, "/drain/:peer_id", self::v1::undrain_peer),
+        ],
+    )
+    .into_iter()
+    .map(|Route { method, path, handler }| Route {
+        method,
+        path,
+        handler: handler.route_layer(middleware::from_fn(require_admin_token)),
+    })
+    .collect()
+}
@@ -0,0 +1,157 @@
+use axum::{extract::Extension, http::StatusCode};
+
+use crate::{
+    audit_log::{AuditEvent, AuditLog, AuditQuery},
+    drain_list::{DrainEntry, DrainList, DrainRequest},
+    error::{AuditLogError, PeerPolicyError},
+    peer_policy::{PeerPolicy, PolicyEntry, PolicyRule},
+    web::{
+        extract::{Json, Path, Query},
+        problem::{Problem, ProblemCode},
+    },
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/peer-policy",
+    tag = "admin",
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Rules of the peer policy", body = Vec<PolicyEntry>),
+        (status = 401, body = Problem, content_type = "application/problem+json"),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_peer_policy(
+    Extension(policy): Extension<PeerPolicy>,
+) -> (StatusCode, Json<Vec<PolicyEntry>>) {
+    (StatusCode::OK, Json(policy.snapshot().await.rules().to_vec()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/peer-policy",
+    tag = "admin",
+    request_body = PolicyRule,
+    security(("admin_token" = [])),
+    responses(
+        (status = 201, description = "The rule is added", body = PolicyEntry),
+        (status = 400, body = Problem, content_type = "application/problem+json"),
+        (status = 500, body = Problem, content_type = "application/problem+json"),
+        (status = 401, body = Problem, content_type = "application/problem+json"),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn add_peer_policy_rule(
+    Extension(policy): Extension<PeerPolicy>,
+    Json(rule): Json<PolicyRule>,
+) -> Result<(StatusCode, Json<PolicyEntry>), PeerPolicyError> {
+    Ok((StatusCode::CREATED, Json(policy.add_rule(rule).await?)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/peer-policy/{rule_id}",
+    tag = "admin",
+    params(("rule_id" = u64, Path, description = "ID of the rule")),
+    security(("admin_token" = [])),
+    responses(
+        (status = 204, description = "The rule is removed"),
+        (status = 500, body = Problem, content_type = "application/problem+json"),
+        (status = 401, body = Problem, content_type = "application/problem+json"),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn remove_peer_policy_rule(
+    Extension(policy): Extension<PeerPolicy>,
+    Path(rule_id): Path<u64>,
+) -> Result<StatusCode, Problem> {
+    if policy.remove_rule(rule_id).await?.is_some() {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(Problem::new(ProblemCode::PolicyRuleNotFound, format!("rule `{rule_id}` not found")))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/drain",
+    tag = "admin",
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Draining peers", body = Vec<DrainEntry>),
+        (status = 401, body = Problem, content_type = "application/problem+json"),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_draining_peers(
+    Extension(drain_list): Extension<DrainList>,
+) -> (StatusCode, Json<Vec<DrainEntry>>) {
+    (StatusCode::OK, Json(drain_list.entries().await))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/drain/{peer_id}",
+    tag = "admin",
+    params(("peer_id" = String, Path, description = "libp2p peer ID")),
+    request_body(content = Option<DrainRequest>),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "The peer is draining", body = DrainEntry),
+        (status = 401, body = Problem, content_type = "application/problem+json"),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn drain_peer(
+    Extension(drain_list): Extension<DrainList>,
+    Path(peer_id): Path<String>,
+    request: Option<Json<DrainRequest>>,
+) -> (StatusCode, Json<DrainEntry>) {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    (StatusCode::OK, Json(drain_list.drain(peer_id, request).await))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/drain/{peer_id}",
+    tag = "admin",
+    params(("peer_id" = String, Path, description = "libp2p peer ID")),
+    security(("admin_token" = [])),
+    responses(
+        (status = 204, description = "The peer is no longer draining"),
+        (status = 401, body = Problem, content_type = "application/problem+json"),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn undrain_peer(
+    Extension(drain_list): Extension<DrainList>,
+    Path(peer_id): Path<String>,
+) -> Result<StatusCode, Problem> {
+    if drain_list.undrain(&peer_id).await {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(Problem::new(ProblemCode::DrainNotFound, format!("peer `{peer_id}` is not draining")))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit-log",
+    tag = "admin",
+    params(AuditQuery),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Matching audit events", body = Vec<AuditEvent>),
+        (status = 400, body = Problem, content_type = "application/problem+json"),
+        (status = 500, body = Problem, content_type = "application/problem+json"),
+        (status = 401, body = Problem, content_type = "application/problem+json"),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_audit_log(
+    Extension(audit_log): Extension<AuditLog>,
+    Query(query): Query<AuditQuery>,
+) -> Result<(StatusCode, Json<Vec<AuditEvent>>), AuditLogError> {
+    Ok((StatusCode::OK, Json(audit_log.query(&query).await?)))
+}
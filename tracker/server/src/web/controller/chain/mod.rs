@@ -0,0 +1,37 @@
+mod v2;
+
+use axum::http::Method;
+use utoipa::OpenApi;
+
+use crate::web::controller::{nest, Route};
+
+pub use self::v2::{InsertPeerAddressRequest, InsertPeerAddressResponse};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        self::v2::get_chain_spec,
+        self::v2::get_peers,
+        self::v2::get_topology,
+        self::v2::insert_peers
+    ),
+    components(schemas(
+        self::v2::GetPeersResponse,
+        InsertPeerAddressRequest,
+        InsertPeerAddressResponse,
+        crate::peer_address_book::PeerAddressGroup
+    ))
+)]
+pub struct V2ApiDoc;
+
+pub fn v2() -> Vec<Route> {
+    nest(
+        "/v2/:layer",
+        vec![
+            Route::new(Method::GET, "/:chain_id/chain-spec", self::v2::get_chain_spec),
+            Route::new(Method::GET, "/:chain_id/peers", self::v2::get_peers),
+            Route::new(Method::GET, "/:chain_id/topology", self::v2::get_topology),
+            Route::new(Method::POST, "/:chain_id/insert", self::v2::insert_peers),
+        ],
+    )
+}
@@ -0,0 +1,190 @@
+use std::net::SocketAddr;
+
+use axum::{
+    extract::{ConnectInfo, Extension},
+    headers::ContentType,
+    http::StatusCode,
+    response::Response,
+    TypedHeader,
+};
+use kallax_primitives::{BlockchainLayer, ExternalEndpoint, NodeRole, PeerAddress, PeerMetadata};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    address_filter::AddressFilter,
+    audit_log::AuditLog,
+    by_layer::ByLayer,
+    chain_spec_list::ChainSpecList,
+    error::InsertPeerError,
+    peer_address_book::{PeerAddressBook, PeerAddressGroup},
+    web::{
+        controller::topology::{self, GetTopologyQuery},
+        extract::{Json, Path, Query},
+        problem::{Problem, ProblemCode},
+    },
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/{layer}/{chain_id}/chain-spec",
+    tag = "chain",
+    params(
+        ("layer" = BlockchainLayer, Path, description = "Layer of the chain"),
+        ("chain_id" = String, Path, description = "Chain ID, e.g. `thx-mainnet`"),
+    ),
+    responses(
+        (status = 200, description = "Raw chain spec", body = Object),
+        (status = 400, body = Problem, content_type = "application/problem+json"),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_chain_spec(
+    Extension(lists): Extension<ByLayer<ChainSpecList>>,
+    Path((layer, chain_id)): Path<(BlockchainLayer, String)>,
+) -> Result<(StatusCode, TypedHeader<ContentType>, Vec<u8>), Problem> {
+    let chain_spec = lists.get(layer).get(&chain_id).await.ok_or_else(|| {
+        Problem::new(
+            ProblemCode::ChainSpecNotFound,
+            format!("chain spec of {layer} `{chain_id}` not found"),
+        )
+    })?;
+    Ok((StatusCode::OK, TypedHeader(ContentType::json()), chain_spec.as_ref().to_vec()))
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetPeersQuery {
+    pub role: Option<NodeRole>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GetPeersResponse {
+    pub peers: Vec<PeerAddressGroup>,
+
+    /// IDs of peers which were evicted or cleared recently.
+    pub tombstones: Vec<String>,
+
+    /// IDs of tracked peers which are draining and not handed out.
+    pub draining: Vec<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/{layer}/{chain_id}/peers",
+    tag = "chain",
+    params(
+        ("layer" = BlockchainLayer, Path, description = "Layer of the chain"),
+        ("chain_id" = String, Path, description = "Chain ID, e.g. `thx-mainnet`"),
+        GetPeersQuery,
+    ),
+    responses(
+        (status = 200, description = "Peers or boot nodes", body = GetPeersResponse),
+        (status = 400, body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_peers(
+    Extension(books): Extension<ByLayer<PeerAddressBook>>,
+    Extension(lists): Extension<ByLayer<ChainSpecList>>,
+    Extension(address_filter): Extension<AddressFilter>,
+    ConnectInfo(caller): ConnectInfo<SocketAddr>,
+    Path((layer, chain_id)): Path<(BlockchainLayer, String)>,
+    Query(GetPeersQuery { role }): Query<GetPeersQuery>,
+) -> (StatusCode, Json<GetPeersResponse>) {
+    let book = books.get(layer);
+    let mut peers = address_filter
+        .served_groups(book.fetch_peer_groups(&chain_id, role).await, Some(caller.ip()));
+    if peers.is_empty() {
+        peers = PeerAddressGroup::from_addresses(lists.get(layer).boot_nodes(&chain_id).await);
+    }
+    let tombstones = book.tombstones(&chain_id).await;
+    let draining = book.draining_peers(&chain_id).await;
+    (StatusCode::OK, Json(GetPeersResponse { peers, tombstones, draining }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/{layer}/{chain_id}/topology",
+    tag = "chain",
+    params(
+        ("layer" = BlockchainLayer, Path, description = "Layer of the chain"),
+        ("chain_id" = String, Path, description = "Chain ID, e.g. `thx-mainnet`"),
+        GetTopologyQuery,
+    ),
+    responses(
+        (status = 200, content(("application/json" = Topology), ("text/vnd.graphviz" = String))),
+        (status = 400, body = Problem, content_type = "application/problem+json"),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_topology(
+    Extension(books): Extension<ByLayer<PeerAddressBook>>,
+    Path((layer, chain_id)): Path<(BlockchainLayer, String)>,
+    Query(GetTopologyQuery { format }): Query<GetTopologyQuery>,
+) -> Result<Response, Problem> {
+    let topology = books.get(layer).topology(&chain_id).await.ok_or_else(|| {
+        Problem::new(
+            ProblemCode::ChainNotFound,
+            format!("no peer of {layer} `{chain_id}` is tracked"),
+        )
+    })?;
+    Ok(topology::render(topology, format))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InsertPeerAddressRequest {
+    pub peer_address: PeerAddress,
+    #[serde(default)]
+    pub external_endpoints: Vec<ExternalEndpoint>,
+    #[serde(default)]
+    pub metadata: PeerMetadata,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct InsertPeerAddressResponse {
+    /// e.g. the peer ID is also registered by another node
+    pub warnings: Vec<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v2/{layer}/{chain_id}/insert",
+    tag = "chain",
+    params(
+        ("layer" = BlockchainLayer, Path, description = "Layer of the chain"),
+        ("chain_id" = String, Path, description = "Chain ID, e.g. `thx-mainnet`"),
+    ),
+    request_body = InsertPeerAddressRequest,
+    responses(
+        (status = 200, description = "Inserted or skipped", body = InsertPeerAddressResponse),
+        (status = 400, body = Problem, content_type = "application/problem+json"),
+        (status = 403, body = Problem, content_type = "application/problem+json"),
+        (status = 429, body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn insert_peers(
+    Extension(books): Extension<ByLayer<PeerAddressBook>>,
+    Extension(audit_log): Extension<AuditLog>,
+    Extension(address_filter): Extension<AddressFilter>,
+    ConnectInfo(caller): ConnectInfo<SocketAddr>,
+    Path((layer, chain_id)): Path<(BlockchainLayer, String)>,
+    Json(payload): Json<InsertPeerAddressRequest>,
+) -> Result<Json<InsertPeerAddressResponse>, InsertPeerError> {
+    let InsertPeerAddressRequest { peer_address, external_endpoints, metadata } = payload;
+    if !address_filter.accepts(&peer_address) {
+        tracing::info!(
+            "New peer `{peer_address}` is in {} network, skip to insert to {layer} `{chain_id}`",
+            peer_address.scope()
+        );
+        return Ok(Json(InsertPeerAddressResponse::default()));
+    }
+    tracing::info!("Insert new peer `{peer_address}` to {layer} `{chain_id}`");
+    let outcome = books
+        .get(layer)
+        .insert(&chain_id, peer_address.clone(), external_endpoints, metadata, Some(caller.ip()))
+        .await?;
+    audit_log.record_insert(layer, Some(caller), &chain_id, &peer_address, &outcome).await;
+
+    let warnings = outcome.warning(&peer_address.id()).into_iter().collect();
+    Ok(Json(InsertPeerAddressResponse { warnings }))
+}
@@ -0,0 +1,39 @@
+use axum::{extract::Extension, http::StatusCode};
+use kallax_primitives::{BlockchainComponent, BlockchainLayer, ServedChain};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    component_registry::{Component, ComponentRegistry},
+    web::extract::{Json, Query},
+};
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetComponentsQuery {
+    pub kind: Option<BlockchainComponent>,
+
+    /// Returns only components serving the chain if given along with
+    /// `chain_id`.
+    pub layer: Option<BlockchainLayer>,
+
+    pub chain_id: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/components",
+    tag = "component",
+    params(GetComponentsQuery),
+    responses(
+        (status = 200, description = "Live components", body = Vec<Component>),
+        (status = 400, body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_components(
+    Extension(registry): Extension<ComponentRegistry>,
+    Query(GetComponentsQuery { kind, layer, chain_id }): Query<GetComponentsQuery>,
+) -> (StatusCode, Json<Vec<Component>>) {
+    let chain = layer.zip(chain_id).map(|(layer, chain_id)| ServedChain { layer, chain_id });
+    (StatusCode::OK, Json(registry.list(kind, chain.as_ref()).await))
+}
@@ -2,49 +2,80 @@ use std::collections::HashMap;
 
 use axum::{extract::Extension, http::StatusCode, response::IntoResponse, Json};
 use serde::Serialize;
+use utoipa::ToSchema;
 
-use crate::web::extension::{
-    LeafchainPeerAddressBook, RootchainPeerAddressBook, TrackerConfig, TrackerStartTime,
+use crate::{
+    component_registry::{Component, ComponentRegistry},
+    drain_list::{DrainEntry, DrainList},
+    peer_address_book::{CapacityRejections, DiagnosticPeer, KeyConflict, PeerAddressBook},
+    rate_limiter::RateLimiter,
+    web::extension::{
+        LeafchainPeerAddressBook, RootchainPeerAddressBook, TrackerConfig, TrackerStartTime,
+    },
 };
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-#[derive(Serialize)]
-struct DiagnosticResponse {
+#[derive(Serialize, ToSchema)]
+pub struct DiagnosticResponse {
+    #[schema(value_type = String)]
     version: &'static str,
     uptime_seconds: u64,
+    #[schema(value_type = Object)]
     config: TrackerConfig,
+    rate_limited_requests: u64,
+    draining_peers: Vec<DrainEntry>,
     rootchain: ChainSummary,
     leafchain: ChainSummary,
+    components: Vec<Component>,
 }
 
-#[derive(Serialize)]
-struct ChainSummary {
+#[derive(Serialize, ToSchema)]
+pub struct ChainSummary {
     chain_count: usize,
     total_peer_count: usize,
     peer_count_per_chain: HashMap<String, usize>,
+    #[schema(value_type = Object)]
+    rejected_insertions: CapacityRejections,
+    #[schema(value_type = Object)]
+    key_conflicts: HashMap<String, Vec<KeyConflict>>,
+    /// Tracked addresses of every chain with their state and metadata.
+    peers: HashMap<String, Vec<DiagnosticPeer>>,
 }
 
+impl ChainSummary {
+    async fn new(book: &PeerAddressBook) -> Self {
+        let peer_count_per_chain = book.peer_counts().await;
+        Self {
+            chain_count: peer_count_per_chain.len(),
+            total_peer_count: peer_count_per_chain.values().sum(),
+            peer_count_per_chain,
+            rejected_insertions: book.capacity_rejections(),
+            key_conflicts: book.key_conflicts().await,
+            peers: book.diagnostic_snapshot().await,
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/diagnostic",
+    tag = "status",
+    responses(
+        (status = 200, description = "Tracker state", body = DiagnosticResponse),
+    )
+)]
 pub async fn get_diagnostic(
     Extension(config): Extension<TrackerConfig>,
     Extension(start_time): Extension<TrackerStartTime>,
     Extension(RootchainPeerAddressBook(rootchain_book)): Extension<RootchainPeerAddressBook>,
     Extension(LeafchainPeerAddressBook(leafchain_book)): Extension<LeafchainPeerAddressBook>,
+    Extension(rate_limiter): Extension<RateLimiter>,
+    Extension(drain_list): Extension<DrainList>,
+    Extension(component_registry): Extension<ComponentRegistry>,
 ) -> impl IntoResponse {
-    let rootchain_counts = rootchain_book.peer_counts().await;
-    let leafchain_counts = leafchain_book.peer_counts().await;
-
-    let rootchain = ChainSummary {
-        chain_count: rootchain_counts.len(),
-        total_peer_count: rootchain_counts.values().sum(),
-        peer_count_per_chain: rootchain_counts,
-    };
-
-    let leafchain = ChainSummary {
-        chain_count: leafchain_counts.len(),
-        total_peer_count: leafchain_counts.values().sum(),
-        peer_count_per_chain: leafchain_counts,
-    };
+    let rootchain = ChainSummary::new(&rootchain_book).await;
+    let leafchain = ChainSummary::new(&leafchain_book).await;
 
     (
         StatusCode::OK,
@@ -52,8 +83,157 @@ pub async fn get_diagnostic(
             version: VERSION,
             uptime_seconds: start_time.0.elapsed().as_secs(),
             config,
+            rate_limited_requests: rate_limiter.rejected_requests(),
+            draining_peers: drain_list.entries().await,
             rootchain,
             leafchain,
+            components: component_registry.list(None, None).await,
         }),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use axum::{
+        body::Body,
+        extract::Extension,
+        http::{Request, StatusCode},
+        routing, Router,
+    };
+    use kallax_primitives::{ExternalEndpoint, NodeRole, PeerAddress, PeerMetadata};
+    use tower::ServiceExt;
+
+    use super::get_diagnostic;
+    use crate::{
+        component_registry::ComponentRegistry,
+        drain_list::DrainList,
+        peer_address_book::{Capacity, PeerAddressBook},
+        peer_verifier::{AddressVerification, PeerVerifier, Verification},
+        rate_limiter::RateLimiter,
+        web::extension::{
+            LeafchainPeerAddressBook, RootchainPeerAddressBook, TrackerConfig, TrackerStartTime,
+        },
+        VerificationMode,
+    };
+
+    const PEER_ADDRESS: &str =
+        "/ip4/10.0.0.1/tcp/30333/p2p/12D3KooWEYdR9WN6tyReBTmngueGTRAQztkWrNLx9kCw9aQ3Tbwo";
+
+    async fn diagnostic(rootchain_book: PeerAddressBook) -> serde_json::Value {
+        let config = TrackerConfig {
+            peer_time_to_live_seconds: 0,
+            tombstone_time_to_live_seconds: 0,
+            min_surviving_peers_per_chain: 0,
+            rate_limit_per_second: 0,
+            rate_limit_burst: 0,
+            capacity: Capacity::default(),
+            peer_verification_mode: VerificationMode::Disabled,
+            accepted_address_scopes: kallax_primitives::AddressPolicy::default(),
+            scope_addresses_by_caller: false,
+            record_observed_addresses: false,
+            chain_stall_threshold_seconds: 0,
+        };
+        let router = Router::new()
+            .route("/", routing::get(get_diagnostic))
+            .layer(Extension(config))
+            .layer(Extension(TrackerStartTime(std::time::Instant::now())))
+            .layer(Extension(RootchainPeerAddressBook(rootchain_book)))
+            .layer(Extension(LeafchainPeerAddressBook(PeerAddressBook::new())))
+            .layer(Extension(RateLimiter::new(0, 0)))
+            .layer(Extension(DrainList::default()))
+            .layer(Extension(ComponentRegistry::with_ttl(std::time::Duration::from_secs(60))));
+
+        let response =
+            router.oneshot(Request::get("/").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn peers_are_served_with_their_metadata() {
+        let book = PeerAddressBook::new();
+        let metadata = PeerMetadata {
+            role: Some(NodeRole::Validator),
+            client_version: Some("0.9.40".to_string()),
+            best_block_number: Some(42),
+            connected_peers: vec![
+                "12D3KooWHdiAxVd8uMQR1hGWXccidmfCwLqcMpGwR6QcTP6QRMuD".to_string()
+            ],
+            ..PeerMetadata::default()
+        };
+        let address = PeerAddress::from_str(PEER_ADDRESS).unwrap();
+        let _unused =
+            book.insert("chain-1", address, Vec::new(), metadata.clone(), None).await.unwrap();
+
+        let response = diagnostic(book).await;
+        let peers = &response["rootchain"]["peers"]["chain-1"];
+        assert_eq!(peers.as_array().map(Vec::len), Some(1));
+        assert_eq!(peers[0]["address"], PEER_ADDRESS);
+        assert_eq!(peers[0]["metadata"], serde_json::to_value(metadata).unwrap());
+        assert!(peers[0]["last_seen"].is_string());
+    }
+
+    #[tokio::test]
+    async fn peers_are_served_with_the_verification_of_each_address() {
+        let (queue, _verifier) = PeerVerifier::new(std::time::Duration::from_secs(1), 1);
+        let book = PeerAddressBook::new().with_verification(VerificationMode::Observe, queue);
+        let address = PeerAddress::from_str(PEER_ADDRESS).unwrap();
+        let external = ExternalEndpoint::new("203.0.113.7".to_string(), 30333);
+        let exposed = address.exposed(&external).unwrap();
+        let _unused = book
+            .insert(
+                "chain-1",
+                address.clone(),
+                vec![external.clone()],
+                PeerMetadata::default(),
+                None,
+            )
+            .await
+            .unwrap();
+        book.set_verification(
+            "chain-1",
+            address.clone(),
+            vec![external],
+            Verification::Verified,
+            vec![
+                AddressVerification {
+                    address: exposed.clone(),
+                    verification: Verification::Verified,
+                },
+                AddressVerification { address, verification: Verification::Unreachable },
+            ],
+        )
+        .await;
+
+        let response = diagnostic(book).await;
+        let peer = &response["rootchain"]["peers"]["chain-1"][0];
+        assert_eq!(peer["verification"], "verified");
+        assert_eq!(
+            peer["address_verifications"],
+            serde_json::json!([
+                { "address": exposed.to_string(), "verification": "verified" },
+                { "address": PEER_ADDRESS, "verification": "unreachable" },
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn stale_peers_are_flagged() {
+        let book = PeerAddressBook::with_ttl(std::time::Duration::ZERO).with_min_survivors(1);
+        let address = PeerAddress::from_str(PEER_ADDRESS).unwrap();
+        let _unused = book
+            .insert("chain-1", address, Vec::new(), PeerMetadata::default(), None)
+            .await
+            .unwrap();
+        let response = diagnostic(book.clone()).await;
+        assert_eq!(response["rootchain"]["peers"]["chain-1"][0]["is_stale"], false);
+
+        // the expired peer is kept as the last survivor of the chain
+        assert!(book.flush().await.is_empty());
+        let response = diagnostic(book).await;
+        assert_eq!(response["rootchain"]["peers"]["chain-1"][0]["is_stale"], true);
+    }
+}
@@ -0,0 +1,170 @@
+use axum::{
+    extract::Extension,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use kallax_primitives::BlockchainComponent;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    chain_health::ChainHealth,
+    component_registry::ComponentRegistry,
+    peer_address_book::PeerAddressBook,
+    rate_limiter::RateLimiter,
+    web::extension::{LeafchainPeerAddressBook, RootchainPeerAddressBook},
+};
+
+#[derive(Serialize, ToSchema)]
+pub struct HealthResponse {
+    rootchain: Vec<ChainHealth>,
+    leafchain: Vec<ChainHealth>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/health",
+    tag = "status",
+    responses((status = 200, description = "Health of every tracked chain", body = HealthResponse))
+)]
+pub async fn get_health(
+    Extension(RootchainPeerAddressBook(rootchain_book)): Extension<RootchainPeerAddressBook>,
+    Extension(LeafchainPeerAddressBook(leafchain_book)): Extension<LeafchainPeerAddressBook>,
+) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(HealthResponse {
+            rootchain: rootchain_book.chain_health().await,
+            leafchain: leafchain_book.chain_health().await,
+        }),
+    )
+}
+
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+type ChainGauge = (&'static str, &'static str, fn(&ChainHealth) -> Option<f64>);
+
+#[allow(clippy::cast_precision_loss)]
+const CHAIN_GAUGES: &[ChainGauge] = &[
+    ("kallax_chain_reporting_nodes", "Nodes which reported a best block number", |h| {
+        Some(h.reporting_nodes as f64)
+    }),
+    ("kallax_chain_syncing_fraction", "Fraction of reporting nodes which are syncing", |h| {
+        Some(h.syncing_fraction)
+    }),
+    ("kallax_chain_lowest_best_block", "Lowest best block number reported", |h| {
+        h.lowest_best_block.map(|n| n as f64)
+    }),
+    ("kallax_chain_highest_best_block", "Highest best block number reported", |h| {
+        h.highest_best_block.map(|n| n as f64)
+    }),
+    ("kallax_chain_height_spread", "Difference between highest and lowest best block", |h| {
+        Some(h.height_spread as f64)
+    }),
+    ("kallax_chain_highest_finalized_block", "Highest finalized block number reported", |h| {
+        h.highest_finalized_block.map(|n| n as f64)
+    }),
+    ("kallax_chain_stuck_nodes", "Nodes whose best block did not change recently", |h| {
+        Some(h.stuck_nodes as f64)
+    }),
+    ("kallax_chain_stalled", "Whether every reporting node is stuck", |h| {
+        Some(f64::from(u8::from(h.is_stalled)))
+    }),
+    ("kallax_chain_possible_fork", "Whether nodes disagree on a finalized block", |h| {
+        Some(f64::from(u8::from(h.possible_fork)))
+    }),
+];
+
+/// Renders the tracker state in the Prometheus text exposition format.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "status",
+    responses(
+        (status = 200, description = "Metrics", body = String, content_type = "text/plain"),
+    )
+)]
+pub async fn get_metrics(
+    Extension(RootchainPeerAddressBook(rootchain_book)): Extension<RootchainPeerAddressBook>,
+    Extension(LeafchainPeerAddressBook(leafchain_book)): Extension<LeafchainPeerAddressBook>,
+    Extension(rate_limiter): Extension<RateLimiter>,
+    Extension(component_registry): Extension<ComponentRegistry>,
+) -> impl IntoResponse {
+    let layers = [("rootchain", &rootchain_book), ("leafchain", &leafchain_book)];
+    let mut lines = Vec::new();
+
+    lines.push("# HELP kallax_tracker_peer_addresses Tracked peer addresses".to_string());
+    lines.push("# TYPE kallax_tracker_peer_addresses gauge".to_string());
+    for (layer, book) in layers {
+        let mut peer_counts = book.peer_counts().await.into_iter().collect::<Vec<_>>();
+        peer_counts.sort_unstable();
+        for (chain_id, count) in peer_counts {
+            lines.push(format!(
+                "kallax_tracker_peer_addresses{{{}}} {count}",
+                labels(layer, &chain_id)
+            ));
+        }
+    }
+
+    lines.push(
+        "# HELP kallax_tracker_key_conflicts Peer IDs registered from several sources".to_string(),
+    );
+    lines.push("# TYPE kallax_tracker_key_conflicts gauge".to_string());
+    for (layer, book) in layers {
+        let mut conflicts = book.key_conflicts().await.into_iter().collect::<Vec<_>>();
+        conflicts.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        for (chain_id, conflicts) in conflicts {
+            lines.push(format!(
+                "kallax_tracker_key_conflicts{{{}}} {}",
+                labels(layer, &chain_id),
+                conflicts.len()
+            ));
+        }
+    }
+
+    lines.push("# HELP kallax_tracker_rate_limited_requests Rejected requests".to_string());
+    lines.push("# TYPE kallax_tracker_rate_limited_requests counter".to_string());
+    lines
+        .push(format!("kallax_tracker_rate_limited_requests {}", rate_limiter.rejected_requests()));
+
+    lines.push("# HELP kallax_tracker_components Live components by kind".to_string());
+    lines.push("# TYPE kallax_tracker_components gauge".to_string());
+    let components = component_registry.list(None, None).await;
+    for (kind, label) in [
+        (BlockchainComponent::Bridge, "bridge"),
+        (BlockchainComponent::OfflineWorker, "offline_worker"),
+    ] {
+        let count = components.iter().filter(|component| component.kind == kind).count();
+        lines.push(format!("kallax_tracker_components{{kind=\"{label}\"}} {count}"));
+    }
+
+    let health = health_per_layer(&layers).await;
+    for (name, help, value) in CHAIN_GAUGES {
+        lines.push(format!("# HELP {name} {help}"));
+        lines.push(format!("# TYPE {name} gauge"));
+        for (layer, chain) in &health {
+            if let Some(value) = value(chain) {
+                lines.push(format!("{name}{{{}}} {value}", labels(layer, &chain.chain_id)));
+            }
+        }
+    }
+
+    lines.push(String::new());
+    (StatusCode::OK, [(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], lines.join("\n"))
+}
+
+async fn health_per_layer(
+    layers: &[(&'static str, &PeerAddressBook)],
+) -> Vec<(&'static str, ChainHealth)> {
+    let mut health = Vec::new();
+    for (layer, book) in layers {
+        health.extend(book.chain_health().await.into_iter().map(|chain| (*layer, chain)));
+    }
+    health
+}
+
+fn labels(layer: &str, chain_id: &str) -> String {
+    let chain_id = chain_id.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+    format!("layer=\"{layer}\",chain_id=\"{chain_id}\"")
+}
@@ -1,66 +1,167 @@
+use std::net::SocketAddr;
+
 use axum::{
-    body,
-    extract::{Extension, Json, Path},
+    extract::{ConnectInfo, Extension},
     headers::ContentType,
     http::StatusCode,
-    response::{IntoResponse, Response},
+    response::Response,
     TypedHeader,
 };
-use kallax_primitives::{ExternalEndpoint, PeerAddress};
+use kallax_primitives::{BlockchainLayer, ExternalEndpoint, NodeRole, PeerAddress, PeerMetadata};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-use crate::web::extension::{LeafchainPeerAddressBook, LeafchainSpecList};
-
-#[derive(Clone, Debug)]
-pub enum GetChainSpecError {
-    NotFound,
-}
-
-impl IntoResponse for GetChainSpecError {
-    fn into_response(self) -> Response {
-        let (status, body) = match self {
-            Self::NotFound => (StatusCode::NOT_FOUND, body::Full::from(String::new())),
-        };
-
-        Response::builder()
-            .status(status)
-            .body(body::boxed(body))
-            .expect("response should always build successfully")
-    }
-}
+use crate::{
+    address_filter::AddressFilter,
+    audit_log::AuditLog,
+    error::InsertPeerError,
+    web::controller::topology::{self, GetTopologyQuery},
+    web::{
+        extension::{LeafchainPeerAddressBook, LeafchainSpecList},
+        extract::{Json, Path, Query},
+        problem::{Problem, ProblemCode},
+    },
+};
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/leafchain/{chain_id}/chain-spec",
+    tag = "leafchain",
+    params(("chain_id" = String, Path, description = "Chain ID, e.g. `thx-mainnet`")),
+    responses(
+        (status = 200, description = "Raw chain spec", body = Object),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn get_chain_spec(
     Extension(LeafchainSpecList(list)): Extension<LeafchainSpecList>,
     Path(chain_id): Path<String>,
-) -> Result<(StatusCode, TypedHeader<ContentType>, Vec<u8>), GetChainSpecError> {
-    list.get(&chain_id).await.map_or(Err(GetChainSpecError::NotFound), |chain_spec| {
-        Ok((StatusCode::OK, TypedHeader(ContentType::json()), chain_spec.as_ref().to_vec()))
-    })
+) -> Result<(StatusCode, TypedHeader<ContentType>, Vec<u8>), Problem> {
+    let chain_spec = list.get(&chain_id).await.ok_or_else(|| {
+        Problem::new(ProblemCode::ChainSpecNotFound, format!("chain spec `{chain_id}` not found"))
+    })?;
+    Ok((StatusCode::OK, TypedHeader(ContentType::json()), chain_spec.as_ref().to_vec()))
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetPeersQuery {
+    pub role: Option<NodeRole>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/leafchain/{chain_id}/peers",
+    tag = "leafchain",
+    params(
+        ("chain_id" = String, Path, description = "Chain ID, e.g. `thx-mainnet`"),
+        GetPeersQuery,
+    ),
+    responses(
+        (status = 200, description = "Peers, boot nodes if no peer is tracked", body = Vec<String>),
+    )
+)]
 pub async fn get_peers(
     Extension(LeafchainPeerAddressBook(book)): Extension<LeafchainPeerAddressBook>,
+    Extension(LeafchainSpecList(list)): Extension<LeafchainSpecList>,
+    Extension(address_filter): Extension<AddressFilter>,
+    ConnectInfo(caller): ConnectInfo<SocketAddr>,
     Path(chain_id): Path<String>,
+    Query(GetPeersQuery { role }): Query<GetPeersQuery>,
 ) -> (StatusCode, Json<Vec<String>>) {
-    (
-        StatusCode::OK,
-        Json(book.fetch_all_peers(chain_id).await.into_iter().map(|a| a.to_string()).collect()),
+    let mut peers =
+        address_filter.served(book.fetch_all_peers(&chain_id, role).await, Some(caller.ip()));
+    if peers.is_empty() {
+        peers = list.boot_nodes(&chain_id).await;
+    }
+    (StatusCode::OK, Json(peers.into_iter().map(|a| a.to_string()).collect()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/leafchain/{chain_id}/topology",
+    tag = "leafchain",
+    params(
+        ("chain_id" = String, Path, description = "Chain ID, e.g. `thx-mainnet`"),
+        GetTopologyQuery,
+    ),
+    responses(
+        (status = 200, content(("application/json" = Topology), ("text/vnd.graphviz" = String))),
+        (status = 404, body = Problem, content_type = "application/problem+json"),
     )
+)]
+pub async fn get_topology(
+    Extension(LeafchainPeerAddressBook(book)): Extension<LeafchainPeerAddressBook>,
+    Path(chain_id): Path<String>,
+    Query(GetTopologyQuery { format }): Query<GetTopologyQuery>,
+) -> Result<Response, Problem> {
+    let topology = book.topology(&chain_id).await.ok_or_else(|| {
+        Problem::new(
+            ProblemCode::ChainNotFound,
+            format!("no peer of chain `{chain_id}` is tracked"),
+        )
+    })?;
+    Ok(topology::render(topology, format))
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct InsertLeafchainPeerAddressRequest {
     pub peer_address: PeerAddress,
-    pub external_endpoint: ExternalEndpoint,
+    /// Superseded by `external_endpoints`, kept for older clients.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_endpoint: Option<ExternalEndpoint>,
+    #[serde(default)]
+    pub external_endpoints: Vec<ExternalEndpoint>,
+    #[serde(default)]
+    pub metadata: PeerMetadata,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/leafchain/{chain_id}/insert",
+    tag = "leafchain",
+    params(("chain_id" = String, Path, description = "Chain ID, e.g. `thx-mainnet`")),
+    request_body = InsertLeafchainPeerAddressRequest,
+    responses(
+        (status = 200, description = "Inserted or skipped", body = String, example = json!("{}")),
+        (status = 400, body = Problem, content_type = "application/problem+json"),
+        (status = 403, body = Problem, content_type = "application/problem+json"),
+        (status = 429, body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn insert_peers(
     Extension(LeafchainPeerAddressBook(book)): Extension<LeafchainPeerAddressBook>,
+    Extension(audit_log): Extension<AuditLog>,
+    Extension(address_filter): Extension<AddressFilter>,
+    ConnectInfo(caller): ConnectInfo<SocketAddr>,
     Path(chain_id): Path<String>,
     Json(payload): Json<InsertLeafchainPeerAddressRequest>,
-) -> (StatusCode, Json<String>) {
-    let InsertLeafchainPeerAddressRequest { peer_address, external_endpoint } = payload;
+) -> Result<(StatusCode, Json<String>), InsertPeerError> {
+    let InsertLeafchainPeerAddressRequest {
+        peer_address,
+        external_endpoint,
+        external_endpoints,
+        metadata,
+    } = payload;
+    if !address_filter.accepts(&peer_address) {
+        tracing::info!(
+            "New peer `{peer_address}` is in {} network, skip to insert to chain `{chain_id}`",
+            peer_address.scope()
+        );
+        return Ok((StatusCode::OK, Json("{}".to_string())));
+    }
     tracing::info!("Insert new peer `{peer_address}` to chain `{chain_id}`");
-    book.insert(chain_id, peer_address, Some(external_endpoint)).await;
-    (StatusCode::OK, Json("{}".to_string()))
+    let outcome = book
+        .insert(
+            &chain_id,
+            peer_address.clone(),
+            external_endpoint.into_iter().chain(external_endpoints).collect(),
+            metadata,
+            Some(caller.ip()),
+        )
+        .await?;
+    audit_log
+        .record_insert(BlockchainLayer::Leafchain, Some(caller), &chain_id, &peer_address, &outcome)
+        .await;
+    Ok((StatusCode::OK, Json("{}".to_string())))
 }
@@ -1,15 +1,288 @@
+pub mod admin;
+pub mod chain;
+mod component;
 mod diagnostic;
+mod health;
 pub mod leafchain;
 pub mod rootchain;
+mod topology;
 
-use axum::{routing, Router};
+use axum::{
+    handler::Handler,
+    http::Method,
+    routing::{self, MethodFilter, MethodRouter},
+    Json, Router,
+};
+use kallax_primitives::{
+    BlockchainComponent, BlockchainLayer, ExternalEndpoint, NodeRole, PeerAddress, PeerMetadata,
+    ServedChain, SyncState, Transport,
+};
+use utoipa::OpenApi;
 
-pub fn api_v1_router() -> Router {
-    Router::new().nest(
+use crate::{
+    chain_health::ChainHealth,
+    component_registry::Component,
+    peer_address_book::DiagnosticPeer,
+    peer_verifier::{AddressVerification, Verification},
+    topology::{Topology, TopologyEdge, TopologyNode},
+    web::problem::{Problem, ProblemCode},
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Kallax Tracker API"),
+    paths(
+        self::component::get_components,
+        self::diagnostic::get_diagnostic,
+        self::health::get_health,
+        self::health::get_metrics,
+        get_openapi
+    ),
+    components(schemas(
+        AddressVerification,
+        BlockchainComponent,
+        BlockchainLayer,
+        ChainHealth,
+        Component,
+        DiagnosticPeer,
+        ExternalEndpoint,
+        NodeRole,
+        PeerAddress,
+        PeerMetadata,
+        Problem,
+        ProblemCode,
+        ServedChain,
+        SyncState,
+        Topology,
+        TopologyEdge,
+        TopologyNode,
+        Transport,
+        Verification,
+        self::diagnostic::ChainSummary,
+        self::diagnostic::DiagnosticResponse,
+        self::health::HealthResponse,
+        self::topology::TopologyFormat
+    ))
+)]
+struct ApiDoc;
+
+/// OpenAPI document of every route of [`api_v1_router`].
+#[must_use]
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+    doc.merge(self::rootchain::V1ApiDoc::openapi());
+    doc.merge(self::leafchain::V1ApiDoc::openapi());
+    doc.merge(self::chain::V2ApiDoc::openapi());
+    doc.merge(self::admin::V1ApiDoc::openapi());
+    doc
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/openapi.json",
+    tag = "status",
+    responses((status = 200, description = "OpenAPI document of the API", body = Object))
+)]
+#[allow(clippy::unused_async)]
+async fn get_openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(openapi())
+}
+
+/// Route of the REST API, see [`routes`].
+pub struct Route {
+    method: Method,
+
+    path: String,
+
+    handler: MethodRouter,
+}
+
+impl Route {
+    fn new<H, T>(method: Method, path: &str, handler: H) -> Self
+    where
+        H: Handler<T, ()>,
+        T: 'static,
+    {
+        let filter = MethodFilter::try_from(method.clone()).expect("routes use standard methods");
+        Self { method, path: path.to_string(), handler: routing::on(filter, handler) }
+    }
+}
+
+/// Prefixes the path of every route with `prefix`.
+fn nest(prefix: &str, routes: Vec<Route>) -> Vec<Route> {
+    routes
+        .into_iter()
+        .map(|route| Route { path: format!("{prefix}{}", route.path), ..route })
+        .collect()
+}
+
+/// Every route of the REST API. [`api_v1_router`] mounts this table and the
+/// OpenAPI document is tested against it.
+fn routes() -> Vec<Route> {
+    let mut routes = nest(
         "/api",
-        Router::new()
-            .merge(self::rootchain::v1())
-            .merge(self::leafchain::v1())
-            .route("/v1/diagnostic", routing::get(self::diagnostic::get_diagnostic)),
-    )
+        [
+            self::rootchain::v1(),
+            self::leafchain::v1(),
+            self::chain::v2(),
+            self::admin::v1(),
+            vec![
+                Route::new(Method::GET, "/v2/components", self::component::get_components),
+                Route::new(Method::GET, "/v1/diagnostic", self::diagnostic::get_diagnostic),
+                Route::new(Method::GET, "/v1/health", self::health::get_health),
+                Route::new(Method::GET, "/openapi.json", get_openapi),
+            ],
+        ]
+        .into_iter()
+        .flatten()
+        .collect(),
+    );
+    routes.push(Route::new(Method::GET, "/metrics", self::health::get_metrics));
+    routes
+}
+
+pub fn api_v1_router() -> Router {
+    routes()
+        .into_iter()
+        .fold(Router::new(), |router, Route { path, handler, .. }| router.route(&path, handler))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, net::SocketAddr, sync::Arc};
+
+    use axum::{
+        body::Body,
+        extract::ConnectInfo,
+        http::{header, Method, Request, StatusCode},
+    };
+    use tower::ServiceExt;
+    use utoipa::openapi::PathItemType;
+
+    use super::{api_v1_router, openapi, routes, Route};
+    use crate::web::{
+        extension::AdminToken,
+        problem::{Problem, ProblemCode, PROBLEM_CONTENT_TYPE},
+    };
+
+    const ADMIN_TOKEN: &str = "token";
+
+    const METHODS: [(PathItemType, Method); 4] = [
+        (PathItemType::Get, Method::GET),
+        (PathItemType::Post, Method::POST),
+        (PathItemType::Put, Method::PUT),
+        (PathItemType::Delete, Method::DELETE),
+    ];
+
+    /// Converts the path parameters of axum, `/:name`, to the ones of OpenAPI,
+    /// `/{name}`.
+    fn openapi_path(path: &str) -> String {
+        path.split('/')
+            .map(|segment| {
+                segment
+                    .strip_prefix(':')
+                    .map_or_else(|| segment.to_string(), |name| format!("{{{name}}}"))
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn fill_path_parameters(path: &str) -> String {
+        path.replace("{layer}", "rootchain")
+            .replace("{chain_id}", "thx-mainnet")
+            .replace("{rule_id}", "1")
+            .replace("{peer_id}", "12D3KooWBmAwcd4PJNJvfV89HwE48nwkRmAgo8Vy3uQEyNNHBox2")
+    }
+
+    /// Sends a request without any extension but the admin token, a routed
+    /// request fails in its extractors rather than falling through the
+    /// router.
+    async fn send(method: Method, uri: &str) -> (StatusCode, Option<ProblemCode>) {
+        let router = api_v1_router()
+            .layer(axum::Extension(AdminToken(Some(Arc::from(ADMIN_TOKEN)))))
+            .fallback(crate::api_fallback);
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {ADMIN_TOKEN}"))
+            .body(Body::empty())
+            .unwrap();
+        let _ = request.extensions_mut().insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))));
+
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let is_problem = response.headers().get(header::CONTENT_TYPE)
+            == Some(&header::HeaderValue::from_static(PROBLEM_CONTENT_TYPE));
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let code = is_problem
+            .then(|| serde_json::from_slice::<Problem>(&body).map(|problem| problem.code).ok())
+            .flatten();
+        (status, code)
+    }
+
+    #[test]
+    fn document_matches_routes() {
+        let routed = routes()
+            .into_iter()
+            .map(|Route { method, path, .. }| (method.to_string(), openapi_path(&path)))
+            .collect::<BTreeSet<_>>();
+        let documented = openapi()
+            .paths
+            .paths
+            .iter()
+            .flat_map(|(path, item)| {
+                METHODS
+                    .into_iter()
+                    .filter(|(item_type, _)| item.operations.contains_key(item_type))
+                    .map(|(_, method)| (method.to_string(), path.clone()))
+            })
+            .collect::<BTreeSet<_>>();
+
+        assert!(!routed.is_empty());
+        let undocumented = routed.difference(&documented).collect::<Vec<_>>();
+        assert!(undocumented.is_empty(), "routed but not documented: {undocumented:?}");
+        let unrouted = documented.difference(&routed).collect::<Vec<_>>();
+        assert!(unrouted.is_empty(), "documented but not routed: {unrouted:?}");
+    }
+
+    #[tokio::test]
+    async fn router_serves_routes() {
+        let routed = routes()
+            .into_iter()
+            .map(|Route { method, path, .. }| (method, path))
+            .collect::<Vec<_>>();
+        let paths = routed.iter().map(|(_, path)| path).collect::<BTreeSet<_>>();
+
+        for path in paths {
+            let uri = fill_path_parameters(&openapi_path(path));
+            for (_, method) in METHODS {
+                let (status, code) = send(method.clone(), &uri).await;
+                if routed.contains(&(method.clone(), path.clone())) {
+                    assert_ne!(status, StatusCode::METHOD_NOT_ALLOWED, "{method} {path}");
+                    assert_ne!(code, Some(ProblemCode::NotFound), "{method} {path}");
+                } else {
+                    assert!(
+                        status == StatusCode::METHOD_NOT_ALLOWED
+                            || code == Some(ProblemCode::NotFound),
+                        "{method} {path} is served but not in the route table"
+                    );
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn document_is_served() {
+        let response = api_v1_router()
+            .oneshot(Request::get("/api/openapi.json").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let doc: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(doc, serde_json::to_value(openapi()).unwrap());
+        assert!(doc["paths"]["/api/v1/rootchain/{chain_id}/insert"]["post"].is_object());
+        assert!(doc["components"]["schemas"]["InsertRootchainPeerAddressRequest"].is_object());
+    }
 }
@@ -1,15 +1,32 @@
 mod v1;
 
-use axum::{routing, Router};
+use axum::http::Method;
+use utoipa::OpenApi;
+
+use crate::web::controller::{nest, Route};
 
 pub use self::v1::InsertRootchainPeerAddressRequest;
 
-pub fn v1() -> Router {
-    Router::new().nest(
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        self::v1::get_chain_spec,
+        self::v1::get_peers,
+        self::v1::get_topology,
+        self::v1::insert_peers
+    ),
+    components(schemas(InsertRootchainPeerAddressRequest))
+)]
+pub struct V1ApiDoc;
+
+pub fn v1() -> Vec<Route> {
+    nest(
         "/v1/rootchain",
-        Router::new()
-            .route("/:chain_id/chain-spec", routing::get(self::v1::get_chain_spec))
-            .route("/:chain_id/peers", routing::get(self::v1::get_peers))
-            .route("/:chain_id/insert", routing::post(self::v1::insert_peers)),
+        vec![
+            Route::new(Method::GET, "/:chain_id/chain-spec", self::v1::get_chain_spec),
+            Route::new(Method::GET, "/:chain_id/peers", self::v1::get_peers),
+            Route::new(Method::GET, "/:chain_id/topology", self::v1::get_topology),
+            Route::new(Method::POST, "/:chain_id/insert", self::v1::insert_peers),
+        ],
     )
 }
@@ -0,0 +1,35 @@
+use axum::{
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::topology::Topology;
+
+const GRAPHVIZ_CONTENT_TYPE: &str = "text/vnd.graphviz; charset=utf-8";
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TopologyFormat {
+    #[default]
+    Json,
+    Dot,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetTopologyQuery {
+    #[serde(default)]
+    pub format: TopologyFormat,
+}
+
+pub fn render(topology: Topology, format: TopologyFormat) -> Response {
+    match format {
+        TopologyFormat::Json => Json(topology).into_response(),
+        TopologyFormat::Dot => {
+            ([(header::CONTENT_TYPE, GRAPHVIZ_CONTENT_TYPE)], topology.to_dot()).into_response()
+        }
+    }
+}
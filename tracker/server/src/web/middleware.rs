@@ -0,0 +1,132 @@
+use std::net::SocketAddr;
+
+use axum::{
+    extract::{ConnectInfo, Extension, State},
+    headers::{authorization::Bearer, Authorization},
+    http::{header, HeaderValue, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    TypedHeader,
+};
+use subtle::ConstantTimeEq;
+
+use crate::{
+    rate_limiter::RateLimiter,
+    web::{
+        extension::AdminToken,
+        problem::{Problem, ProblemCode},
+    },
+};
+
+pub async fn rate_limit<B>(
+    State(rate_limiter): State<RateLimiter>,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    if rate_limiter.check(remote_addr.ip()) {
+        next.run(req).await
+    } else {
+        tracing::debug!("Reject request from `{remote_addr}`, rate limit exceeded");
+        let mut response = Problem::new(
+            ProblemCode::RateLimited,
+            format!(
+                "rate limit of {} requests per second exceeded",
+                rate_limiter.requests_per_second()
+            ),
+        )
+        .into_response();
+        // `Retry-After` is in whole seconds
+        let retry_delay = rate_limiter.retry_delay();
+        let retry_after = retry_delay.as_secs() + u64::from(retry_delay.subsec_nanos() > 0);
+        let _ = response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        response
+    }
+}
+
+/// Rejects requests without the configured admin bearer token, the admin API
+/// is disabled entirely if no token is configured. The token is compared in
+/// constant time.
+pub async fn require_admin_token<B>(
+    Extension(AdminToken(admin_token)): Extension<AdminToken>,
+    authorization: Option<TypedHeader<Authorization<Bearer>>>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let Some(admin_token) = admin_token else {
+        return Problem::from(ProblemCode::AdminApiDisabled).into_response();
+    };
+
+    match authorization {
+        Some(TypedHeader(Authorization(bearer)))
+            if bool::from(bearer.token().as_bytes().ct_eq(admin_token.as_bytes())) =>
+        {
+            next.run(req).await
+        }
+        _ => Problem::from(ProblemCode::Unauthorized).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use std::net::SocketAddr;
+
+    use axum::{
+        body::Body,
+        extract::ConnectInfo,
+        http::{header, Request, StatusCode},
+        middleware, routing, Extension, Router,
+    };
+    use tower::ServiceExt;
+
+    use super::{rate_limit, require_admin_token};
+    use crate::{rate_limiter::RateLimiter, web::extension::AdminToken};
+
+    async fn status(admin_token: Option<&str>, authorization: Option<&str>) -> StatusCode {
+        let router = Router::new()
+            .route("/", routing::get(|| async {}))
+            .route_layer(middleware::from_fn(require_admin_token))
+            .layer(Extension(AdminToken(admin_token.map(Arc::from))));
+        let mut request = Request::get("/");
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+        router.oneshot(request.body(Body::empty()).unwrap()).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn admin_token_must_match_exactly() {
+        assert_eq!(status(Some("secret"), Some("Bearer secret")).await, StatusCode::OK);
+
+        for authorization in [None, Some("Bearer secre"), Some("Bearer secrets"), Some("Bearer ")] {
+            assert_eq!(
+                status(Some("secret"), authorization).await,
+                StatusCode::UNAUTHORIZED,
+                "{authorization:?}"
+            );
+        }
+
+        assert_eq!(status(None, Some("Bearer secret")).await, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_requests_carry_retry_after() {
+        let router = Router::new()
+            .route("/", routing::get(|| async {}))
+            .route_layer(middleware::from_fn_with_state(RateLimiter::new(2, 1), rate_limit));
+        let request = || {
+            let mut request = Request::get("/").body(Body::empty()).unwrap();
+            let _ = request
+                .extensions_mut()
+                .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 30333))));
+            request
+        };
+
+        assert_eq!(router.clone().oneshot(request()).await.unwrap().status(), StatusCode::OK);
+        let response = router.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+    }
+}
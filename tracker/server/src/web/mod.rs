@@ -1,9 +1,15 @@
 pub mod controller;
+pub mod extract;
+pub mod middleware;
+pub mod problem;
 
 pub mod extension {
+    use std::sync::Arc;
+
+    use kallax_primitives::AddressPolicy;
     use serde::Serialize;
 
-    use crate::{ChainSpecList, PeerAddressBook};
+    use crate::{peer_address_book::Capacity, ChainSpecList, PeerAddressBook, VerificationMode};
 
     #[derive(Clone, Debug)]
     pub struct RootchainPeerAddressBook(pub PeerAddressBook);
@@ -20,8 +26,22 @@ pub mod extension {
     #[derive(Clone, Debug, Serialize)]
     pub struct TrackerConfig {
         pub peer_time_to_live_seconds: u64,
+        pub tombstone_time_to_live_seconds: u64,
+        pub min_surviving_peers_per_chain: usize,
+        pub rate_limit_per_second: u32,
+        pub rate_limit_burst: u32,
+        pub capacity: Capacity,
+        pub peer_verification_mode: VerificationMode,
+        pub accepted_address_scopes: AddressPolicy,
+        pub scope_addresses_by_caller: bool,
+        pub record_observed_addresses: bool,
+        pub chain_stall_threshold_seconds: u64,
     }
 
+    /// Bearer token guarding the admin API, `None` disables the admin API.
+    #[derive(Clone, Debug)]
+    pub struct AdminToken(pub Option<Arc<str>>);
+
     #[derive(Clone, Debug)]
     pub struct TrackerStartTime(pub std::time::Instant);
 }
@@ -0,0 +1,219 @@
+use std::fmt;
+
+use axum::{
+    extract::rejection::{JsonRejection, PathRejection, QueryRejection},
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Content type of [`Problem`], defined by RFC 7807.
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Stable code of a failed request of the REST API, carried by [`Problem`].
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemCode {
+    /// No route matches the request.
+    NotFound,
+
+    ChainSpecNotFound,
+
+    /// No peer of the chain is tracked.
+    ChainNotFound,
+
+    PolicyRuleNotFound,
+
+    /// The peer is not draining.
+    DrainNotFound,
+
+    InvalidPath,
+
+    InvalidQuery,
+
+    InvalidBody,
+
+    InvalidArgument,
+
+    RejectedByPolicy,
+
+    CapacityExceeded,
+
+    RateLimited,
+
+    AdminApiDisabled,
+
+    Unauthorized,
+
+    Internal,
+
+    /// A code unknown to this version, sent by a newer tracker.
+    #[serde(other)]
+    Unknown,
+}
+
+impl ProblemCode {
+    #[must_use]
+    pub const fn status(self) -> StatusCode {
+        match self {
+            Self::NotFound
+            | Self::ChainSpecNotFound
+            | Self::ChainNotFound
+            | Self::PolicyRuleNotFound
+            | Self::DrainNotFound
+            | Self::AdminApiDisabled => StatusCode::NOT_FOUND,
+            Self::InvalidPath | Self::InvalidQuery | Self::InvalidBody | Self::InvalidArgument => {
+                StatusCode::BAD_REQUEST
+            }
+            Self::RejectedByPolicy => StatusCode::FORBIDDEN,
+            Self::CapacityExceeded | Self::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::Internal | Self::Unknown => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    #[must_use]
+    pub const fn title(self) -> &'static str {
+        match self {
+            Self::NotFound => "Resource not found",
+            Self::ChainSpecNotFound => "Chain spec not found",
+            Self::ChainNotFound => "Chain not found",
+            Self::PolicyRuleNotFound => "Peer policy rule not found",
+            Self::DrainNotFound => "Peer is not draining",
+            Self::InvalidPath => "Invalid path parameter",
+            Self::InvalidQuery => "Invalid query string",
+            Self::InvalidBody => "Invalid request body",
+            Self::InvalidArgument => "Invalid argument",
+            Self::RejectedByPolicy => "Rejected by peer policy",
+            Self::CapacityExceeded => "Capacity exceeded",
+            Self::RateLimited => "Rate limit exceeded",
+            Self::AdminApiDisabled => "Admin API is disabled",
+            Self::Unauthorized => "Invalid admin token",
+            Self::Internal => "Internal error",
+            Self::Unknown => "Unknown error",
+        }
+    }
+}
+
+/// Problem details of RFC 7807, the body of every error response of the REST
+/// API.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    #[schema(example = "urn:kallax:tracker:problem:chain_spec_not_found")]
+    pub type_uri: String,
+
+    pub title: String,
+
+    pub status: u16,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+
+    pub code: ProblemCode,
+}
+
+impl Problem {
+    #[must_use]
+    pub fn new(code: ProblemCode, detail: impl fmt::Display) -> Self {
+        Self { detail: Some(detail.to_string()), ..Self::from(code) }
+    }
+}
+
+impl From<ProblemCode> for Problem {
+    fn from(code: ProblemCode) -> Self {
+        let code_name = serde_json::to_value(code)
+            .ok()
+            .and_then(|value| value.as_str().map(ToString::to_string))
+            .unwrap_or_default();
+        Self {
+            type_uri: format!("urn:kallax:tracker:problem:{code_name}"),
+            title: code.title().to_string(),
+            status: code.status().as_u16(),
+            detail: None,
+            code,
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.detail {
+            Some(detail) => write!(f, "{} ({}): {detail}", self.title, self.status),
+            None => write!(f, "{} ({})", self.title, self.status),
+        }
+    }
+}
+
+impl std::error::Error for Problem {}
+
+impl IntoResponse for Problem {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (status, axum::Json(self)).into_response();
+        let _ = response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_CONTENT_TYPE));
+        response
+    }
+}
+
+impl From<JsonRejection> for Problem {
+    fn from(rejection: JsonRejection) -> Self {
+        Self::new(ProblemCode::InvalidBody, rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for Problem {
+    fn from(rejection: PathRejection) -> Self {
+        Self::new(ProblemCode::InvalidPath, rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for Problem {
+    fn from(rejection: QueryRejection) -> Self {
+        Self::new(ProblemCode::InvalidQuery, rejection.body_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::response::IntoResponse;
+
+    use super::{Problem, ProblemCode, PROBLEM_CONTENT_TYPE};
+
+    #[test]
+    fn problem_is_serialized_with_stable_code() {
+        let problem = Problem::new(ProblemCode::ChainSpecNotFound, "chain spec `thx` not found");
+
+        assert_eq!(
+            serde_json::to_value(&problem).unwrap(),
+            serde_json::json!({
+                "type": "urn:kallax:tracker:problem:chain_spec_not_found",
+                "title": "Chain spec not found",
+                "status": 404,
+                "detail": "chain spec `thx` not found",
+                "code": "chain_spec_not_found",
+            })
+        );
+
+        let response = problem.into_response();
+        assert_eq!(response.status(), 404);
+        assert_eq!(response.headers()["content-type"], PROBLEM_CONTENT_TYPE);
+    }
+
+    #[test]
+    fn unknown_code_is_deserialized() {
+        let problem: Problem = serde_json::from_value(serde_json::json!({
+            "type": "urn:kallax:tracker:problem:from_the_future",
+            "title": "From the future",
+            "status": 418,
+            "code": "from_the_future",
+        }))
+        .unwrap();
+
+        assert_eq!(problem.code, ProblemCode::Unknown);
+        assert_eq!(problem.detail, None);
+    }
+}